@@ -11,19 +11,45 @@ pub use rust::RustAllocator;
 pub type RawMemPtr = *mut u8;
 
 /// The allocator interface
+///
+/// All memory used by a runtime created with
+/// [`Runtime::new_with_alloc`](crate::Runtime::new_with_alloc) is requested through this trait.
+///
+/// The methods are called from within the quickjs engine while the runtime is locked, an
+/// implementation must therefore never call back into the runtime it is allocating for, for
+/// example by creating or dropping javascript values. Allocation can happen at any point during
+/// the execution of javascript, so implementations should also avoid panicking.
 #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "allocator")))]
 pub trait Allocator {
     /// Allocate new memory
+    ///
+    /// Should return a pointer to a memory region of at least `size` bytes aligned to 8 bytes or
+    /// a null pointer if the allocation failed.
     fn alloc(&mut self, size: usize) -> RawMemPtr;
 
     /// De-allocate previously allocated memory
-    fn dealloc(&mut self, ptr: RawMemPtr);
+    ///
+    /// # Safety
+    /// `ptr` must have been returned by [`Allocator::alloc`] or [`Allocator::realloc`] of this
+    /// allocator and must not have been de-allocated already.
+    unsafe fn dealloc(&mut self, ptr: RawMemPtr);
 
     /// Re-allocate previously allocated memory
-    fn realloc(&mut self, ptr: RawMemPtr, new_size: usize) -> RawMemPtr;
+    ///
+    /// Should return a pointer to a memory region of at least `new_size` bytes which contains
+    /// the data of the previous region or a null pointer if the allocation failed, in which case
+    /// the previous region must remain valid.
+    ///
+    /// # Safety
+    /// `ptr` must have been returned by [`Allocator::alloc`] or [`Allocator::realloc`] of this
+    /// allocator and must not have been de-allocated already.
+    unsafe fn realloc(&mut self, ptr: RawMemPtr, new_size: usize) -> RawMemPtr;
 
     /// Get usable size of allocated memory region
-    fn usable_size(ptr: RawMemPtr) -> usize
+    ///
+    /// # Safety
+    /// `ptr` must point to a live memory region allocated by this type of allocator.
+    unsafe fn usable_size(ptr: RawMemPtr) -> usize
     where
         Self: Sized;
 }
@@ -124,3 +150,71 @@ impl AllocatorHolder {
         A::usable_size(ptr as _).try_into().unwrap()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{Allocator, RawMemPtr, RustAllocator};
+    use crate::{Context, Runtime};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    struct CountingAllocator {
+        inner: RustAllocator,
+        allocs: Arc<AtomicUsize>,
+        live: Arc<AtomicUsize>,
+    }
+
+    impl Allocator for CountingAllocator {
+        fn alloc(&mut self, size: usize) -> RawMemPtr {
+            let ptr = self.inner.alloc(size);
+            if !ptr.is_null() {
+                self.allocs.fetch_add(1, Ordering::Relaxed);
+                self.live.fetch_add(1, Ordering::Relaxed);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&mut self, ptr: RawMemPtr) {
+            self.live.fetch_sub(1, Ordering::Relaxed);
+            self.inner.dealloc(ptr)
+        }
+
+        unsafe fn realloc(&mut self, ptr: RawMemPtr, new_size: usize) -> RawMemPtr {
+            self.allocs.fetch_add(1, Ordering::Relaxed);
+            self.inner.realloc(ptr, new_size)
+        }
+
+        unsafe fn usable_size(ptr: RawMemPtr) -> usize {
+            RustAllocator::usable_size(ptr)
+        }
+    }
+
+    #[test]
+    fn counting_allocator() {
+        let allocs = Arc::new(AtomicUsize::new(0));
+        let live = Arc::new(AtomicUsize::new(0));
+        let allocator = CountingAllocator {
+            inner: RustAllocator,
+            allocs: allocs.clone(),
+            live: live.clone(),
+        };
+
+        {
+            let rt = Runtime::new_with_alloc(allocator).unwrap();
+            let ctx = Context::full(&rt).unwrap();
+            let before = allocs.load(Ordering::Relaxed);
+            assert!(before > 0);
+
+            ctx.with(|ctx| {
+                let res: usize = ctx
+                    .eval("new Array(1000).fill(0).map((_, i) => ({ i })).length")
+                    .unwrap();
+                assert_eq!(res, 1000);
+            });
+            assert!(allocs.load(Ordering::Relaxed) > before);
+        }
+        assert_eq!(live.load(Ordering::Relaxed), 0);
+    }
+}
@@ -60,47 +60,44 @@ impl Allocator for RustAllocator {
         unsafe { ptr.add(HEADER_SIZE) }
     }
 
-    #[allow(clippy::not_unsafe_ptr_arg_deref)]
-    fn dealloc(&mut self, ptr: RawMemPtr) {
-        let ptr = unsafe { ptr.sub(HEADER_SIZE) };
+    unsafe fn dealloc(&mut self, ptr: RawMemPtr) {
+        let ptr = ptr.sub(HEADER_SIZE);
         let alloc_size = {
-            let header = unsafe { &*(ptr as *const Header) };
+            let header = &*(ptr as *const Header);
             header.size + HEADER_SIZE
         };
-        let layout = unsafe { Layout::from_size_align_unchecked(alloc_size, ALLOC_ALIGN) };
+        let layout = Layout::from_size_align_unchecked(alloc_size, ALLOC_ALIGN);
 
-        unsafe { dealloc(ptr, layout) };
+        dealloc(ptr, layout);
     }
 
-    #[allow(clippy::not_unsafe_ptr_arg_deref)]
-    fn realloc(&mut self, ptr: RawMemPtr, new_size: usize) -> RawMemPtr {
+    unsafe fn realloc(&mut self, ptr: RawMemPtr, new_size: usize) -> RawMemPtr {
         let new_size = round_size(new_size);
-        let ptr = unsafe { ptr.sub(HEADER_SIZE) };
+        let ptr = ptr.sub(HEADER_SIZE);
         let alloc_size = {
-            let header = unsafe { &*(ptr as *const Header) };
+            let header = &*(ptr as *const Header);
             header.size + HEADER_SIZE
         };
-        let layout = unsafe { Layout::from_size_align_unchecked(alloc_size, ALLOC_ALIGN) };
+        let layout = Layout::from_size_align_unchecked(alloc_size, ALLOC_ALIGN);
 
         let new_alloc_size = new_size + HEADER_SIZE;
 
-        let ptr = unsafe { realloc(ptr, layout, new_alloc_size) };
+        let ptr = realloc(ptr, layout, new_alloc_size);
 
         if ptr.is_null() {
             return null_mut();
         }
         {
-            let header = unsafe { &mut *(ptr as *mut Header) };
+            let header = &mut *(ptr as *mut Header);
             header.size = new_size;
         }
 
-        unsafe { ptr.add(HEADER_SIZE) }
+        ptr.add(HEADER_SIZE)
     }
 
-    #[allow(clippy::not_unsafe_ptr_arg_deref)]
-    fn usable_size(ptr: RawMemPtr) -> usize {
-        let ptr = unsafe { ptr.sub(HEADER_SIZE) };
-        let header = unsafe { &*(ptr as *const Header) };
+    unsafe fn usable_size(ptr: RawMemPtr) -> usize {
+        let ptr = ptr.sub(HEADER_SIZE);
+        let header = &*(ptr as *const Header);
         header.size
     }
 }
@@ -7,6 +7,7 @@ use crate::{
 use std::{
     ffi::CString,
     marker::PhantomData,
+    mem,
     ops::Deref,
     ptr::{self, NonNull},
 };
@@ -19,6 +20,10 @@ pub use cell::{
     Borrow, BorrowMut, JsCell, Mutability, OwnedBorrow, OwnedBorrowMut, Readable, Writable,
 };
 mod ffi;
+mod iterable;
+#[cfg(feature = "futures")]
+pub use iterable::AsyncIterable;
+pub use iterable::Iterable;
 mod trace;
 use rquickjs_sys::JS_VALUE_GET_TAG;
 pub use trace::{Trace, Tracer};
@@ -50,6 +55,15 @@ pub trait JsClass<'js>: Trace<'js> {
     }
 }
 
+/// A rust class which extends the rust class `B`, see [`Class::extends`].
+///
+/// Instances of the class can be used as instances of `B`, so methods of `B` which borrow its rust
+/// value also work on them.
+///
+/// # Safety
+/// The type must be `#[repr(C)]` and have a value of `B` as its first field.
+pub unsafe trait Extends<'js, B: JsClass<'js>>: JsClass<'js, Mutable = B::Mutable> {}
+
 /// A object which is instance of a rust class.
 #[repr(transparent)]
 pub struct Class<'js, C: JsClass<'js>>(pub(crate) Object<'js>, PhantomData<C>);
@@ -152,6 +166,53 @@ impl<'js, C: JsClass<'js>> Class<'js, C> {
         Ok(())
     }
 
+    /// Make instances of this class inherit from the class `B`.
+    ///
+    /// Sets the `[[Prototype]]` of the prototype of this class to the prototype of `B`, so methods
+    /// not found on this class are looked up on the base class and instances of this class are
+    /// `instanceof` the constructor of `B`. Instances of this class can also be converted into a
+    /// [`Class`] of `B`, which borrows the `B` the rust value starts with, so methods of the base
+    /// class work on them.
+    ///
+    /// Class prototypes are stored per context, so both classes are registered on the given
+    /// context and the inheritance only applies to that context. This should be called on every
+    /// context in which the classes are used.
+    pub fn extends<B: JsClass<'js>>(ctx: &Ctx<'js>) -> Result<()>
+    where
+        C: Extends<'js, B>,
+    {
+        // The value of a `Writable` cell follows the borrow count, so it only starts at the same
+        // offset when the types are aligned alike.
+        if mem::align_of::<C>().max(mem::align_of::<usize>())
+            != mem::align_of::<B>().max(mem::align_of::<usize>())
+        {
+            return Err(Error::new_into_js_message(
+                C::NAME,
+                B::NAME,
+                "the class must have the same alignment as the class it extends",
+            ));
+        }
+        Self::register(ctx)?;
+        Class::<B>::register(ctx)?;
+        let (Some(proto), Some(base)) = (
+            Self::prototype(ctx.clone()),
+            Class::<B>::prototype(ctx.clone()),
+        ) else {
+            return Err(Error::new_into_js_message(
+                C::NAME,
+                B::NAME,
+                "both classes must have a prototype to inherit",
+            ));
+        };
+        proto.set_prototype(Some(&base))?;
+        unsafe {
+            (*ctx.get_opaque())
+                .class_bases
+                .insert(C::class_id().get(), B::class_id().get());
+        }
+        Ok(())
+    }
+
     /// Returns if the class is registered in the runtime.
     #[inline]
     pub fn is_registered(ctx: &Ctx<'js>) -> bool {
@@ -215,7 +276,8 @@ impl<'js, C: JsClass<'js>> Class<'js, C> {
     /// of a class object as a `Rc<RefCell<C>>` and with similar borrowing functionality.
     ///
     /// # Panic
-    /// This function panics if the class is already borrowed mutably
+    /// This function panics if the class is already borrowed mutably. Use [`Class::try_borrow`]
+    /// when javascript code can re-enter rust while the class is borrowed.
     #[inline]
     pub fn borrow<'a>(&'a self) -> Borrow<'a, 'js, C> {
         self.get_cell().borrow()
@@ -239,10 +301,16 @@ impl<'js, C: JsClass<'js>> Class<'js, C> {
     /// Javascript classes behave similar to [`Rc`](std::rc::Rc) in rust, you can essentially think
     /// of a class object as a `Rc<RefCell<C>>` and with similar borrowing functionality.
     ///
-    /// This returns an error when the class is already borrowed mutably.
+    /// This returns an [`Error::BorrowConflict`] when the class is already borrowed mutably, which
+    /// is thrown as a javascript exception when returned from a rust callback.
     #[inline]
     pub fn try_borrow<'a>(&'a self) -> Result<Borrow<'a, 'js, C>> {
-        self.get_cell().try_borrow().map_err(Error::ClassBorrow)
+        self.get_cell()
+            .try_borrow()
+            .map_err(|error| Error::BorrowConflict {
+                class: C::NAME,
+                error,
+            })
     }
 
     /// Try to borrow the rust class type mutably.
@@ -250,24 +318,25 @@ impl<'js, C: JsClass<'js>> Class<'js, C> {
     /// Javascript classes behave similar to [`Rc`](std::rc::Rc) in rust, you can essentially think
     /// of a class object as a `Rc<RefCell<C>>` and with similar borrowing functionality.
     ///
-    /// This returns an error when the class is already borrowed mutably, immutably or the class
-    /// can't be borrowed mutably.
+    /// This returns an [`Error::BorrowConflict`] when the class is already borrowed mutably,
+    /// immutably or the class can't be borrowed mutably.
     #[inline]
     pub fn try_borrow_mut<'a>(&'a self) -> Result<BorrowMut<'a, 'js, C>> {
-        self.get_cell().try_borrow_mut().map_err(Error::ClassBorrow)
+        self.get_cell()
+            .try_borrow_mut()
+            .map_err(|error| Error::BorrowConflict {
+                class: C::NAME,
+                error,
+            })
     }
 
     /// returns a pointer to the class object.
     #[inline]
     pub(crate) fn get_class_ptr(&self) -> NonNull<JsCell<'js, C>> {
-        let ptr = unsafe {
-            qjs::JS_GetOpaque2(
-                self.0.ctx.as_ptr(),
-                self.0 .0.as_js_value(),
-                C::class_id().get(),
-            )
-        };
-        NonNull::new(ptr.cast()).expect("invalid class object, object didn't have opaque value")
+        self.0
+            .class_opaque(C::class_id().get())
+            .expect("invalid class object, object didn't have opaque value")
+            .cast()
     }
 
     /// Turns the class back into a generic object.
@@ -309,20 +378,25 @@ impl<'js, C: JsClass<'js>> Class<'js, C> {
 }
 
 impl<'js> Object<'js> {
-    /// Returns if the object is of a certain rust class.
+    /// Returns if the object is of a certain rust class, or of a class which extends it.
     pub fn instance_of<C: JsClass<'js>>(&self) -> bool {
         if !Class::<C>::is_registered(&self.ctx) {
             return false;
         }
 
-        let p = unsafe {
-            qjs::JS_GetOpaque2(
-                self.0.ctx.as_ptr(),
-                self.0.as_js_value(),
-                C::class_id().get(),
-            )
-        };
-        !p.is_null()
+        self.class_opaque(C::class_id().get()).is_some()
+    }
+
+    /// Returns the rust value of the object if it is of the class with the given id or of a class
+    /// which extends it.
+    fn class_opaque(&self, class_id: qjs::JSClassID) -> Option<NonNull<qjs::c_void>> {
+        let own_id = unsafe { qjs::JS_GetClassID(self.as_js_value()) };
+        let bases = unsafe { &(*self.ctx.get_opaque()).class_bases };
+        let mut id = own_id;
+        while id != class_id {
+            id = *bases.get(&id)?;
+        }
+        NonNull::new(unsafe { qjs::JS_GetOpaque(self.as_js_value(), own_id) })
     }
 
     /// Turn the object into the class if it is an instance of that class.
@@ -373,11 +447,11 @@ mod test {
     };
 
     use crate::{
-        class::{ClassId, JsClass, Readable, Trace, Tracer, Writable},
+        class::{ClassId, Extends, JsClass, OwnedBorrowMut, Readable, Trace, Tracer, Writable},
         function::This,
         test_with,
         value::Constructor,
-        Class, Context, FromJs, Function, IntoJs, Object, Runtime,
+        Class, Context, Error, FromJs, Function, IntoJs, Object, Runtime, StdString,
     };
 
     /// Test circlular references.
@@ -577,4 +651,206 @@ mod test {
             Class::<X>::register(&ctx).unwrap();
         })
     }
+
+    #[test]
+    fn extends() {
+        pub struct Animal {
+            name: StdString,
+        }
+
+        impl<'js> Trace<'js> for Animal {
+            fn trace<'a>(&self, _tracer: Tracer<'a, 'js>) {}
+        }
+
+        impl<'js> JsClass<'js> for Animal {
+            const NAME: &'static str = "Animal";
+
+            type Mutable = Writable;
+
+            fn class_id() -> &'static crate::class::ClassId {
+                static ID: ClassId = ClassId::new();
+                &ID
+            }
+
+            fn prototype(ctx: &crate::Ctx<'js>) -> crate::Result<Option<crate::Object<'js>>> {
+                let proto = Object::new(ctx.clone())?;
+                let speak = Function::new(ctx.clone(), |this: This<Class<'js, Animal>>| {
+                    format!("{} makes a sound", this.borrow().name)
+                })?;
+                proto.set("speak", speak)?;
+                let rename = Function::new(
+                    ctx.clone(),
+                    |this: This<Class<'js, Animal>>, name: StdString| {
+                        this.borrow_mut().name = name;
+                    },
+                )?;
+                proto.set("rename", rename)?;
+                Ok(Some(proto))
+            }
+
+            fn constructor(
+                ctx: &crate::Ctx<'js>,
+            ) -> crate::Result<Option<crate::value::Constructor<'js>>> {
+                let constr =
+                    Constructor::new_class::<Animal, _, _>(ctx.clone(), |ctx, name: StdString| {
+                        Class::instance(ctx, Animal { name })
+                    })?;
+                Ok(Some(constr))
+            }
+        }
+
+        #[repr(C)]
+        pub struct Dog {
+            animal: Animal,
+            tricks: u32,
+        }
+
+        impl<'js> Trace<'js> for Dog {
+            fn trace<'a>(&self, _tracer: Tracer<'a, 'js>) {}
+        }
+
+        impl<'js> JsClass<'js> for Dog {
+            const NAME: &'static str = "Dog";
+
+            type Mutable = Writable;
+
+            fn class_id() -> &'static crate::class::ClassId {
+                static ID: ClassId = ClassId::new();
+                &ID
+            }
+
+            fn prototype(ctx: &crate::Ctx<'js>) -> crate::Result<Option<crate::Object<'js>>> {
+                let proto = Object::new(ctx.clone())?;
+                let tricks = Function::new(ctx.clone(), |this: This<Class<'js, Dog>>| {
+                    this.borrow().tricks
+                })?;
+                proto.set("tricks", tricks)?;
+                Ok(Some(proto))
+            }
+
+            fn constructor(
+                ctx: &crate::Ctx<'js>,
+            ) -> crate::Result<Option<crate::value::Constructor<'js>>> {
+                let constr =
+                    Constructor::new_class::<Dog, _, _>(ctx.clone(), |ctx, name: StdString| {
+                        let animal = Animal { name };
+                        Class::instance(ctx, Dog { animal, tricks: 3 })
+                    })?;
+                Ok(Some(constr))
+            }
+        }
+
+        unsafe impl<'js> Extends<'js, Animal> for Dog {}
+
+        test_with(|ctx| {
+            Class::<Dog>::extends::<Animal>(&ctx).unwrap();
+            Class::<Animal>::define(&ctx.globals()).unwrap();
+            Class::<Dog>::define(&ctx.globals()).unwrap();
+
+            let res: bool = ctx
+                .eval(
+                    r#"
+                    var dog = new Dog("Rex");
+                    dog instanceof Dog
+                        && dog instanceof Animal
+                        && !(new Animal("Tom") instanceof Dog)
+                        && Object.getPrototypeOf(Dog.prototype) === Animal.prototype
+                        && dog.speak() === "Rex makes a sound"
+                        && dog.tricks() === 3
+                    "#,
+                )
+                .unwrap();
+            assert!(res);
+
+            // Methods of the base class borrow the base part of the subclass.
+            ctx.eval::<(), _>("dog.rename('Max')").unwrap();
+            let dog: Class<Dog> = ctx.globals().get("dog").unwrap();
+            assert_eq!(dog.borrow().animal.name, "Max");
+            assert_eq!(dog.borrow().tricks, 3);
+            let animal: Class<Animal> = ctx.globals().get("dog").unwrap();
+            assert_eq!(animal.borrow().name, "Max");
+            assert!(dog.try_borrow_mut().is_ok());
+
+            // But an animal isn't a dog.
+            let res = ctx.eval::<u32, _>("Dog.prototype.tricks.call(new Animal('Tom'))");
+            assert!(matches!(res, Err(Error::Exception)));
+            ctx.catch();
+        })
+    }
+
+    #[test]
+    fn reentrant_borrow() {
+        pub struct Counter(u32);
+
+        impl<'js> Trace<'js> for Counter {
+            fn trace<'a>(&self, _tracer: Tracer<'a, 'js>) {}
+        }
+
+        impl<'js> JsClass<'js> for Counter {
+            const NAME: &'static str = "Counter";
+
+            type Mutable = Writable;
+
+            fn class_id() -> &'static crate::class::ClassId {
+                static ID: ClassId = ClassId::new();
+                &ID
+            }
+
+            fn prototype(ctx: &crate::Ctx<'js>) -> crate::Result<Option<crate::Object<'js>>> {
+                let proto = Object::new(ctx.clone())?;
+                let update = Function::new(
+                    ctx.clone(),
+                    |this: This<OwnedBorrowMut<'js, Counter>>, f: Function<'js>| {
+                        let mut this = this.0;
+                        this.0 += 1;
+                        f.call::<_, ()>(())
+                    },
+                )?;
+                proto.set("update", update)?;
+                Ok(Some(proto))
+            }
+
+            fn constructor(
+                _ctx: &crate::Ctx<'js>,
+            ) -> crate::Result<Option<crate::value::Constructor<'js>>> {
+                Ok(None)
+            }
+        }
+
+        test_with(|ctx| {
+            let counter = Class::instance(ctx.clone(), Counter(0)).unwrap();
+            ctx.globals().set("counter", counter.clone()).unwrap();
+
+            let message: StdString = ctx
+                .eval(
+                    r#"
+                    let message;
+                    counter.update(() => {
+                        try {
+                            counter.update(() => {});
+                        } catch (e) {
+                            message = e.message;
+                        }
+                    });
+                    message
+                    "#,
+                )
+                .unwrap();
+            assert!(message.contains("`Counter`"), "{message}");
+            assert!(message.contains("already borrowed"), "{message}");
+            assert_eq!(counter.borrow().0, 1);
+
+            let borrow = counter.borrow();
+            let err = counter.try_borrow_mut().err();
+            assert!(matches!(
+                err,
+                Some(Error::BorrowConflict {
+                    class: "Counter",
+                    ..
+                })
+            ));
+            drop(borrow);
+            assert!(counter.try_borrow_mut().is_ok());
+        })
+    }
 }
@@ -104,6 +104,8 @@ unsafe impl Mutability for Readable {
 /// When a class has `Writable` as it Mutable type you can borrow it both mutability and immutable.
 pub enum Writable {}
 
+// The layout is fixed so the value only depends on its alignment, see `Extends`.
+#[repr(C)]
 pub struct WritableCell<T> {
     count: Cell<usize>,
     value: UnsafeCell<T>,
@@ -203,6 +205,7 @@ unsafe impl Mutability for Writable {
 /// A cell type for rust classes passed to javascript.
 ///
 /// Implements a RefCell like borrow checking.
+#[repr(transparent)]
 pub struct JsCell<'js, T: JsClass<'js>> {
     pub(crate) cell: <T::Mutable as Mutability>::Cell<T>,
 }
@@ -221,7 +224,8 @@ impl<'js, T: JsClass<'js>> JsCell<'js, T> {
     /// Panics if the value is already borrowed mutably
     pub fn borrow<'a>(&'a self) -> Borrow<'a, 'js, T> {
         unsafe {
-            <T::Mutable as Mutability>::borrow(&self.cell).unwrap();
+            <T::Mutable as Mutability>::borrow(&self.cell)
+                .unwrap_or_else(|e| panic!("failed to borrow class `{}`: {}", T::NAME, e));
             Borrow(&self.cell)
         }
     }
@@ -240,7 +244,8 @@ impl<'js, T: JsClass<'js>> JsCell<'js, T> {
     /// Panics if the value is already borrowed mutably or the class can't be borrowed mutably.
     pub fn borrow_mut<'a>(&'a self) -> BorrowMut<'a, 'js, T> {
         unsafe {
-            <T::Mutable as Mutability>::borrow_mut(&self.cell).unwrap();
+            <T::Mutable as Mutability>::borrow_mut(&self.cell)
+                .unwrap_or_else(|e| panic!("failed to borrow class `{}`: {}", T::NAME, e));
             BorrowMut(&self.cell)
         }
     }
@@ -342,7 +347,10 @@ impl<'js, T: JsClass<'js> + 'js> Deref for OwnedBorrow<'js, T> {
 impl<'js, T: JsClass<'js>> FromJs<'js> for OwnedBorrow<'js, T> {
     fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self, Error> {
         let cls = Class::from_js(ctx, value)?;
-        OwnedBorrow::try_from_class(cls).map_err(Error::ClassBorrow)
+        OwnedBorrow::try_from_class(cls).map_err(|error| Error::BorrowConflict {
+            class: T::NAME,
+            error,
+        })
     }
 }
 
@@ -406,7 +414,10 @@ impl<'js, T: JsClass<'js> + 'js> DerefMut for OwnedBorrowMut<'js, T> {
 impl<'js, T: JsClass<'js>> FromJs<'js> for OwnedBorrowMut<'js, T> {
     fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self, Error> {
         let cls = Class::from_js(ctx, value)?;
-        OwnedBorrowMut::try_from_class(cls).map_err(Error::ClassBorrow)
+        OwnedBorrowMut::try_from_class(cls).map_err(|error| Error::BorrowConflict {
+            class: T::NAME,
+            error,
+        })
     }
 }
 
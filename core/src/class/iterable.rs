@@ -0,0 +1,322 @@
+use crate::{
+    class::{Class, JsClass},
+    function::{Func, MutFn, This},
+    Ctx, Function, IntoJs, Object, Result, Symbol,
+};
+#[cfg(feature = "futures")]
+use crate::{promise::Promised, Value};
+#[cfg(feature = "futures")]
+use std::{
+    cell::RefCell,
+    future,
+    rc::Rc,
+    task::{Context as TaskContext, Poll},
+};
+
+/// A rust class which can be iterated over from javascript with `for...of`.
+///
+/// [`Iterable::define_iterator`] installs a `[Symbol.iterator]` method on the prototype of the
+/// class. Every call of that method creates a new rust iterator with [`Iterable::js_iter`] and
+/// wraps it into a javascript iterator object, each call to `next()` pulls the next item from the
+/// rust iterator and converts it with [`IntoJs`].
+///
+/// Classes defined with the `#[class(iterable)]` macro attribute have the iterator installed
+/// automatically, manual implementations of [`JsClass`] should call
+/// [`Iterable::define_iterator`] from [`JsClass::prototype`].
+pub trait Iterable<'js>: JsClass<'js> + Sized + 'js {
+    /// The type of the values yielded to javascript.
+    type Item: IntoJs<'js>;
+
+    /// The rust iterator which is wrapped into a javascript iterator.
+    type Iter: Iterator<Item = Self::Item> + 'js;
+
+    /// Create a new iterator over the class.
+    fn js_iter(&self) -> Self::Iter;
+
+    /// Define the `[Symbol.iterator]` method on the given prototype.
+    fn define_iterator(proto: &Object<'js>) -> Result<()> {
+        proto.set(
+            Symbol::iterator(proto.ctx().clone()),
+            Func::from(|this: This<Class<'js, Self>>| {
+                let iter = this.0.try_borrow()?.js_iter();
+                iterator_object(this.0.ctx().clone(), iter)
+            }),
+        )
+    }
+}
+
+/// Wrap a rust iterator into a javascript iterator object.
+fn iterator_object<'js, I>(ctx: Ctx<'js>, mut iter: I) -> Result<Object<'js>>
+where
+    I: Iterator + 'js,
+    I::Item: IntoJs<'js>,
+{
+    let object = Object::new(ctx.clone())?;
+    let next = Function::new(
+        ctx.clone(),
+        MutFn::from(move |ctx: Ctx<'js>| {
+            let res = Object::new(ctx)?;
+            match iter.next() {
+                Some(value) => {
+                    res.set("value", value)?;
+                    res.set("done", false)?;
+                }
+                None => res.set("done", true)?,
+            }
+            Result::Ok(res)
+        }),
+    )?;
+    object.set("next", next)?;
+    // Iterators are iterable themselves so they can be used with spread and `for...of`.
+    object.set(
+        Symbol::iterator(ctx),
+        Func::from(|this: This<Object<'js>>| this.0),
+    )?;
+    Ok(object)
+}
+
+/// A rust class which can be iterated over asynchronously from javascript with `for await...of`.
+///
+/// [`AsyncIterable::define_async_iterator`] installs a `[Symbol.asyncIterator]` method on the
+/// prototype of the class. Every call of that method creates a new stream with
+/// [`AsyncIterable::js_stream`] and wraps it into a javascript async iterator object. Each call
+/// to `next()` returns a promise which resolves to `{ value, done }` once
+/// [`AsyncIterable::poll_next`] returns a value or the end of the stream.
+///
+/// The promises are driven by the spawner of the runtime, so the class can only be iterated in
+/// contexts of an [`AsyncRuntime`](crate::AsyncRuntime). A type implementing the `Stream` trait of
+/// the `futures` crate can implement [`AsyncIterable::poll_next`] by forwarding to
+/// `poll_next_unpin`.
+#[cfg(feature = "futures")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "futures")))]
+pub trait AsyncIterable<'js>: JsClass<'js> + Sized + 'js {
+    /// The type of the values yielded to javascript.
+    type Item: IntoJs<'js> + 'js;
+
+    /// The state of a single iteration over the class.
+    type Stream: 'js;
+
+    /// Create a new stream over the class.
+    fn js_stream(&self) -> Self::Stream;
+
+    /// Poll the next item of the stream, returning `Poll::Ready(None)` once the stream ended.
+    fn poll_next(stream: &mut Self::Stream, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>>;
+
+    /// Define the `[Symbol.asyncIterator]` method on the given prototype.
+    fn define_async_iterator(proto: &Object<'js>) -> Result<()> {
+        proto.set(
+            Symbol::async_iterator(proto.ctx().clone()),
+            Func::from(|this: This<Class<'js, Self>>| {
+                let stream = this.0.try_borrow()?.js_stream();
+                async_iterator_object::<Self>(this.0.ctx().clone(), stream)
+            }),
+        )
+    }
+}
+
+/// Wrap a stream of an async iterable class into a javascript async iterator object.
+#[cfg(feature = "futures")]
+fn async_iterator_object<'js, T>(ctx: Ctx<'js>, stream: T::Stream) -> Result<Object<'js>>
+where
+    T: AsyncIterable<'js>,
+{
+    let stream = Rc::new(RefCell::new(stream));
+    let object = Object::new(ctx.clone())?;
+    let next = Function::new(ctx.clone(), move || {
+        let stream = stream.clone();
+        Promised(future::poll_fn(move |cx| {
+            T::poll_next(&mut stream.borrow_mut(), cx).map(IteratorResult)
+        }))
+    })?;
+    object.set("next", next)?;
+    object.set(
+        Symbol::async_iterator(ctx),
+        Func::from(|this: This<Object<'js>>| this.0),
+    )?;
+    Ok(object)
+}
+
+/// The result of a step of an async iterator, converted into `{ value, done }`.
+#[cfg(feature = "futures")]
+struct IteratorResult<T>(Option<T>);
+
+#[cfg(feature = "futures")]
+impl<'js, T: IntoJs<'js>> IntoJs<'js> for IteratorResult<T> {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        let res = Object::new(ctx.clone())?;
+        match self.0 {
+            Some(value) => {
+                res.set("value", value)?;
+                res.set("done", false)?;
+            }
+            None => res.set("done", true)?,
+        }
+        Ok(res.into_value())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Iterable;
+    use crate::{
+        class::{ClassId, JsClass, Trace, Tracer, Writable},
+        test_with, Class, Ctx, Object, Result, StdString,
+    };
+    use std::ops::RangeInclusive;
+
+    struct Range {
+        end: i32,
+    }
+
+    impl<'js> Trace<'js> for Range {
+        fn trace<'a>(&self, _tracer: Tracer<'a, 'js>) {}
+    }
+
+    impl<'js> JsClass<'js> for Range {
+        const NAME: &'static str = "Range";
+
+        type Mutable = Writable;
+
+        fn class_id() -> &'static ClassId {
+            static ID: ClassId = ClassId::new();
+            &ID
+        }
+
+        fn prototype(ctx: &Ctx<'js>) -> Result<Option<Object<'js>>> {
+            let proto = Object::new(ctx.clone())?;
+            Self::define_iterator(&proto)?;
+            Ok(Some(proto))
+        }
+
+        fn constructor(_ctx: &Ctx<'js>) -> Result<Option<crate::value::Constructor<'js>>> {
+            Ok(None)
+        }
+    }
+
+    impl<'js> Iterable<'js> for Range {
+        type Item = i32;
+        type Iter = RangeInclusive<i32>;
+
+        fn js_iter(&self) -> Self::Iter {
+            1..=self.end
+        }
+    }
+
+    #[test]
+    fn for_of() {
+        test_with(|ctx| {
+            let range = Class::instance(ctx.clone(), Range { end: 3 }).unwrap();
+            ctx.globals().set("range", range.clone()).unwrap();
+
+            let res: StdString = ctx
+                .eval("let res = []; for (const x of range) res.push(x); res.join()")
+                .unwrap();
+            assert_eq!(res, "1,2,3");
+
+            range.borrow_mut().end = 2;
+            let res: StdString = ctx.eval("[...range, ...range].join()").unwrap();
+            assert_eq!(res, "1,2,1,2");
+
+            let res: bool = ctx
+                .eval("const iter = range[Symbol.iterator](); iter.next(); iter.next(); iter.next().done")
+                .unwrap();
+            assert!(res);
+        });
+    }
+
+    #[cfg(feature = "futures")]
+    mod r#async {
+        use crate::{
+            async_with,
+            class::{AsyncIterable, ClassId, JsClass, Trace, Tracer, Writable},
+            promise::Promise,
+            runtime::async_test_case,
+            Class, Ctx, Object, Result, StdString,
+        };
+        use std::task::{Context, Poll};
+
+        struct Ticker {
+            count: i32,
+        }
+
+        impl<'js> Trace<'js> for Ticker {
+            fn trace<'a>(&self, _tracer: Tracer<'a, 'js>) {}
+        }
+
+        impl<'js> JsClass<'js> for Ticker {
+            const NAME: &'static str = "Ticker";
+
+            type Mutable = Writable;
+
+            fn class_id() -> &'static ClassId {
+                static ID: ClassId = ClassId::new();
+                &ID
+            }
+
+            fn prototype(ctx: &Ctx<'js>) -> Result<Option<Object<'js>>> {
+                let proto = Object::new(ctx.clone())?;
+                Self::define_async_iterator(&proto)?;
+                Ok(Some(proto))
+            }
+
+            fn constructor(_ctx: &Ctx<'js>) -> Result<Option<crate::value::Constructor<'js>>> {
+                Ok(None)
+            }
+        }
+
+        struct Ticks {
+            next: i32,
+            end: i32,
+            waited: bool,
+        }
+
+        impl<'js> AsyncIterable<'js> for Ticker {
+            type Item = i32;
+            type Stream = Ticks;
+
+            fn js_stream(&self) -> Ticks {
+                Ticks {
+                    next: 1,
+                    end: self.count,
+                    waited: false,
+                }
+            }
+
+            fn poll_next(stream: &mut Ticks, cx: &mut Context<'_>) -> Poll<Option<i32>> {
+                // Every value only becomes available after being polled once.
+                if !stream.waited {
+                    stream.waited = true;
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                stream.waited = false;
+                if stream.next > stream.end {
+                    return Poll::Ready(None);
+                }
+                stream.next += 1;
+                Poll::Ready(Some(stream.next - 1))
+            }
+        }
+
+        async_test_case!(for_await => (_rt, ctx) {
+            async_with!(&ctx => |ctx| {
+                let ticker = Class::instance(ctx.clone(), Ticker { count: 3 }).unwrap();
+                ctx.globals().set("ticker", ticker).unwrap();
+
+                let res: Promise<StdString> = ctx
+                    .eval(
+                        r#"
+                        (async () => {
+                            const res = [];
+                            for await (const x of ticker) res.push(x);
+                            return res.join();
+                        })()
+                    "#,
+                    )
+                    .unwrap();
+                assert_eq!(res.await.unwrap(), "1,2,3");
+            })
+            .await;
+        });
+    }
+}
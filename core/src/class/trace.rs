@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use crate::{markers::Invariant, qjs, Ctx, Value};
+use crate::{markers::Invariant, qjs, Atom, Ctx, Value};
 
 /// A trait for classes for tracing references to quickjs objects.
 ///
@@ -47,6 +47,16 @@ impl<'a, 'js> Tracer<'a, 'js> {
         }
     }
 
+    /// Mark an atom as being reachable from the current traced object.
+    ///
+    /// Atoms are not managed by the cycle collector, an atom, including one which wraps a symbol,
+    /// is kept alive by its own reference count which [`Atom`] holds until it is dropped. So
+    /// apart from the context of the atom there is nothing to mark, this exists so that types
+    /// containing atoms can forward their trace implementation.
+    pub fn mark_atom(self, atom: &Atom<'js>) {
+        self.mark_ctx(&atom.ctx);
+    }
+
     pub fn mark_ctx(self, ctx: &Ctx<'js>) {
         let ptr = ctx.as_ptr();
         unsafe { (self.mark_func.unwrap())(self.rt, ptr.cast()) }
@@ -65,6 +75,23 @@ impl<'js> Trace<'js> for Ctx<'js> {
     }
 }
 
+impl<'js> Trace<'js> for Atom<'js> {
+    fn trace<'a>(&self, tracer: Tracer<'a, 'js>) {
+        tracer.mark_atom(self);
+    }
+}
+
+impl<'js, T, const N: usize> Trace<'js> for [T; N]
+where
+    T: Trace<'js>,
+{
+    fn trace<'a>(&self, tracer: Tracer<'a, 'js>) {
+        for item in self.iter() {
+            item.trace(tracer);
+        }
+    }
+}
+
 macro_rules! trace_impls {
 
     (primitive: $( $(#[$meta:meta])* $($type:ident)::+$(<$lt:lifetime>)?,)*) => {
@@ -158,8 +185,12 @@ trace_impls! {
     f32,f64,
     bool,char,
     String,
-    crate::Atom<'js>,
     crate::Module<'js>,
+    crate::WeakRef<'js>,
+}
+
+impl<'js> Trace<'js> for std::borrow::Cow<'_, str> {
+    fn trace<'a>(&self, _tracer: Tracer<'a, 'js>) {}
 }
 
 trace_impls! {
@@ -168,8 +199,13 @@ trace_impls! {
     crate::Array,
     crate::Function,
     crate::BigInt,
+    crate::Date,
     crate::Symbol,
     crate::Exception,
+    crate::RegExp,
+    crate::Map,
+    crate::Set,
+    crate::Proxy,
     crate::String,
 }
 
@@ -221,3 +257,106 @@ trace_impls! {
     #[cfg_attr(feature = "doc-cfg", doc(cfg(all(feature = "classes", feature = "indexmap"))))]
     indexmap::IndexMap {S},
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use crate::{
+        class::{ClassId, JsClass, Trace, Tracer, Writable},
+        value::Constructor,
+        Atom, Class, Context, Ctx, Object, Result, Runtime, Symbol, Value,
+    };
+
+    /// Sets the flag when dropped so tests can check that it was collected.
+    pub struct Container<'js> {
+        objects: [Object<'js>; 3],
+        atom: Atom<'js>,
+        dropped: Arc<AtomicBool>,
+    }
+
+    impl<'js> Drop for Container<'js> {
+        fn drop(&mut self) {
+            self.dropped.store(true, Ordering::SeqCst);
+        }
+    }
+
+    impl<'js> Trace<'js> for Container<'js> {
+        fn trace<'a>(&self, tracer: Tracer<'a, 'js>) {
+            self.objects.trace(tracer);
+            self.atom.trace(tracer);
+        }
+    }
+
+    impl<'js> JsClass<'js> for Container<'js> {
+        const NAME: &'static str = "Container";
+
+        type Mutable = Writable;
+
+        fn class_id() -> &'static ClassId {
+            static ID: ClassId = ClassId::new();
+            &ID
+        }
+
+        fn prototype(ctx: &Ctx<'js>) -> Result<Option<Object<'js>>> {
+            Ok(Some(Object::new(ctx.clone())?))
+        }
+
+        fn constructor(_ctx: &Ctx<'js>) -> Result<Option<Constructor<'js>>> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn trace_container() {
+        // whether the objects of the container refer back to it forming a cycle.
+        for cycle in [false, true] {
+            let rt = Runtime::new().unwrap();
+            let ctx = Context::full(&rt).unwrap();
+
+            let dropped = Arc::new(AtomicBool::new(false));
+
+            ctx.with(|ctx| {
+                let objects = [
+                    Object::new(ctx.clone()).unwrap(),
+                    Object::new(ctx.clone()).unwrap(),
+                    Object::new(ctx.clone()).unwrap(),
+                ];
+                let symbol: Value = ctx.eval("Symbol('foo')").unwrap();
+                let cls = Class::instance(
+                    ctx.clone(),
+                    Container {
+                        objects: objects.clone(),
+                        atom: Atom::from_value(ctx.clone(), &symbol).unwrap(),
+                        dropped: dropped.clone(),
+                    },
+                )
+                .unwrap();
+                if cycle {
+                    for obj in objects.iter() {
+                        obj.set("container", cls.clone()).unwrap();
+                    }
+                }
+                ctx.globals().set("t", cls).unwrap();
+            });
+            rt.run_gc();
+            assert!(!dropped.load(Ordering::SeqCst));
+            ctx.with(|ctx| {
+                let cls: Class<Container> = ctx.globals().get("t").unwrap();
+                // Only the atom references the symbol, its reference count kept it alive.
+                let value = unsafe {
+                    let val = crate::qjs::JS_AtomToValue(ctx.as_ptr(), cls.borrow().atom.atom);
+                    Value::from_js_value(ctx.clone(), val)
+                };
+                let symbol = Symbol::from_value(value).unwrap();
+                assert_eq!(symbol.description().unwrap().to_string().unwrap(), "foo");
+                ctx.globals().remove("t").unwrap();
+            });
+            rt.run_gc();
+            assert!(dropped.load(Ordering::SeqCst));
+        }
+    }
+}
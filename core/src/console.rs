@@ -0,0 +1,181 @@
+//! A minimal `console` object forwarding output to rust.
+//!
+//! ```
+//! # use rquickjs::{Runtime, Context, console::{self, Level}};
+//! # let rt = Runtime::new().unwrap();
+//! # let ctx = Context::full(&rt).unwrap();
+//! ctx.with(|ctx| {
+//!     console::install(&ctx, |level: Level, msg: String| eprintln!("[{}] {}", level, msg))
+//!         .unwrap();
+//!     ctx.eval::<(), _>("console.warn('low disk space', { free: 12 })").unwrap();
+//! });
+//! ```
+
+use std::{fmt, rc::Rc};
+
+use crate::{function::Rest, Ctx, Function, Object, Result, StdString, Value};
+
+/// How many levels of nested objects and arrays are formatted before they are abbreviated.
+const MAX_DEPTH: usize = 2;
+
+/// The console method a message was logged with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Level {
+    /// `console.debug`
+    Debug,
+    /// `console.log`
+    Log,
+    /// `console.info`
+    Info,
+    /// `console.warn`
+    Warn,
+    /// `console.error`
+    Error,
+}
+
+impl Level {
+    const ALL: [Level; 5] = [
+        Level::Debug,
+        Level::Log,
+        Level::Info,
+        Level::Warn,
+        Level::Error,
+    ];
+
+    /// The name of the console method for this level.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Log => "log",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// Install a `console` object with `log`, `debug`, `info`, `warn` and `error` methods on the
+/// global object of the context.
+///
+/// Every call formats its arguments with [`format`] and passes the resulting line to `sink`
+/// together with the [`Level`] of the method which was called. An existing `console` global is
+/// replaced.
+pub fn install<'js, F>(ctx: &Ctx<'js>, sink: F) -> Result<()>
+where
+    F: Fn(Level, StdString) + 'js,
+{
+    let sink = Rc::new(sink);
+    let console = Object::new(ctx.clone())?;
+    for level in Level::ALL {
+        let sink = sink.clone();
+        let func = Function::new(ctx.clone(), move |args: Rest<Value<'js>>| {
+            sink(level, format(&args)?);
+            Result::Ok(())
+        })?
+        .with_name(level.as_str())?;
+        console.set(level.as_str(), func)?;
+    }
+    ctx.globals().set("console", console)
+}
+
+/// Format console arguments into a single line, approximating node's `util.inspect`.
+///
+/// Arguments are separated by spaces. Strings given directly are printed as is, all other values
+/// are rendered with [`Value::inspect`], nested objects and arrays are printed up to two levels
+/// deep and like in node at most 100 entries of arrays, maps and sets are printed.
+pub fn format<'js>(args: &[Value<'js>]) -> Result<StdString> {
+    let mut out = StdString::new();
+    for (idx, arg) in args.iter().enumerate() {
+        if idx > 0 {
+            out.push(' ');
+        }
+        if let Some(string) = arg.as_string() {
+            out.push_str(&string.to_string()?);
+        } else {
+            out.push_str(&arg.inspect(MAX_DEPTH)?);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::Level;
+    use crate::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn capture_log() {
+        test_with(|ctx| {
+            let buffer = Rc::new(RefCell::new(Vec::new()));
+            let sink = buffer.clone();
+            console::install(&ctx, move |level, msg| sink.borrow_mut().push((level, msg))).unwrap();
+
+            ctx.eval::<(), _>(
+                r#"
+                console.log("x", {a:1});
+                console.error([1, "two", [3, [4, [5]]]], null, undefined);
+                console.info({ a: { b: { c: { d: 1 } } }, "odd key": () => {} });
+                const cycle = { name: "cycle" };
+                cycle.self = cycle;
+                console.warn(new Map([["k", new Set([1])]]), cycle);
+                console.debug(new TypeError("bad"), Symbol("s"), 10n, 1.5);
+                "#,
+            )
+            .unwrap();
+
+            assert_eq!(
+                *buffer.borrow(),
+                [
+                    (Level::Log, "x { a: 1 }".to_string()),
+                    (
+                        Level::Error,
+                        "[ 1, 'two', [ 3, [ 4, [Array] ] ] ] null undefined".to_string()
+                    ),
+                    (
+                        Level::Info,
+                        "{ a: { b: { c: [Object] } }, 'odd key': [Function: odd key] }".to_string()
+                    ),
+                    (
+                        Level::Warn,
+                        "Map(1) { 'k' => Set(1) { 1 } } { name: 'cycle', self: [Circular] }"
+                            .to_string()
+                    ),
+                    (Level::Debug, "TypeError: bad Symbol(s) 10n 1.5".to_string()),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn huge_sparse_array() {
+        test_with(|ctx| {
+            let buffer = Rc::new(RefCell::new(Vec::new()));
+            let sink = buffer.clone();
+            console::install(&ctx, move |_, msg| sink.borrow_mut().push(msg)).unwrap();
+
+            ctx.eval::<(), _>(
+                r#"
+                const sparse = [];
+                sparse[4294967294] = "last";
+                console.log(sparse);
+                const holes = [];
+                for (let idx = 0; idx < 300; idx += 2) holes[idx] = idx;
+                console.log(holes);
+                "#,
+            )
+            .unwrap();
+
+            let buffer = buffer.borrow();
+            assert_eq!(buffer[0], "[ <4294967294 empty items>, 'last' ]");
+            assert!(buffer[1].starts_with("[ 0, <1 empty item>, 2, <1 empty item>, 4,"));
+            assert!(buffer[1].ends_with(", 98, <1 empty item>, ... 199 more items ]"));
+        });
+    }
+}
@@ -3,7 +3,12 @@
 mod builder;
 pub use builder::{intrinsic, ContextBuilder, Intrinsic};
 mod ctx;
+#[cfg(feature = "loader")]
+pub(crate) use ctx::strip_bytecode_header;
 pub use ctx::{Ctx, EvalOptions};
+mod globals;
+pub use globals::GlobalsSnapshot;
+mod json;
 #[cfg(feature = "multi-ctx")]
 mod multi_with_impl;
 
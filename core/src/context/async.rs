@@ -247,10 +247,12 @@ impl AsyncContext {
         F: for<'js> FnOnce(Ctx<'js>) -> R + ParallelSend,
         R: ParallelSend,
     {
-        let guard = self.rt.inner.lock().await;
+        let mut guard = self.rt.inner.lock().await;
         guard.update_stack_top();
         let ctx = unsafe { Ctx::new_async(self) };
-        f(ctx)
+        let res = f(ctx);
+        guard.resume_panic();
+        res
     }
 }
 
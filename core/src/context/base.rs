@@ -102,10 +102,12 @@ impl Context {
     where
         F: FnOnce(Ctx) -> R,
     {
-        let guard = self.rt.inner.lock();
+        let mut guard = self.rt.inner.lock();
         guard.update_stack_top();
         let ctx = unsafe { Ctx::new(self) };
-        f(ctx)
+        let res = f(ctx);
+        guard.resume_panic();
+        res
     }
 
     pub(crate) unsafe fn init_raw(ctx: *mut qjs::JSContext) {
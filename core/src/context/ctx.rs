@@ -1,34 +1,54 @@
 use std::{
+    any::Any,
     convert::TryInto,
     ffi::{CStr, CString},
-    fs, mem,
+    fs, mem, panic,
     path::Path,
-    ptr::NonNull,
+    ptr::{self, NonNull},
+    slice,
+    time::{Duration, Instant},
 };
 
 #[cfg(feature = "futures")]
 use std::future::Future;
 
-#[cfg(feature = "futures")]
-use crate::AsyncContext;
+#[cfg(feature = "loader")]
+use crate::loader::{LoaderHolder, RawLoader, Resolver};
 use crate::{
-    markers::Invariant, qjs, runtime::raw::Opaque, Context, Error, FromJs, Function, IntoJs,
-    Module, Object, Result, String, Value,
+    markers::{Invariant, ParallelSend, ParallelSync},
+    qjs,
+    runtime::raw::Opaque,
+    userdata::{ContextUserData, UserDataMap},
+    Class, Context, Error, Exception, FromJs, Function, IntoJs, Module, Object, Result, StdResult,
+    StdString, String, Value,
 };
+#[cfg(feature = "futures")]
+use crate::{promise::Promise, AsyncContext};
 
 /// Eval options.
 pub struct EvalOptions {
     /// Global code.
+    ///
+    /// When false the code is evaluated as a module, just like when `module` is set.
     pub global: bool,
     /// Force 'strict' mode.
     pub strict: bool,
+    /// Evaluate the code as a module.
+    ///
+    /// Overrides `global`, module code is always strict.
+    pub module: bool,
     /// Don't include the stack frames before this eval in the Error() backtraces.
     pub backtrace_barrier: bool,
+    /// Only compile the code without running it.
+    ///
+    /// The returned value is then the compiled script or module instead of the result of the
+    /// evaluation.
+    pub compile_only: bool,
 }
 
 impl EvalOptions {
     fn to_flag(&self) -> i32 {
-        let mut flag = if self.global {
+        let mut flag = if self.global && !self.module {
             qjs::JS_EVAL_TYPE_GLOBAL
         } else {
             qjs::JS_EVAL_TYPE_MODULE
@@ -42,6 +62,10 @@ impl EvalOptions {
             flag |= qjs::JS_EVAL_FLAG_BACKTRACE_BARRIER;
         }
 
+        if self.compile_only {
+            flag |= qjs::JS_EVAL_FLAG_COMPILE_ONLY;
+        }
+
         flag as i32
     }
 }
@@ -51,11 +75,46 @@ impl Default for EvalOptions {
         EvalOptions {
             global: true,
             strict: true,
+            module: false,
             backtrace_barrier: false,
+            compile_only: false,
         }
     }
 }
 
+const BYTECODE_MAGIC: &[u8] = b"rquickjs";
+
+fn bytecode_version() -> StdString {
+    format!("{}+{}", qjs::QUICKJS_VERSION, qjs::VERSION)
+}
+
+fn bytecode_header() -> Vec<u8> {
+    let version = bytecode_version();
+    let mut header = BYTECODE_MAGIC.to_vec();
+    header.push(version.len() as u8);
+    header.extend_from_slice(version.as_bytes());
+    header
+}
+
+/// Strip the version header from bytecode created by [`Ctx::compile_to_bytecode`] returning an
+/// error if it was compiled by another version.
+pub(crate) fn strip_bytecode_header(bytecode: &[u8]) -> Result<&[u8]> {
+    let header = bytecode_header();
+    bytecode.strip_prefix(header.as_slice()).ok_or_else(|| {
+        let found = bytecode
+            .strip_prefix(BYTECODE_MAGIC)
+            .and_then(|rest| {
+                let len = *rest.first()? as usize;
+                rest.get(1..len + 1)
+            })
+            .map(|version| StdString::from_utf8_lossy(version).into_owned());
+        Error::Bytecode {
+            expected: bytecode_version(),
+            found,
+        }
+    })
+}
+
 /// Context in use, passed to [`Context::with`].
 #[derive(Debug)]
 pub struct Ctx<'js> {
@@ -142,36 +201,230 @@ impl<'js> Ctx<'js> {
         source: S,
         options: EvalOptions,
     ) -> Result<V> {
-        let file_name = cstr!("eval_script");
+        self.eval_named(source, cstr!("eval_script"), options)
+    }
+
+    /// Evaluate a script under the given name.
+    ///
+    /// The name is used as the file name of the script, it shows up in the stack traces of
+    /// errors thrown from the script.
+    pub fn eval_with_name<V: FromJs<'js>, S: Into<Vec<u8>>, N: Into<Vec<u8>>>(
+        &self,
+        source: S,
+        name: N,
+    ) -> Result<V> {
+        self.eval_with_name_and_options(source, name, Default::default())
+    }
+
+    /// Evaluate a script under the given name with the given options.
+    pub fn eval_with_name_and_options<V: FromJs<'js>, S: Into<Vec<u8>>, N: Into<Vec<u8>>>(
+        &self,
+        source: S,
+        name: N,
+        options: EvalOptions,
+    ) -> Result<V> {
+        let name = CString::new(name)?;
+        self.eval_named(source, name.as_c_str(), options)
+    }
 
+    fn eval_named<V: FromJs<'js>, S: Into<Vec<u8>>>(
+        &self,
+        source: S,
+        name: &CStr,
+        options: EvalOptions,
+    ) -> Result<V> {
         V::from_js(self, unsafe {
-            let val = self.eval_raw(source, file_name, options.to_flag())?;
+            let val = self.eval_raw(source, name, options.to_flag())?;
             Value::from_js_value(self.clone(), val)
         })
     }
 
     /// Evaluate a script directly from a file.
+    ///
+    /// The path is used as the name of the script in stack traces. Files with the `.mjs`
+    /// extension are evaluated as a module, other files as a global script.
     pub fn eval_file<V: FromJs<'js>, P: AsRef<Path>>(&self, path: P) -> Result<V> {
-        self.eval_file_with_options(path, Default::default())
+        let module = matches!(path.as_ref().extension(), Some(ext) if ext == "mjs");
+        let options = EvalOptions {
+            module,
+            ..Default::default()
+        };
+        self.eval_file_with_options(path, options)
     }
 
+    /// Evaluate a script directly from a file with the given options.
+    ///
+    /// Unlike [`Ctx::eval_file`] the extension of the file is not considered, the code is
+    /// evaluated as specified by the options.
     pub fn eval_file_with_options<V: FromJs<'js>, P: AsRef<Path>>(
         &self,
         path: P,
         options: EvalOptions,
     ) -> Result<V> {
-        let buffer = fs::read(path.as_ref())?;
-        let file_name = CString::new(
-            path.as_ref()
-                .file_name()
-                .unwrap()
-                .to_string_lossy()
-                .into_owned(),
-        )?;
+        let path = path.as_ref();
+        let buffer = fs::read(path)?;
+        let name = path.to_string_lossy().into_owned();
+        self.eval_with_name_and_options(buffer, name, options)
+    }
 
-        V::from_js(self, unsafe {
-            let val = self.eval_raw(buffer, file_name.as_c_str(), options.to_flag())?;
-            Value::from_js_value(self.clone(), val)
+    /// Evaluate a module and return a promise which settles once the module finished evaluating.
+    ///
+    /// The module is declared under the given name, like with [`Module::evaluate`], so it can be
+    /// imported by other modules afterwards and names must not be reused.
+    ///
+    /// The promise resolves with the namespace object of the module, so the exports can be read
+    /// from it once the promise resolved. Errors thrown while compiling or evaluating the module
+    /// reject the promise instead of being returned. The promise settles on the next run of the
+    /// pending jobs with [`Ctx::run_jobs`] or an async runtime.
+    ///
+    /// The bundled quickjs doesn't support top-level `await`, a module using it fails to
+    /// compile and the promise is rejected with the syntax error. Promises created by the module
+    /// are not awaited, the module is evaluated completely before this function returns.
+    #[cfg(feature = "futures")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "futures")))]
+    pub fn eval_module_promise<N, S>(&self, name: N, source: S) -> Result<Promise<'js, Object<'js>>>
+    where
+        N: Into<Vec<u8>>,
+        S: Into<Vec<u8>>,
+    {
+        let (promise, resolve, reject) = self.promise()?;
+        let namespace = match unsafe { self.eval_module_raw(name, source) } {
+            Ok((result, namespace)) => {
+                resolve.call::<_, ()>((result,))?;
+                namespace
+            }
+            Err(Error::Exception) => {
+                reject.call::<_, ()>((self.catch(),))?;
+                return Ok(Promise::from_object(promise));
+            }
+            Err(e) => return Err(e),
+        };
+        Promise::<Value>::from_object(promise).then(move || namespace.clone())
+    }
+
+    #[cfg(feature = "futures")]
+    unsafe fn eval_module_raw<N, S>(&self, name: N, source: S) -> Result<(Value<'js>, Object<'js>)>
+    where
+        N: Into<Vec<u8>>,
+        S: Into<Vec<u8>>,
+    {
+        let module = Module::unsafe_declare(self.clone(), name, source)?;
+        let def = module.as_module_def().as_ptr();
+        let value = qjs::JS_MKPTR(qjs::JS_TAG_MODULE, def.cast());
+        // JS_EvalFunction `free's` the module so we should dup first
+        let result = qjs::JS_EvalFunction(self.ctx.as_ptr(), qjs::JS_DupValue(value));
+        let result = Value::from_js_value(self.clone(), self.handle_exception(result)?);
+        let namespace = qjs::JS_GetModuleNamespace(self.ctx.as_ptr(), def);
+        let namespace = Value::from_js_value(self.clone(), self.handle_exception(namespace)?);
+        Ok((result, Object::from_js(self, namespace)?))
+    }
+
+    /// Compile a script into bytecode which can later be run with [`Ctx::eval_bytecode`].
+    pub fn compile_to_bytecode<S: Into<Vec<u8>>>(&self, source: S) -> Result<Vec<u8>> {
+        self.compile_to_bytecode_with_options(source, Default::default())
+    }
+
+    /// Compile a script or module into bytecode with the given options.
+    ///
+    /// The bytecode format of quickjs changes between versions so the bytecode is prefixed with
+    /// a small header containing the version it was compiled with, bytecode compiled with a
+    /// different version is rejected by [`Ctx::eval_bytecode`].
+    pub fn compile_to_bytecode_with_options<S: Into<Vec<u8>>>(
+        &self,
+        source: S,
+        options: EvalOptions,
+    ) -> Result<Vec<u8>> {
+        self.compile_named_to_bytecode(source, cstr!("eval_script"), options)
+    }
+
+    /// Compile a module into bytecode under the given name.
+    ///
+    /// The name is the name the module is declared with when the bytecode is loaded, so it should
+    /// be the name the module is imported with, for example when the bytecode is loaded with the
+    /// [`BuiltinLoader`](crate::loader::BuiltinLoader).
+    ///
+    /// The module is not declared in this context, so it can still be loaded here afterwards.
+    pub fn compile_module_to_bytecode<N, S>(&self, name: N, source: S) -> Result<Vec<u8>>
+    where
+        N: Into<Vec<u8>>,
+        S: Into<Vec<u8>>,
+    {
+        let name = CString::new(name)?;
+        let options = EvalOptions {
+            module: true,
+            ..Default::default()
+        };
+        // Compiling a module declares it in the context it is compiled in and quickjs can't
+        // remove a declared module again, so compile it in a context which is thrown away.
+        let realm = self.new_realm()?;
+        match realm.compile_named_to_bytecode(source, name.as_c_str(), options) {
+            Err(Error::Exception) => Err(self.throw(realm.catch())),
+            res => res,
+        }
+    }
+
+    fn compile_named_to_bytecode<S: Into<Vec<u8>>>(
+        &self,
+        source: S,
+        file_name: &CStr,
+        options: EvalOptions,
+    ) -> Result<Vec<u8>> {
+        let options = EvalOptions {
+            compile_only: true,
+            ..options
+        };
+
+        let mut bytecode = bytecode_header();
+        unsafe {
+            let value = Value::from_js_value(
+                self.clone(),
+                self.eval_raw(source, file_name, options.to_flag())?,
+            );
+            let mut len = mem::MaybeUninit::uninit();
+            let buf = qjs::JS_WriteObject(
+                self.ctx.as_ptr(),
+                len.as_mut_ptr(),
+                value.value,
+                qjs::JS_WRITE_OBJ_BYTECODE as i32,
+            );
+            if buf.is_null() {
+                return Err(self.raise_exception());
+            }
+            let len = len.assume_init();
+            bytecode.extend_from_slice(slice::from_raw_parts(buf, len as _));
+            qjs::js_free(self.ctx.as_ptr(), buf as _);
+        }
+        Ok(bytecode)
+    }
+
+    /// Run bytecode created by [`Ctx::compile_to_bytecode`].
+    ///
+    /// Returns an [`Error::Bytecode`] if the version header of the bytecode doesn't match this
+    /// version of quickjs.
+    ///
+    /// # Safety
+    /// Quickjs doesn't validate bytecode, reading malformed bytecode is undefined behavior. The
+    /// bytes must have been created by [`Ctx::compile_to_bytecode`] or
+    /// [`Ctx::compile_module_to_bytecode`] with the same build of quickjs and not been modified
+    /// since, the version header only guards against accidental mismatches and is easily forged.
+    pub unsafe fn eval_bytecode<V: FromJs<'js>>(&self, bytecode: &[u8]) -> Result<V> {
+        let bytecode = strip_bytecode_header(bytecode)?;
+
+        V::from_js(self, {
+            let value = self.handle_exception(qjs::JS_ReadObject(
+                self.ctx.as_ptr(),
+                bytecode.as_ptr(),
+                bytecode.len() as _,
+                qjs::JS_READ_OBJ_BYTECODE as i32,
+            ))?;
+            if qjs::JS_VALUE_GET_TAG(value) == qjs::JS_TAG_MODULE
+                && qjs::JS_ResolveModule(self.ctx.as_ptr(), value) < 0
+            {
+                qjs::JS_FreeValue(self.ctx.as_ptr(), value);
+                return Err(self.raise_exception());
+            }
+            let value = self.handle_exception(qjs::JS_EvalFunction(self.ctx.as_ptr(), value))?;
+            Value::from_js_value(self.clone(), value)
         })
     }
 
@@ -192,6 +445,84 @@ impl<'js> Ctx<'js> {
         }
     }
 
+    /// Create a new realm in the same runtime, a context with its own fresh global object and
+    /// all standard intrinsics.
+    ///
+    /// The new realm shares the heap, atoms and registered classes with this context but
+    /// globals defined in one realm are not visible in the other, similar to contexts of node's
+    /// `vm` module. The returned `Ctx` has the same lifetime as this one, it can be used as long
+    /// as the runtime is locked and the realm is kept alive by it and by its values.
+    ///
+    /// Although objects can technically be passed between realms, an object keeps the
+    /// prototypes and intrinsics of the realm it was created in, so for example an array from
+    /// the other realm is not an `instanceof Array` here. Values which should be independent of
+    /// their origin should be copied with [`Value::structured_clone`] instead.
+    pub fn new_realm(&self) -> Result<Ctx<'js>> {
+        let ctx = unsafe {
+            let rt = qjs::JS_GetRuntime(self.ctx.as_ptr());
+            NonNull::new(qjs::JS_NewContext(rt)).ok_or(Error::Allocation)?
+        };
+        unsafe { Context::init_raw(ctx.as_ptr()) };
+        // The reference of the newly created context is now owned by the returned `Ctx`.
+        Ok(Ctx {
+            ctx,
+            _marker: self._marker,
+        })
+    }
+
+    /// Forbid javascript code in this context from generating code from strings.
+    ///
+    /// Replaces the global `eval`, the `Function` constructor and the constructors of async and
+    /// generator functions reachable through the `constructor` property of function prototypes
+    /// with functions which throw an `EvalError`. Direct calls of `eval` are affected as well as
+    /// they only work with the original `eval` function.
+    ///
+    /// Code which was already compiled keeps working and the host can still evaluate code with
+    /// [`Ctx::eval`] and the other methods of `Ctx`. To remove the ability to evaluate code
+    /// entirely, including from the host, create the context without the
+    /// [`Eval`](crate::context::intrinsic::Eval) intrinsic instead. Other restrictions, like
+    /// whether `Atomics.wait` may block, are separate options of quickjs and not changed by this
+    /// function.
+    pub fn disable_eval(&self) -> Result<()> {
+        fn banned<'js>(ctx: &Ctx<'js>, name: &str) -> Result<Function<'js>> {
+            Function::new(ctx.clone(), |ctx: Ctx<'js>| -> Result<()> {
+                // Created with the intrinsic prototype, scripts may have replaced `EvalError`.
+                let message = "code generation from strings is disabled";
+                let (Ok(e) | Err(e)) =
+                    Exception::from_native(ctx.clone(), qjs::JS_EVAL_ERROR, message)
+                        .map(|error| error.throw());
+                Err(e)
+            })?
+            .with_name(name)
+        }
+
+        let globals = self.globals();
+        globals.set("eval", banned(self, "eval")?)?;
+
+        let mut prototypes = vec![Function::prototype(self.clone())];
+        for source in [
+            "(async function() {})",
+            "(function*() {})",
+            "(async function*() {})",
+        ] {
+            let func: Function = self.eval(source)?;
+            prototypes.extend(func.get_prototype()?);
+        }
+        for proto in prototypes {
+            let ctor: Object = proto.get("constructor")?;
+            let name: StdString = ctor.get("name")?;
+            let func = banned(self, &name)?.with_constructor(true);
+            // Also replaces `constructor` of the prototype so it can't be reached from functions.
+            unsafe {
+                qjs::JS_SetConstructor(self.as_ptr(), func.as_js_value(), proto.as_js_value())
+            };
+            if name == "Function" {
+                globals.set("Function", func)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Returns the last raised javascript exception, if there is no exception the javascript value `null` is returned.
     ///
     /// # Usage
@@ -264,6 +595,10 @@ impl<'js> Ctx<'js> {
     }
 
     /// Stringify a javascript value into its JSON representation
+    ///
+    /// Returns `None` for values which `JSON.stringify` turns into `undefined` like functions,
+    /// symbols and `undefined` itself. Use [`Ctx::json_stringify_replacer_space`] with an
+    /// undefined replacer to indent the output.
     pub fn json_stringify<V>(&self, value: V) -> Result<Option<String<'js>>>
     where
         V: IntoJs<'js>,
@@ -363,11 +698,191 @@ impl<'js> Ctx<'js> {
         })
     }
 
+    /// Execute pending jobs until the job queue is empty.
+    ///
+    /// Returns the number of jobs which were executed. Execution stops at the first job which
+    /// throws, the exception is then returned as [`Error::Exception`] and can be retrieved with
+    /// [`Ctx::catch`].
+    pub fn run_jobs(&self) -> Result<usize> {
+        let mut count = 0;
+        while self.run_job()? {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Execute a single pending job, returns false if there was no job to execute.
+    fn run_job(&self) -> Result<bool> {
+        let mut job_ctx = ptr::null_mut();
+        let res = unsafe {
+            let rt = qjs::JS_GetRuntime(self.ctx.as_ptr());
+            qjs::JS_ExecutePendingJob(rt, &mut job_ctx)
+        };
+        // Panics carried out of the job, or of the rejection tracker it triggered, are resumed
+        // here like in `Runtime::execute_pending_job`.
+        if let Some(panic) = unsafe { (*self.get_opaque()).panic.take() } {
+            panic::resume_unwind(panic)
+        }
+        if res < 0 {
+            // The exception is pending on the context the job ran in, which might not be
+            // this context.
+            if job_ctx != self.ctx.as_ptr() {
+                unsafe {
+                    let exception = qjs::JS_GetException(job_ctx);
+                    qjs::JS_Throw(self.ctx.as_ptr(), exception);
+                }
+            }
+            return Err(Error::Exception);
+        }
+        Ok(res != 0)
+    }
+
+    /// Execute pending jobs in time slices, calling `yield_` between slices.
+    ///
+    /// Jobs are executed until `slice` has passed, then `yield_` is called so the host can do
+    /// other work before the next slice starts. Returning `false` from `yield_` stops execution,
+    /// the remaining jobs stay in the queue and can be resumed later with another call. Returns
+    /// `true` once the job queue is empty and `false` if execution was stopped by `yield_`.
+    ///
+    /// Quickjs can't suspend running javascript code, a job always runs to completion, so a
+    /// slice ends after the job which is running when the time is up. Long synchronous code
+    /// can only be stopped by interrupting it with [`Runtime::set_interrupt_handler`] or
+    /// [`Runtime::set_deadline`], which aborts and doesn't resume it. Code which should share
+    /// time with the host therefore has to be split into jobs, for example by awaiting between
+    /// steps of an `async` function. At least one job is executed per slice.
+    ///
+    /// [`Runtime::set_interrupt_handler`]: crate::Runtime::set_interrupt_handler
+    /// [`Runtime::set_deadline`]: crate::Runtime::set_deadline
+    pub fn run_jobs_yielding<F>(&self, slice: Duration, mut yield_: F) -> Result<bool>
+    where
+        F: FnMut() -> bool,
+    {
+        loop {
+            let start = Instant::now();
+            loop {
+                if !self.run_job()? {
+                    return Ok(true);
+                }
+                if start.elapsed() >= slice {
+                    break;
+                }
+            }
+            let pending = unsafe { qjs::JS_IsJobPending(qjs::JS_GetRuntime(self.ctx.as_ptr())) };
+            if pending == 0 {
+                return Ok(true);
+            }
+            if !yield_() {
+                return Ok(false);
+            }
+        }
+    }
+
+    /// Evaluate a script and then execute the jobs it queued in time slices.
+    ///
+    /// The script itself is evaluated at once, afterwards the job queue is driven like with
+    /// [`Ctx::run_jobs_yielding`]. Returns the result of the script, usually a promise. If
+    /// execution was stopped by `yield_` the remaining jobs can be resumed with
+    /// [`Ctx::run_jobs_yielding`].
+    pub fn eval_yielding<V, S, F>(&self, source: S, slice: Duration, yield_: F) -> Result<V>
+    where
+        V: FromJs<'js>,
+        S: Into<Vec<u8>>,
+        F: FnMut() -> bool,
+    {
+        let res: Value = self.eval(source)?;
+        self.run_jobs_yielding(slice, yield_)?;
+        V::from_js(self, res)
+    }
+
     pub(crate) unsafe fn get_opaque(&self) -> *mut Opaque<'js> {
         let rt = qjs::JS_GetRuntime(self.ctx.as_ptr());
         qjs::JS_GetRuntimeOpaque(rt).cast::<Opaque>()
     }
 
+    /// Associate a rust value with this context.
+    ///
+    /// Works like [`Runtime::set_userdata`](crate::Runtime::set_userdata) but the value is only
+    /// visible from this context, every context of a runtime has its own values independent of
+    /// the values of the runtime and other contexts.
+    ///
+    /// The values are owned by an object kept in the non-enumerable, read-only
+    /// `__rquickjs_userdata` property of the global object and are dropped once the context is
+    /// freed, at the latest when the runtime is dropped. Storing the first value fails if that
+    /// property can't be defined, for example because the global object was made non-extensible.
+    pub fn set_userdata<T>(&self, data: T) -> Result<StdResult<(), T>>
+    where
+        T: Any + ParallelSend + ParallelSync,
+    {
+        let key = cstr!("__rquickjs_userdata");
+        unsafe {
+            let mut userdata = qjs::JS_GetContextOpaque(self.as_ptr()).cast::<UserDataMap>();
+            if userdata.is_null() {
+                let owner = ContextUserData::new();
+                userdata = owner.as_ptr();
+                let owner = Class::instance(self.clone(), owner)?;
+                let res = qjs::JS_DefinePropertyValueStr(
+                    self.as_ptr(),
+                    self.globals().as_js_value(),
+                    key.as_ptr(),
+                    owner.into_value().into_js_value(),
+                    0,
+                );
+                if res < 0 {
+                    return Err(self.raise_exception());
+                }
+                qjs::JS_SetContextOpaque(self.as_ptr(), userdata.cast());
+            }
+            Ok((*userdata).insert(data))
+        }
+    }
+
+    /// Returns the value of the given type associated with this context, if any.
+    pub fn userdata<T: Any>(&self) -> Option<&T> {
+        unsafe {
+            let userdata = qjs::JS_GetContextOpaque(self.as_ptr()).cast::<UserDataMap>();
+            if userdata.is_null() {
+                return None;
+            }
+            // Safety: Values are never removed or replaced and live as long as the context.
+            Some(&*(*userdata).get::<T>()?)
+        }
+    }
+
+    /// Returns the value of the given type associated with the runtime of this context, if any.
+    ///
+    /// Values are associated with the runtime with
+    /// [`Runtime::set_userdata`](crate::Runtime::set_userdata). Unlike
+    /// [`Runtime::userdata`](crate::Runtime::userdata) this doesn't lock the runtime, so it can be
+    /// used from within callbacks.
+    pub fn runtime_userdata<T: Any>(&self) -> Option<&T> {
+        let data = unsafe { (*self.get_opaque()).userdata.get::<T>()? };
+        // Safety: Values are never removed or replaced and live as long as the runtime.
+        Some(unsafe { &*data })
+    }
+
+    /// Replace the module loader of the runtime this context belongs to.
+    ///
+    /// Quickjs has a single module loader per runtime, so this is the same as
+    /// [`Runtime::set_loader`](crate::Runtime::set_loader) but usable from inside
+    /// [`Context::with`], for example to switch between loaders while the runtime is running.
+    ///
+    /// Modules which were already loaded stay loaded, importing them again reuses them without
+    /// calling the new loader. Module names are still resolved with the new resolver though, so
+    /// it must resolve the names of such modules to the same names as before. When called from
+    /// within a resolver or loader, the new loader takes over once the current import has been
+    /// resolved or loaded.
+    #[cfg(feature = "loader")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "loader")))]
+    pub fn set_loader<R, L>(&self, resolver: R, loader: L)
+    where
+        R: Resolver + 'static,
+        L: RawLoader + 'static,
+    {
+        unsafe {
+            LoaderHolder::new(resolver, loader).install(qjs::JS_GetRuntime(self.as_ptr()));
+        }
+    }
+
     /// Spawn future using configured async runtime
     #[cfg(feature = "futures")]
     #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "futures")))]
@@ -428,6 +943,125 @@ mod test {
         });
     }
 
+    #[test]
+    fn new_realm_globals() {
+        use crate::{test_with, Object, Value};
+
+        test_with(|ctx| {
+            let realm = ctx.new_realm().unwrap();
+            ctx.globals().set("shared", 1).unwrap();
+            assert!(realm
+                .globals()
+                .get::<_, Value>("shared")
+                .unwrap()
+                .is_undefined());
+
+            realm.eval::<(), _>("globalThis.local = [1, 2]").unwrap();
+            assert!(ctx
+                .globals()
+                .get::<_, Value>("local")
+                .unwrap()
+                .is_undefined());
+
+            let array: Object = realm.globals().get("local").unwrap();
+            let ctor: Value = ctx.globals().get("Array").unwrap();
+            assert!(!array.is_instance_of(ctor).unwrap());
+            let clone = array.as_value().structured_clone(&ctx).unwrap();
+            ctx.globals().set("local", clone).unwrap();
+            assert!(ctx.eval::<bool, _>("local instanceof Array").unwrap());
+        })
+    }
+
+    #[test]
+    fn eval_yielding() {
+        use crate::{test_with, StdString};
+        use std::time::Duration;
+
+        test_with(|ctx| {
+            let source = r#"
+                globalThis.log = [];
+                async function chain(name) {
+                    for (let i = 0; i < 3; i++) {
+                        log.push(name + i);
+                        await null;
+                    }
+                }
+                chain("a");
+                chain("b");
+            "#;
+            let log = || ctx.eval::<StdString, _>("log.join()").unwrap();
+
+            let mut slices = Vec::new();
+            ctx.eval_yielding::<(), _, _>(source, Duration::ZERO, || {
+                slices.push(log());
+                // Stop after the first slice, the remaining jobs stay queued.
+                false
+            })
+            .unwrap();
+            assert_eq!(slices.len(), 1);
+            assert_ne!(log(), "a0,b0,a1,b1,a2,b2");
+
+            let done = ctx
+                .run_jobs_yielding(Duration::ZERO, || {
+                    slices.push(log());
+                    true
+                })
+                .unwrap();
+            assert!(done);
+            assert_eq!(log(), "a0,b0,a1,b1,a2,b2");
+            // Both chains advanced in turns, one job per slice.
+            assert!(slices.len() >= 3);
+            assert!(slices.windows(2).all(|w| w[0].len() <= w[1].len()));
+            assert_eq!(slices[0], "a0,b0,a1");
+            assert!(slices.iter().any(|s| s == "a0,b0,a1,b1"));
+            assert!(ctx
+                .run_jobs_yielding(Duration::ZERO, || unreachable!())
+                .unwrap());
+        })
+    }
+
+    #[test]
+    fn disable_eval() {
+        use crate::{test_with, Error, Exception, Function, StdString};
+
+        test_with(|ctx| {
+            let compiled: Function = ctx.eval("(a) => a + 1").unwrap();
+            ctx.disable_eval().unwrap();
+
+            for source in [
+                "eval('1+1')",
+                "globalThis.eval('1+1')",
+                "new Function('return 1')()",
+                "(() => {}).constructor('return 1')()",
+                "(async () => {}).constructor('return 1')",
+                "(function*() {}).constructor('yield 1')",
+            ] {
+                let res = ctx.eval::<(), _>(source);
+                assert!(matches!(res, Err(Error::Exception)), "{}", source);
+                let exception = Exception::from_value(ctx.catch()).unwrap();
+                let name: StdString = exception.get("name").unwrap();
+                assert_eq!(name, "EvalError", "{}", source);
+            }
+
+            let res: bool = ctx
+                .eval(
+                    r#"
+                    const Intrinsic = EvalError;
+                    globalThis.EvalError = function () { throw new Error("replaced builtin used"); };
+                    try { eval("1") } catch (e) { e instanceof Intrinsic }
+                    "#,
+                )
+                .unwrap();
+            assert!(res);
+
+            assert_eq!(compiled.call::<_, i32>((1,)).unwrap(), 2);
+            assert!(ctx
+                .eval::<bool, _>("(() => {}) instanceof Function")
+                .unwrap());
+            assert_eq!(ctx.eval::<i32, _>("1 + 1").unwrap(), 2);
+        })
+    }
+
     #[test]
     fn eval() {
         use crate::{Context, Runtime};
@@ -452,6 +1086,49 @@ mod test {
         })
     }
 
+    #[test]
+    fn eval_with_name() {
+        use crate::{test_with, Error};
+
+        test_with(|ctx| {
+            let res = ctx.eval_with_name::<(), _, _>(
+                "function fail() { throw new Error('boom'); }\nfail();",
+                "scripts/failing.js",
+            );
+            assert!(matches!(res, Err(Error::Exception)));
+            let err = ctx.catch();
+            let err = err.as_exception().unwrap();
+            assert_eq!(err.message().as_deref(), Some("boom"));
+            let stack = err.stack().unwrap();
+            assert!(stack.contains("scripts/failing.js"), "{}", stack);
+        });
+    }
+
+    #[test]
+    fn eval_file_infers_module() {
+        use crate::test_with;
+
+        let dir = std::env::temp_dir().join(format!("rquickjs-eval-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("script.js");
+        let module = dir.join("module.mjs");
+        std::fs::write(&script, "var declared = 1; declared + 1").unwrap();
+        std::fs::write(&module, "var hidden = 1; globalThis.exported = hidden;").unwrap();
+
+        test_with(|ctx| {
+            let res: i32 = ctx.eval_file(&script).unwrap();
+            assert_eq!(res, 2);
+            assert!(ctx.globals().contains_key("declared").unwrap());
+
+            ctx.eval_file::<(), _>(&module).unwrap();
+            // Module scoped declarations don't end up on the global object.
+            assert!(!ctx.globals().contains_key("hidden").unwrap());
+            assert_eq!(ctx.globals().get::<_, i32>("exported").unwrap(), 1);
+        });
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     #[should_panic(expected = "'foo' is not defined")]
     fn eval_with_sloppy_code() {
@@ -532,6 +1209,120 @@ mod test {
         })
     }
 
+    #[test]
+    fn eval_with_options_strict_only_error() {
+        use crate::{context::EvalOptions, Context, Error, Runtime};
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+        ctx.with(|ctx| {
+            let source = "delete Object.prototype; 1";
+            let res = ctx.eval_with_options::<i32, _>(
+                source,
+                EvalOptions {
+                    strict: true,
+                    ..Default::default()
+                },
+            );
+            assert!(matches!(res, Err(Error::Exception)));
+            let _ = ctx.catch();
+
+            let res = ctx
+                .eval_with_options::<i32, _>(
+                    source,
+                    EvalOptions {
+                        strict: false,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            assert_eq!(res, 1);
+        })
+    }
+
+    #[test]
+    fn eval_with_options_compile_only() {
+        use crate::{context::EvalOptions, Context, Runtime, Type, Value};
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+        ctx.with(|ctx| {
+            let options = || EvalOptions {
+                compile_only: true,
+                ..Default::default()
+            };
+            let script: Value = ctx
+                .eval_with_options("globalThis.ran = true;", options())
+                .unwrap();
+            assert!(!script.is_undefined());
+            assert!(!ctx.globals().contains_key("ran").unwrap());
+
+            let module: Value = ctx
+                .eval_with_options(
+                    "export const ran = true;",
+                    EvalOptions {
+                        module: true,
+                        ..options()
+                    },
+                )
+                .unwrap();
+            assert_eq!(module.type_of(), Type::Module);
+        })
+    }
+
+    #[test]
+    fn bytecode() {
+        use crate::{context::EvalOptions, Context, Runtime};
+
+        let runtime = Runtime::new().unwrap();
+        let bytecode = Context::full(&runtime)
+            .unwrap()
+            .with(|ctx| {
+                ctx.compile_to_bytecode_with_options(
+                    "globalThis.result = [1, 2, 3].map((x) => x * 2).join(); export const x = 1;",
+                    EvalOptions {
+                        module: true,
+                        ..Default::default()
+                    },
+                )
+            })
+            .unwrap();
+
+        let ctx = Context::full(&runtime).unwrap();
+        ctx.with(|ctx| {
+            assert!(!ctx.globals().contains_key("result").unwrap());
+            unsafe { ctx.eval_bytecode::<()>(&bytecode) }.unwrap();
+            let res: String = ctx.globals().get("result").unwrap();
+            assert_eq!(res, "2,4,6");
+
+            let script = ctx.compile_to_bytecode("1 + 2").unwrap();
+            assert_eq!(unsafe { ctx.eval_bytecode::<i32>(&script) }.unwrap(), 3);
+        });
+    }
+
+    #[test]
+    fn bytecode_version_mismatch() {
+        use crate::{Context, Error, Runtime};
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+        ctx.with(|ctx| {
+            // Both are rejected by the version check before quickjs reads them.
+            let mut bytecode = b"rquickjs".to_vec();
+            bytecode.push(10);
+            bytecode.extend_from_slice(b"1999-01-01");
+            bytecode.extend_from_slice(&[0, 1, 2, 3]);
+            let res = unsafe { ctx.eval_bytecode::<()>(&bytecode) };
+            assert!(matches!(
+                res,
+                Err(Error::Bytecode { found: Some(ref version), .. }) if version == "1999-01-01"
+            ));
+
+            let res = unsafe { ctx.eval_bytecode::<()>(b"not bytecode") };
+            assert!(matches!(res, Err(Error::Bytecode { found: None, .. })));
+        });
+    }
+
     #[test]
     fn json_parse() {
         use crate::{Array, Context, Object, Runtime};
@@ -604,4 +1395,183 @@ mod test {
             assert_eq!(str, r#"{"a":{"b":1,"c":true},"d":[0,"foo"]}"#);
         })
     }
+
+    #[test]
+    fn json_stringify_indent_and_undefined() {
+        use crate::{Context, Function, Runtime, Undefined, Value};
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+        ctx.with(|ctx| {
+            let value = ctx.json_parse(r#"{"a":[1,2]}"#).unwrap();
+            let str = ctx
+                .json_stringify(value.clone())
+                .unwrap()
+                .unwrap()
+                .to_string()
+                .unwrap();
+            assert_eq!(str, r#"{"a":[1,2]}"#);
+
+            let str = ctx
+                .json_stringify_replacer_space(value, Undefined, 2)
+                .unwrap()
+                .unwrap()
+                .to_string()
+                .unwrap();
+            assert_eq!(str, "{\n  \"a\": [\n    1,\n    2\n  ]\n}");
+
+            let func: Function = ctx.eval("() => {}").unwrap();
+            assert!(ctx.json_stringify(func).unwrap().is_none());
+            assert!(ctx
+                .json_stringify(Value::new_undefined(ctx.clone()))
+                .unwrap()
+                .is_none());
+        })
+    }
+
+    #[test]
+    fn run_jobs() {
+        use crate::{Context, Runtime};
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+        ctx.with(|ctx| {
+            ctx.eval::<(), _>(
+                r#"
+                globalThis.result = 0;
+                Promise.resolve(1)
+                    .then((v) => v + 1)
+                    .then((v) => { result = v * 10; });
+            "#,
+            )
+            .unwrap();
+            assert_eq!(ctx.globals().get::<_, i32>("result").unwrap(), 0);
+
+            assert!(ctx.run_jobs().unwrap() >= 2);
+            assert_eq!(ctx.globals().get::<_, i32>("result").unwrap(), 20);
+            assert_eq!(ctx.run_jobs().unwrap(), 0);
+        })
+    }
+
+    #[test]
+    fn run_jobs_resumes_panic() {
+        use crate::{Context, Function, Runtime};
+        use std::panic::{self, AssertUnwindSafe};
+
+        let runtime = Runtime::new().unwrap();
+        runtime.set_panic_to_exception(false);
+        let ctx = Context::full(&runtime).unwrap();
+        ctx.with(|ctx| {
+            let f = Function::new(ctx.clone(), || -> i32 { panic!("job panic") }).unwrap();
+            ctx.globals().set("fail", f).unwrap();
+            // The rejection of the promise of the job is caught, only the panic reports it.
+            ctx.eval::<(), _>("Promise.resolve().then(fail).catch(() => {})")
+                .unwrap();
+            let res = panic::catch_unwind(AssertUnwindSafe(|| ctx.run_jobs()));
+            let panic = res.unwrap_err();
+            assert_eq!(panic.downcast_ref::<&str>(), Some(&"job panic"));
+            assert_eq!(ctx.run_jobs().unwrap(), 1);
+        })
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn eval_module_promise() {
+        use crate::{Context, Exception, Object, Runtime};
+        use std::{cell::RefCell, rc::Rc};
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+        let value = Rc::new(RefCell::new(None));
+        let error = Rc::new(RefCell::new(None));
+        let syntax_error = Rc::new(RefCell::new(None));
+        ctx.with(|ctx| {
+            let promise = ctx
+                .eval_module_promise(
+                    "value",
+                    r#"
+                    export let value = 1;
+                    Promise.resolve(2).then((v) => { value = v; });
+                "#,
+                )
+                .unwrap();
+            let value = value.clone();
+            promise
+                .then::<_, _, ()>(move |ns: Object| {
+                    *value.borrow_mut() = ns.get::<_, i32>("value").ok()
+                })
+                .unwrap();
+
+            let promise = ctx
+                .eval_module_promise("failing", "throw new Error('init failed');")
+                .unwrap();
+            let error = error.clone();
+            promise
+                .catch::<_, _, ()>(move |e: Exception| *error.borrow_mut() = e.message())
+                .unwrap();
+
+            // Top-level await is a syntax error in the bundled quickjs.
+            let promise = ctx
+                .eval_module_promise("awaiting", "export const value = await 1;")
+                .unwrap();
+            let syntax_error = syntax_error.clone();
+            promise
+                .catch::<_, _, ()>(move |e: Exception| {
+                    *syntax_error.borrow_mut() = e.as_object().get::<_, String>("name").ok()
+                })
+                .unwrap();
+
+            ctx.run_jobs().unwrap();
+        });
+        assert_eq!(*value.borrow(), Some(2));
+        assert_eq!(error.borrow().as_deref(), Some("init failed"));
+        assert_eq!(syntax_error.borrow().as_deref(), Some("SyntaxError"));
+    }
+
+    #[test]
+    fn context_userdata() {
+        use crate::{Context, Ctx, Function, Runtime};
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        struct Tenant(u32);
+        struct Tracked(Arc<AtomicUsize>);
+
+        impl Drop for Tracked {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let rt = Runtime::new().unwrap();
+        rt.set_userdata(Tenant(0)).ok().unwrap();
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let contexts = [1, 2].map(|id| {
+            let ctx = Context::full(&rt).unwrap();
+            ctx.with(|ctx| {
+                assert!(ctx.userdata::<Tenant>().is_none());
+                assert!(ctx.set_userdata(Tenant(id)).unwrap().is_ok());
+                assert!(ctx.set_userdata(Tracked(dropped.clone())).unwrap().is_ok());
+                assert!(ctx.set_userdata(Tenant(0)).unwrap().is_err());
+                let tenant =
+                    Function::new(ctx.clone(), |ctx: Ctx| ctx.userdata::<Tenant>().unwrap().0)
+                        .unwrap();
+                ctx.globals().set("tenant", tenant).unwrap();
+            });
+            ctx
+        });
+
+        for (id, ctx) in [1, 2].into_iter().zip(contexts.iter()) {
+            ctx.with(|ctx| {
+                assert_eq!(ctx.eval::<u32, _>("tenant()").unwrap(), id);
+                assert_eq!(ctx.runtime_userdata::<Tenant>().unwrap().0, 0);
+            });
+        }
+        assert_eq!(dropped.load(Ordering::SeqCst), 0);
+        drop(contexts);
+        drop(rt);
+        assert_eq!(dropped.load(Ordering::SeqCst), 2);
+    }
 }
@@ -0,0 +1,155 @@
+use std::collections::HashSet;
+
+use crate::{
+    object::{Descriptor, Filter},
+    qjs, Atom, Ctx, Outlive, Result,
+};
+
+/// The own properties of the global object at some point, created with
+/// [`Ctx::globals_snapshot`].
+#[derive(Debug, Clone)]
+pub struct GlobalsSnapshot<'js> {
+    properties: Vec<(Atom<'js>, Descriptor<'js>)>,
+}
+
+unsafe impl<'js> Outlive<'js> for GlobalsSnapshot<'js> {
+    type Target<'to> = GlobalsSnapshot<'to>;
+}
+
+impl<'js> Ctx<'js> {
+    /// Capture the own properties of the global object, including non-enumerable and symbol
+    /// keyed ones, so they can be restored later with [`Ctx::restore_globals`].
+    ///
+    /// The snapshot is shallow: it records which value or accessor each global has, but not the
+    /// contents of those values, so changes like `Math.answer = 42` are not undone.
+    pub fn globals_snapshot(&self) -> Result<GlobalsSnapshot<'js>> {
+        let globals = self.globals();
+        let mut properties = Vec::new();
+        for key in globals.own_keys::<Atom>(Filter::new().string().symbol()) {
+            let key = key?;
+            // Skip keys removed by a getter or proxy while iterating.
+            if let Some(desc) = globals.own_descriptor(&key)? {
+                properties.push((key, desc));
+            }
+        }
+        Ok(GlobalsSnapshot { properties })
+    }
+
+    /// Reset the global object to the state captured in a snapshot.
+    ///
+    /// Globals which were added after the snapshot was taken are removed and globals which were
+    /// overwritten or removed are defined again with their original value and attributes.
+    /// Properties which are not configurable can't be removed or redefined, the keys of those
+    /// which could not be restored are returned.
+    ///
+    /// Note that top-level `let`, `const` and `class` declarations are not properties of the
+    /// global object and are therefore not affected.
+    pub fn restore_globals(&self, snapshot: &GlobalsSnapshot<'js>) -> Result<Vec<Atom<'js>>> {
+        let globals = self.globals();
+        let known = snapshot
+            .properties
+            .iter()
+            .map(|(key, _)| key.atom)
+            .collect::<HashSet<_>>();
+        let mut failed = Vec::new();
+
+        let current = globals
+            .own_keys::<Atom>(Filter::new().string().symbol())
+            .collect::<Result<Vec<_>>>()?;
+        for key in current {
+            if known.contains(&key.atom) {
+                continue;
+            }
+            let res = unsafe {
+                qjs::JS_DeleteProperty(self.as_ptr(), globals.as_js_value(), key.atom, 0)
+            };
+            if res < 0 {
+                return Err(self.raise_exception());
+            }
+            if res == 0 {
+                failed.push(key);
+            }
+        }
+
+        for (key, desc) in &snapshot.properties {
+            let mut flags = desc.flags
+                & (qjs::JS_PROP_CONFIGURABLE | qjs::JS_PROP_ENUMERABLE) as qjs::c_int
+                | (qjs::JS_PROP_HAS_CONFIGURABLE | qjs::JS_PROP_HAS_ENUMERABLE) as qjs::c_int;
+            if desc.is_accessor() {
+                flags |= (qjs::JS_PROP_HAS_GET | qjs::JS_PROP_HAS_SET) as qjs::c_int;
+            } else {
+                flags |= desc.flags & qjs::JS_PROP_WRITABLE as qjs::c_int
+                    | (qjs::JS_PROP_HAS_VALUE | qjs::JS_PROP_HAS_WRITABLE) as qjs::c_int;
+            }
+            let res = unsafe {
+                qjs::JS_DefineProperty(
+                    self.as_ptr(),
+                    globals.as_js_value(),
+                    key.atom,
+                    desc.value.as_js_value(),
+                    desc.getter.as_js_value(),
+                    desc.setter.as_js_value(),
+                    flags,
+                )
+            };
+            if res < 0 {
+                return Err(self.raise_exception());
+            }
+            if res == 0 {
+                failed.push(key.clone());
+            }
+        }
+
+        Ok(failed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn restore_globals() {
+        test_with(|ctx| {
+            let snapshot = ctx.globals_snapshot().unwrap();
+            ctx.eval::<(), _>(
+                r#"
+                globalThis.added = 1;
+                globalThis.Math = "overwritten";
+                delete globalThis.JSON;
+                Object.defineProperty(globalThis, "pinned", { value: 2, configurable: false });
+            "#,
+            )
+            .unwrap();
+
+            let failed = ctx.restore_globals(&snapshot).unwrap();
+            let failed = failed
+                .iter()
+                .map(|key| key.to_string().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(failed, ["pinned"]);
+
+            let globals = ctx.globals();
+            assert!(!globals.contains_key("added").unwrap());
+            assert!(ctx.eval::<bool, _>("typeof added === 'undefined'").unwrap());
+            assert!(globals.get::<_, Value>("Math").unwrap().is_object());
+            assert_eq!(
+                ctx.eval::<StdString, _>("JSON.stringify([1])").unwrap(),
+                "[1]"
+            );
+        })
+    }
+
+    #[test]
+    fn persistent_snapshot() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        let snapshot = ctx.with(|ctx| Persistent::save(&ctx, ctx.globals_snapshot().unwrap()));
+        ctx.with(|ctx| ctx.globals().set("added", 1).unwrap());
+        ctx.with(|ctx| {
+            let snapshot = snapshot.restore(&ctx).unwrap();
+            assert!(ctx.restore_globals(&snapshot).unwrap().is_empty());
+            assert!(!ctx.globals().contains_key("added").unwrap());
+        });
+    }
+}
@@ -0,0 +1,478 @@
+use std::io::{ErrorKind, Read};
+
+use crate::{qjs, Array, Atom, Ctx, Error, Exception, Object, Result, Value};
+
+/// The number of bytes read from the reader at once.
+const BUFFER_SIZE: usize = 16 * 1024;
+
+impl<'js> Ctx<'js> {
+    /// Parse json from a reader into a javascript value.
+    ///
+    /// Unlike [`Ctx::json_parse`], which requires the whole text in memory and copies it once
+    /// more before handing it to quickjs, the input is read in chunks of a fixed size and the
+    /// javascript values are built while reading. Besides the resulting value only the chunk
+    /// buffer and the longest single string or number in the input have to be kept in memory,
+    /// which makes a difference for inputs of many megabytes. Parsing is still done in one go,
+    /// the call blocks until the whole input was read.
+    ///
+    /// The input is parsed according to the strict JSON grammar, with the same result as
+    /// `JSON.parse` without a reviver. Syntax errors are thrown as a javascript `SyntaxError`
+    /// with the byte offset of the error and returned as [`Error::Exception`], errors from the
+    /// reader are returned as [`Error::Io`]. Nested arrays and objects are tracked on the heap,
+    /// so deeply nested input doesn't overflow the stack.
+    pub fn json_parse_reader<R: Read>(&self, reader: R) -> Result<Value<'js>> {
+        JsonReader::new(self.clone(), reader).parse()
+    }
+}
+
+enum Frame<'js> {
+    Array(Array<'js>, u32),
+    Object(Object<'js>, Atom<'js>),
+}
+
+struct JsonReader<'js, R> {
+    ctx: Ctx<'js>,
+    reader: R,
+    buffer: Box<[u8]>,
+    pos: usize,
+    len: usize,
+    /// The number of bytes read before the current buffer.
+    offset: usize,
+    /// The contents of the string currently being parsed.
+    text: Vec<u8>,
+}
+
+impl<'js, R: Read> JsonReader<'js, R> {
+    fn new(ctx: Ctx<'js>, reader: R) -> Self {
+        JsonReader {
+            ctx,
+            reader,
+            buffer: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            pos: 0,
+            len: 0,
+            offset: 0,
+            text: Vec::new(),
+        }
+    }
+
+    fn parse(mut self) -> Result<Value<'js>> {
+        let mut stack = Vec::new();
+        'value: loop {
+            let mut value = match self.next_token()? {
+                b'[' => {
+                    let array = Array::new(self.ctx.clone())?;
+                    if self.peek_token()? == Some(b']') {
+                        self.pos += 1;
+                        array.into_value()
+                    } else {
+                        stack.push(Frame::Array(array, 0));
+                        continue 'value;
+                    }
+                }
+                b'{' => {
+                    let object = Object::new(self.ctx.clone())?;
+                    if self.peek_token()? == Some(b'}') {
+                        self.pos += 1;
+                        object.into_value()
+                    } else {
+                        let key = self.key()?;
+                        stack.push(Frame::Object(object, key));
+                        continue 'value;
+                    }
+                }
+                b'"' => self.string()?,
+                b't' => self.literal(b"rue", Value::new_bool(self.ctx.clone(), true))?,
+                b'f' => self.literal(b"alse", Value::new_bool(self.ctx.clone(), false))?,
+                b'n' => self.literal(b"ull", Value::new_null(self.ctx.clone()))?,
+                byte @ (b'-' | b'0'..=b'9') => self.number(byte)?,
+                byte => return Err(self.unexpected(byte)),
+            };
+
+            // Add the finished value to its parents, finishing them as well where they end.
+            loop {
+                match stack.last_mut() {
+                    None => {
+                        if let Some(byte) = self.peek_token()? {
+                            self.pos += 1;
+                            return Err(self.unexpected(byte));
+                        }
+                        return Ok(value);
+                    }
+                    Some(Frame::Array(array, index)) => {
+                        unsafe {
+                            let res = qjs::JS_DefinePropertyValueUint32(
+                                self.ctx.as_ptr(),
+                                array.as_js_value(),
+                                *index,
+                                value.into_js_value(),
+                                qjs::JS_PROP_C_W_E as _,
+                            );
+                            if res < 0 {
+                                return Err(self.ctx.raise_exception());
+                            }
+                        }
+                        *index += 1;
+                        match self.next_token()? {
+                            b',' => continue 'value,
+                            b']' => {}
+                            byte => return Err(self.unexpected(byte)),
+                        }
+                    }
+                    Some(Frame::Object(object, key)) => {
+                        unsafe {
+                            let res = qjs::JS_DefinePropertyValue(
+                                self.ctx.as_ptr(),
+                                object.as_js_value(),
+                                key.atom,
+                                value.into_js_value(),
+                                qjs::JS_PROP_C_W_E as _,
+                            );
+                            if res < 0 {
+                                return Err(self.ctx.raise_exception());
+                            }
+                        }
+                        match self.next_token()? {
+                            b',' => {
+                                *key = self.key()?;
+                                continue 'value;
+                            }
+                            b'}' => {}
+                            byte => return Err(self.unexpected(byte)),
+                        }
+                    }
+                }
+                value = match stack.pop() {
+                    Some(Frame::Array(array, _)) => array.into_value(),
+                    Some(Frame::Object(object, _)) => object.into_value(),
+                    None => unreachable!(),
+                };
+            }
+        }
+    }
+
+    fn fill(&mut self) -> Result<bool> {
+        loop {
+            match self.reader.read(&mut self.buffer) {
+                Ok(0) => return Ok(false),
+                Ok(len) => {
+                    self.offset += self.len;
+                    self.pos = 0;
+                    self.len = len;
+                    return Ok(true);
+                }
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>> {
+        if self.pos == self.len && !self.fill()? {
+            return Ok(None);
+        }
+        Ok(Some(self.buffer[self.pos]))
+    }
+
+    fn next(&mut self) -> Result<u8> {
+        let byte = self
+            .peek()?
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    /// Skip whitespace and return the next byte without consuming it.
+    fn peek_token(&mut self) -> Result<Option<u8>> {
+        while let Some(byte) = self.peek()? {
+            if !matches!(byte, b' ' | b'\t' | b'\n' | b'\r') {
+                return Ok(Some(byte));
+            }
+            self.pos += 1;
+        }
+        Ok(None)
+    }
+
+    /// Skip whitespace and consume the next byte.
+    fn next_token(&mut self) -> Result<u8> {
+        self.peek_token()?;
+        self.next()
+    }
+
+    fn position(&self) -> usize {
+        self.offset + self.pos
+    }
+
+    fn error(&self, message: &str) -> Error {
+        let message = format!("{} at position {}", message, self.position());
+        Exception::throw_syntax(&self.ctx, &message)
+    }
+
+    /// Returns an error for the byte which was just consumed.
+    fn unexpected(&mut self, byte: u8) -> Error {
+        // Report the position of the unexpected byte itself.
+        self.pos -= 1;
+        let message = if byte.is_ascii_graphic() {
+            format!("unexpected character '{}'", byte as char)
+        } else {
+            format!("unexpected byte 0x{:02x}", byte)
+        };
+        let error = self.error(&message);
+        self.pos += 1;
+        error
+    }
+
+    fn literal(&mut self, rest: &[u8], value: Value<'js>) -> Result<Value<'js>> {
+        for expected in rest {
+            let byte = self.next()?;
+            if byte != *expected {
+                return Err(self.unexpected(byte));
+            }
+        }
+        Ok(value)
+    }
+
+    fn number(&mut self, first: u8) -> Result<Value<'js>> {
+        self.text.clear();
+        self.text.push(first);
+        while let Some(byte) = self.peek()? {
+            if !matches!(byte, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+                break;
+            }
+            self.text.push(byte);
+            self.pos += 1;
+        }
+        if !is_json_number(&self.text) {
+            return Err(self.error("invalid number"));
+        }
+        // The text only consists of ascii characters at this point.
+        let text = std::str::from_utf8(&self.text).unwrap();
+        if !text.contains(['.', 'e', 'E']) && text != "-0" {
+            if let Ok(int) = text.parse::<i32>() {
+                return Ok(Value::new_int(self.ctx.clone(), int));
+            }
+        }
+        let float = text.parse::<f64>().unwrap();
+        Ok(Value::new_float(self.ctx.clone(), float))
+    }
+
+    fn key(&mut self) -> Result<Atom<'js>> {
+        match self.next_token()? {
+            b'"' => {}
+            byte => return Err(self.unexpected(byte)),
+        }
+        let key = self.string()?;
+        match self.next_token()? {
+            b':' => {}
+            byte => return Err(self.unexpected(byte)),
+        }
+        Atom::from_value(self.ctx.clone(), &key)
+    }
+
+    fn string(&mut self) -> Result<Value<'js>> {
+        self.text.clear();
+        // The start of the bytes which were copied from the input since the last escape.
+        let mut run = 0;
+        loop {
+            match self.next()? {
+                b'"' => break,
+                b'\\' => {
+                    self.check_utf8(run)?;
+                    self.escape()?;
+                    run = self.text.len();
+                }
+                0..=0x1f => return Err(self.error("control character in string")),
+                byte => self.text.push(byte),
+            }
+        }
+        self.check_utf8(run)?;
+        unsafe {
+            let value = qjs::JS_NewStringLen(
+                self.ctx.as_ptr(),
+                self.text.as_ptr() as _,
+                self.text.len() as _,
+            );
+            let value = self.ctx.handle_exception(value)?;
+            Ok(Value::from_js_value(self.ctx.clone(), value))
+        }
+    }
+
+    fn check_utf8(&self, run: usize) -> Result<()> {
+        // Runs are split at ascii characters so they must be valid on their own.
+        if std::str::from_utf8(&self.text[run..]).is_err() {
+            return Err(self.error("invalid UTF-8 in string"));
+        }
+        Ok(())
+    }
+
+    /// Parse an escape sequence following a backslash.
+    fn escape(&mut self) -> Result<()> {
+        let byte = match self.next()? {
+            b'"' => b'"',
+            b'\\' => b'\\',
+            b'/' => b'/',
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'u' => {
+                let mut unit = self.hex()?;
+                while (0xD800..0xDC00).contains(&unit) && self.peek()? == Some(b'\\') {
+                    self.pos += 1;
+                    if self.peek()? != Some(b'u') {
+                        // A lone high surrogate followed by some other escape.
+                        push_code_point(&mut self.text, unit);
+                        return self.escape();
+                    }
+                    self.pos += 1;
+                    let low = self.hex()?;
+                    if (0xDC00..0xE000).contains(&low) {
+                        unit = 0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00);
+                        break;
+                    }
+                    push_code_point(&mut self.text, unit);
+                    unit = low;
+                }
+                // Lone surrogates are kept like in javascript strings.
+                push_code_point(&mut self.text, unit);
+                return Ok(());
+            }
+            byte => return Err(self.unexpected(byte)),
+        };
+        self.text.push(byte);
+        Ok(())
+    }
+
+    fn hex(&mut self) -> Result<u32> {
+        let mut unit = 0;
+        for _ in 0..4 {
+            let byte = self.next()?;
+            let digit = (byte as char)
+                .to_digit(16)
+                .ok_or_else(|| self.unexpected(byte))?;
+            unit = unit * 16 + digit;
+        }
+        Ok(unit)
+    }
+}
+
+/// Encode a code point like UTF-8 but allowing surrogates, which quickjs accepts when creating
+/// strings.
+fn push_code_point(text: &mut Vec<u8>, code: u32) {
+    if code < 0x80 {
+        text.push(code as u8);
+    } else if code < 0x800 {
+        text.extend_from_slice(&[0xC0 | (code >> 6) as u8, 0x80 | (code & 0x3F) as u8]);
+    } else if code < 0x10000 {
+        text.extend_from_slice(&[
+            0xE0 | (code >> 12) as u8,
+            0x80 | ((code >> 6) & 0x3F) as u8,
+            0x80 | (code & 0x3F) as u8,
+        ]);
+    } else {
+        text.extend_from_slice(&[
+            0xF0 | (code >> 18) as u8,
+            0x80 | ((code >> 12) & 0x3F) as u8,
+            0x80 | ((code >> 6) & 0x3F) as u8,
+            0x80 | (code & 0x3F) as u8,
+        ]);
+    }
+}
+
+/// Check a number against the JSON grammar, which is stricter than rust's float parsing.
+fn is_json_number(text: &[u8]) -> bool {
+    let digits = |i: &mut usize| {
+        let start = *i;
+        while matches!(text.get(*i), Some(b'0'..=b'9')) {
+            *i += 1;
+        }
+        *i > start
+    };
+    let mut i = 0;
+    if text.get(i) == Some(&b'-') {
+        i += 1;
+    }
+    match text.get(i) {
+        Some(b'0') => i += 1,
+        Some(b'1'..=b'9') => {
+            digits(&mut i);
+        }
+        _ => return false,
+    }
+    if text.get(i) == Some(&b'.') {
+        i += 1;
+        if !digits(&mut i) {
+            return false;
+        }
+    }
+    if matches!(text.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(text.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        if !digits(&mut i) {
+            return false;
+        }
+    }
+    i == text.len()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn large_array() {
+        test_with(|ctx| {
+            let mut json = StdString::from("[");
+            for i in 0..50_000 {
+                if i > 0 {
+                    json.push(',');
+                }
+                json.push_str(&format!(
+                    r#"{{ "id": {}, "name": "item \"{}\"", "tags": [true, null, -1.5e3] }}"#,
+                    i, i
+                ));
+            }
+            json.push(']');
+
+            let value = ctx.json_parse_reader(Cursor::new(json.as_bytes())).unwrap();
+            let array = value.into_array().unwrap();
+            assert_eq!(array.len(), 50_000);
+            let last: Object = array.get(49_999).unwrap();
+            assert_eq!(last.get::<_, i32>("id").unwrap(), 49_999);
+            assert_eq!(last.get::<_, StdString>("name").unwrap(), "item \"49999\"");
+            let expected = ctx.json_parse(json).unwrap();
+            assert!(array.into_value().deep_equal(&expected).unwrap());
+        });
+    }
+
+    #[test]
+    fn strings_and_errors() {
+        test_with(|ctx| {
+            let json = r#"{ "__proto__": 1, "s": "é😀\n\ud800", "z": -0 }"#;
+            let value = ctx.json_parse_reader(json.as_bytes()).unwrap();
+            ctx.globals().set("v", value).unwrap();
+            let res: bool = ctx
+                .eval(
+                    r#"
+                    Object.getPrototypeOf(v) === Object.prototype &&
+                    Object.keys(v).join() === "__proto__,s,z" &&
+                    v.s === "é😀\n\ud800" &&
+                    Object.is(v.z, -0)
+                "#,
+                )
+                .unwrap();
+            assert!(res);
+
+            for json in ["[1,]", "{\"a\" 1}", "01", "[1] 2", "\"abc", "tru"] {
+                let res = ctx.json_parse_reader(json.as_bytes());
+                assert!(matches!(res, Err(Error::Exception)), "{}", json);
+                let err = ctx.catch();
+                let err = err.as_exception().unwrap();
+                assert!(err.message().unwrap().contains("at position"), "{}", json);
+            }
+        });
+    }
+}
@@ -58,12 +58,14 @@ pub use context::AsyncContext;
 pub use context::MultiWith;
 pub use context::{Context, Ctx};
 mod persistent;
+mod userdata;
 mod value;
-pub use persistent::{Outlive, Persistent};
+pub use persistent::{Outlive, Persistent, WeakPersistent};
 pub use value::{
-    array, atom, convert, function, module, object, Array, Atom, BigInt, Exception, FromAtom,
-    FromJs, Function, IntoAtom, IntoJs, Module, Null, Object, String, Symbol, Type, Undefined,
-    Value,
+    array, atom, convert, function, map, module, object, proxy, set, Array, Atom, BigInt, Date,
+    Exception, ExceptionKind, FromAtom, FromJs, Function, IntoAtom, IntoException, IntoJs, JsType,
+    Map, Module, Null, Object, Proxy, ProxyHandler, RegExp, Set, String, StringRef, Symbol, Type,
+    Undefined, Value, WeakRef,
 };
 
 pub mod class;
@@ -87,12 +89,24 @@ pub mod allocator;
 #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "loader")))]
 pub mod loader;
 
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "serde")))]
+pub mod serde;
+
+#[cfg(feature = "console")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "console")))]
+pub mod console;
+
+#[cfg(feature = "timers")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "timers")))]
+pub mod timers;
+
 pub mod prelude {
     //! A group of often used types.
     #[cfg(feature = "multi-ctx")]
     pub use crate::context::MultiWith;
     pub use crate::{
-        convert::{Coerced, FromAtom, FromJs, IntoAtom, IntoJs, IteratorJs, List},
+        convert::{Coerced, FromAtom, FromJs, IntoAtom, IntoJs, IteratorJs, List, Throw},
         function::{
             Exhaustive, Flat, Func, FuncArg, IntoArg, IntoArgs, MutFn, OnceFn, Opt, Rest, This,
         },
@@ -2,7 +2,7 @@
 
 use std::{ffi::CStr, ptr};
 
-use crate::{module::ModuleData, qjs, Ctx, Module, Result};
+use crate::{module::ModuleData, qjs, runtime::raw::Opaque, Ctx, Module, Object, Result};
 
 mod builtin_resolver;
 pub use builtin_resolver::BuiltinResolver;
@@ -16,6 +16,12 @@ pub use script_loader::ScriptLoader;
 mod builtin_loader;
 pub use builtin_loader::BuiltinLoader;
 
+mod caching_loader;
+pub use caching_loader::CachingFileLoader;
+
+mod module_cache;
+pub use module_cache::ModuleCache;
+
 mod module_loader;
 pub use module_loader::ModuleLoader;
 
@@ -40,7 +46,19 @@ pub type Bundle = bundle::Bundle<bundle::ScaBundleData<&'static [u8]>>;
 
 mod util;
 
+/// The type of the hook which populates the `import.meta` object of loaded modules.
+#[cfg(not(feature = "parallel"))]
+pub type ImportMetaHook = Box<dyn for<'js> Fn(&Module<'js>, &Object<'js>) -> Result<()> + 'static>;
+/// The type of the hook which populates the `import.meta` object of loaded modules.
+#[cfg(feature = "parallel")]
+pub type ImportMetaHook =
+    Box<dyn for<'js> Fn(&Module<'js>, &Object<'js>) -> Result<()> + Send + 'static>;
+
 /// Module resolver interface
+///
+/// The configured resolver and loader are consulted for both static `import` declarations and
+/// dynamic `import()` expressions. For the latter an error from either of them rejects the
+/// promise returned by `import()` with the thrown error.
 #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "loader")))]
 pub trait Resolver {
     /// Normalize module name
@@ -123,6 +141,37 @@ impl LoaderHolder {
         })))
     }
 
+    /// Make the loader the module loader of the runtime.
+    ///
+    /// When called from within a loader callback the current loader is still in use, the new
+    /// loader then only replaces it once the callback returned.
+    ///
+    /// # Safety
+    /// The runtime must be a runtime created by rquickjs which is currently locked.
+    pub(crate) unsafe fn install(self, rt: *mut qjs::JSRuntime) {
+        let opaque = &mut *(qjs::JS_GetRuntimeOpaque(rt) as *mut Opaque);
+        if opaque.loader_depth > 0 {
+            opaque.pending_loader = Some(self);
+            return;
+        }
+        self.set_to_runtime(rt);
+        opaque.loader = Some(self);
+    }
+
+    unsafe fn enter(ctx: &Ctx) {
+        (*ctx.get_opaque()).loader_depth += 1;
+    }
+
+    unsafe fn leave(ctx: &Ctx) {
+        let opaque = &mut *ctx.get_opaque();
+        opaque.loader_depth -= 1;
+        if opaque.loader_depth == 0 {
+            if let Some(loader) = opaque.pending_loader.take() {
+                loader.install(qjs::JS_GetRuntime(ctx.as_ptr()));
+            }
+        }
+    }
+
     pub(crate) fn set_to_runtime(&self, rt: *mut qjs::JSRuntime) {
         unsafe {
             qjs::JS_SetModuleLoaderFunc(
@@ -165,10 +214,13 @@ impl LoaderHolder {
         let name = CStr::from_ptr(name);
         let loader = &mut *(opaque as *mut LoaderOpaque);
 
-        Self::normalize(loader, &ctx, base, name).unwrap_or_else(|error| {
+        Self::enter(&ctx);
+        let res = Self::normalize(loader, &ctx, base, name).unwrap_or_else(|error| {
             error.throw(&ctx);
             ptr::null_mut()
-        })
+        });
+        Self::leave(&ctx);
+        res
     }
 
     #[inline]
@@ -179,7 +231,19 @@ impl LoaderHolder {
     ) -> Result<*mut qjs::JSModuleDef> {
         let name = name.to_str()?;
 
-        Ok(opaque.loader.raw_load(ctx, name)?.as_module_def().as_ptr())
+        let module = opaque.loader.raw_load(ctx, name)?;
+        Self::init_meta(ctx, &module, name)?;
+        Ok(module.as_module_def().as_ptr())
+    }
+
+    /// Set `import.meta.url` to the resolved name and run the user provided hook, if any.
+    unsafe fn init_meta<'js>(ctx: &Ctx<'js>, module: &Module<'js>, name: &str) -> Result<()> {
+        let meta: Object = module.meta()?;
+        meta.set("url", name)?;
+        if let Some(hook) = (*ctx.get_opaque()).import_meta.as_ref() {
+            hook(module, &meta)?;
+        }
+        Ok(())
     }
 
     unsafe extern "C" fn load_raw(
@@ -191,10 +255,13 @@ impl LoaderHolder {
         let name = CStr::from_ptr(name);
         let loader = &mut *(opaque as *mut LoaderOpaque);
 
-        Self::load(loader, &ctx, name).unwrap_or_else(|error| {
+        Self::enter(&ctx);
+        let res = Self::load(loader, &ctx, name).unwrap_or_else(|error| {
             error.throw(&ctx);
             ptr::null_mut()
-        })
+        });
+        Self::leave(&ctx);
+        res
     }
 }
 
@@ -269,9 +336,9 @@ loader_impls!(A B C D E F G H);
 
 #[cfg(test)]
 mod test {
-    use crate::{module::ModuleData, Context, Ctx, Error, Result, Runtime};
+    use crate::{module::ModuleData, CatchResultExt, Context, Ctx, Error, Result, Runtime};
 
-    use super::{Loader, Resolver};
+    use super::{BuiltinLoader, BuiltinResolver, Loader, ModuleLoader, Resolver};
 
     struct TestResolver;
 
@@ -325,6 +392,49 @@ mod test {
         })
     }
 
+    #[test]
+    fn import_meta() {
+        struct MetaResolver;
+
+        impl Resolver for MetaResolver {
+            fn resolve<'js>(&mut self, _ctx: &Ctx<'js>, _base: &str, name: &str) -> Result<String> {
+                Ok(format!("/modules/{}", name.trim_start_matches("./")))
+            }
+        }
+
+        struct MetaLoader;
+
+        impl Loader for MetaLoader {
+            fn load<'js>(&mut self, _ctx: &Ctx<'js>, name: &str) -> Result<ModuleData> {
+                Ok(ModuleData::source(
+                    name,
+                    "export const url = import.meta.url; export const env = import.meta.env;",
+                ))
+            }
+        }
+
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        rt.set_loader(MetaResolver, MetaLoader);
+        rt.set_import_meta(Some(Box::new(|module, meta| {
+            let name: String = module.name()?;
+            meta.set("env", format!("test:{}", name))
+        })));
+        ctx.with(|ctx| {
+            let module = ctx
+                .compile(
+                    "main",
+                    r#"
+                      import { url, env } from "./dep.js";
+                      export default [url, env];
+                    "#,
+                )
+                .unwrap();
+            let res: Vec<String> = module.get("default").unwrap();
+            assert_eq!(res, ["/modules/dep.js", "test:/modules/dep.js"]);
+        })
+    }
+
     #[test]
     #[should_panic(expected = "Unable to resolve")]
     fn resolving_error() {
@@ -350,4 +460,204 @@ mod test {
                 .expect("Unable to resolve");
         })
     }
+
+    #[test]
+    fn swap_loader() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        rt.set_loader(
+            BuiltinResolver::default().with_module("a"),
+            BuiltinLoader::default().with_module("a", "export const name = 'a';"),
+        );
+        ctx.with(|ctx| {
+            let module = ctx
+                .clone()
+                .compile("first", "import { name } from 'a'; export default name;")
+                .unwrap();
+            assert_eq!(module.get::<_, String>("default").unwrap(), "a");
+
+            ctx.set_loader(
+                BuiltinResolver::default().with_module("a").with_module("b"),
+                BuiltinLoader::default().with_module("b", "export const name = 'b';"),
+            );
+            // The new loader doesn't know module `a` but it is already loaded.
+            let module = ctx
+                .clone()
+                .compile(
+                    "second",
+                    "import { name as a } from 'a'; import { name as b } from 'b'; export default a + b;",
+                )
+                .unwrap();
+            assert_eq!(module.get::<_, String>("default").unwrap(), "ab");
+        })
+    }
+
+    #[test]
+    fn bytecode_module() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        let (bytecode, mut outdated) = ctx.with(|ctx| {
+            let bytecode = ctx
+                .compile_module_to_bytecode("util", "export const answer = 40 + 2;")
+                .unwrap();
+            let outdated = ctx
+                .compile_module_to_bytecode("old", "export const answer = 0;")
+                .unwrap();
+            // Errors are raised in this context, not the one the module is compiled in.
+            let res = ctx
+                .compile_module_to_bytecode("broken", "export const = 0;")
+                .catch(&ctx);
+            let err = res.unwrap_err().to_string();
+            assert!(err.contains("variable name expected"), "{}", err);
+            (bytecode, outdated)
+        });
+        // Pretend the second module was compiled with another version.
+        let version = outdated.iter().position(|b| *b == b'+').unwrap();
+        outdated[version - 1] ^= 1;
+
+        let loaders = || {
+            (
+                BuiltinResolver::default()
+                    .with_module("util")
+                    .with_module("old"),
+                // Safety: `util` was compiled by this build and `old` is rejected by its header
+                // before quickjs reads it.
+                unsafe {
+                    BuiltinLoader::default()
+                        .with_bytecode_module("util", bytecode.clone())
+                        .with_bytecode_module("old", outdated.clone())
+                },
+            )
+        };
+        let import = |ctx: &Context| {
+            ctx.with(|ctx| {
+                let module = ctx
+                    .clone()
+                    .compile(
+                        "main",
+                        "import { answer } from 'util'; export default answer;",
+                    )
+                    .unwrap();
+                assert_eq!(module.get::<_, i32>("default").unwrap(), 42);
+
+                let res = ctx
+                    .clone()
+                    .compile("other", "import { answer } from 'old';")
+                    .catch(&ctx);
+                let Err(err) = res else {
+                    panic!("outdated bytecode was loaded");
+                };
+                let err = err.to_string();
+                assert!(
+                    err.contains("Bytecode was compiled for quickjs version"),
+                    "{}",
+                    err
+                );
+            })
+        };
+
+        // Load the bytecode in a fresh runtime, like bytecode shipped with an application.
+        let other_rt = Runtime::new().unwrap();
+        let (resolver, loader) = loaders();
+        other_rt.set_loader(resolver, loader);
+        import(&Context::full(&other_rt).unwrap());
+
+        // Compiling didn't declare the modules in the context they were compiled in.
+        let (resolver, loader) = loaders();
+        rt.set_loader(resolver, loader);
+        import(&ctx);
+    }
+
+    #[test]
+    fn native_module_functions() {
+        use crate::{
+            module::{Declarations, Exports, ModuleDef},
+            Function,
+        };
+
+        struct Fs;
+
+        impl ModuleDef for Fs {
+            fn declare(declare: &mut Declarations) -> Result<()> {
+                declare.declare("read")?.declare("exists")?;
+                Ok(())
+            }
+
+            fn evaluate<'js>(ctx: &Ctx<'js>, exports: &mut Exports<'js>) -> Result<()> {
+                exports.export(
+                    "read",
+                    Function::new(ctx.clone(), |path: String| format!("<{path}>"))?,
+                )?;
+                exports.export(
+                    "exists",
+                    Function::new(ctx.clone(), |path: String| path == "a.txt")?,
+                )?;
+                Ok(())
+            }
+        }
+
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        rt.set_loader(
+            BuiltinResolver::default().with_module("native:fs"),
+            ModuleLoader::default().with_module("native:fs", Fs),
+        );
+        ctx.with(|ctx| {
+            let module = ctx
+                .clone()
+                .compile(
+                    "main",
+                    r#"
+                    import { read, exists } from "native:fs";
+                    export default exists("a.txt") && !exists("b.txt") ? read("a.txt") : null;
+                "#,
+                )
+                .unwrap();
+            assert_eq!(module.get::<_, String>("default").unwrap(), "<a.txt>");
+        })
+    }
+
+    #[test]
+    fn dynamic_import() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        rt.set_loader(
+            BuiltinResolver::default().with_module("m.js"),
+            BuiltinLoader::default().with_module("m.js", "export const answer = 42;"),
+        );
+        ctx.with(|ctx| {
+            ctx.eval::<(), _>(
+                r#"
+                  globalThis.result = null;
+                  (async () => {
+                    const m = await import("./m.js");
+                    result = m.answer;
+                  })();
+                "#,
+            )
+            .unwrap();
+            ctx.run_jobs().unwrap();
+            assert_eq!(ctx.globals().get::<_, i32>("result").unwrap(), 42);
+        })
+    }
+
+    #[test]
+    fn dynamic_import_error() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        rt.set_loader(TestResolver, TestLoader);
+        ctx.with(|ctx| {
+            ctx.eval::<(), _>(
+                r#"
+                  globalThis.result = null;
+                  import("missing").catch((e) => {
+                    result = e instanceof Error && e.message.includes("missing");
+                  });
+                "#,
+            )
+            .unwrap();
+            ctx.run_jobs().unwrap();
+            assert!(ctx.globals().get::<_, bool>("result").unwrap());
+        })
+    }
 }
@@ -1,12 +1,24 @@
-use crate::{loader::Loader, module::ModuleData, Ctx, Error, Result};
+use crate::{
+    context::strip_bytecode_header, loader::Loader, module::ModuleData, Ctx, Error, Result,
+};
 use std::collections::HashMap;
 
+#[derive(Debug)]
+enum BuiltinModule {
+    Source(Vec<u8>),
+    Bytecode(Vec<u8>),
+}
+
 /// The builtin script module loader
 ///
 /// This loader can be used as the nested backing loader in user-defined loaders.
+///
+/// Modules can be added either as source or as bytecode. Bytecode must be compiled with
+/// [`Ctx::compile_module_to_bytecode`] under the name the module is loaded with, loading bytecode
+/// which was compiled by another version of quickjs fails with an [`Error::Bytecode`] error.
 #[derive(Debug, Default)]
 pub struct BuiltinLoader {
-    modules: HashMap<String, Vec<u8>>,
+    modules: HashMap<String, BuiltinModule>,
 }
 
 impl BuiltinLoader {
@@ -16,7 +28,8 @@ impl BuiltinLoader {
         name: N,
         source: S,
     ) -> &mut Self {
-        self.modules.insert(name.into(), source.into());
+        self.modules
+            .insert(name.into(), BuiltinModule::Source(source.into()));
         self
     }
 
@@ -26,12 +39,49 @@ impl BuiltinLoader {
         self.add_module(name, source);
         self
     }
+
+    /// Add builtin module compiled to bytecode
+    ///
+    /// # Safety
+    /// The bytecode is read by quickjs when the module is loaded, which is undefined behavior if
+    /// the bytecode is malformed. It must have been created by
+    /// [`Ctx::compile_module_to_bytecode`] with the same build of quickjs and not been modified
+    /// since, see [`Ctx::eval_bytecode`].
+    pub unsafe fn add_bytecode_module<N: Into<String>, B: Into<Vec<u8>>>(
+        &mut self,
+        name: N,
+        bytecode: B,
+    ) -> &mut Self {
+        self.modules
+            .insert(name.into(), BuiltinModule::Bytecode(bytecode.into()));
+        self
+    }
+
+    /// Add builtin module compiled to bytecode
+    ///
+    /// # Safety
+    /// See [`BuiltinLoader::add_bytecode_module`].
+    #[must_use]
+    pub unsafe fn with_bytecode_module<N: Into<String>, B: Into<Vec<u8>>>(
+        mut self,
+        name: N,
+        bytecode: B,
+    ) -> Self {
+        self.add_bytecode_module(name, bytecode);
+        self
+    }
 }
 
 impl Loader for BuiltinLoader {
     fn load<'js>(&mut self, _ctx: &Ctx<'js>, path: &str) -> Result<ModuleData> {
         match self.modules.remove(path) {
-            Some(source) => Ok(ModuleData::source(path, source)),
+            Some(BuiltinModule::Source(source)) => Ok(ModuleData::source(path, source)),
+            Some(BuiltinModule::Bytecode(bytecode)) => {
+                let bytecode = strip_bytecode_header(&bytecode)?.to_vec();
+                // Safety: guaranteed by the caller of `add_bytecode_module`, the header check
+                // only turns accidental version mismatches into an error.
+                Ok(unsafe { ModuleData::bytecode(path, bytecode) })
+            }
             _ => Err(Error::new_loading(path)),
         }
     }
@@ -0,0 +1,170 @@
+use crate::{
+    loader::{RawLoader, ScriptLoader},
+    Ctx, Module, Mut, Ref, Result,
+};
+use std::{collections::HashMap, fs, time::SystemTime};
+
+/// The script module loader which caches compiled modules
+///
+/// Modules are loaded from the file system with the wrapped loader, by default a
+/// [`ScriptLoader`], but the compiled bytecode is kept keyed by the resolved path. Loading the
+/// same path again only reads the modification time of the file and reuses the bytecode if the
+/// file did not change since it was compiled. Names which are not files are passed on to the
+/// wrapped loader without caching.
+///
+/// The cache is shared between clones of the loader, so a clone can be kept around to clear the
+/// cache after the loader was passed to the runtime. Bytecode is not tied to a context, so the
+/// cache is reused by all contexts of the runtime.
+#[derive(Clone)]
+pub struct CachingFileLoader<L = ScriptLoader> {
+    loader: L,
+    cache: Ref<Mut<HashMap<String, CacheEntry>>>,
+}
+
+struct CacheEntry {
+    modified: SystemTime,
+    bytecode: Vec<u8>,
+}
+
+impl<L> CachingFileLoader<L> {
+    /// Wrap a loader which loads modules from files with an empty cache.
+    #[must_use]
+    pub fn new(loader: L) -> Self {
+        Self {
+            loader,
+            cache: Ref::new(Mut::new(HashMap::new())),
+        }
+    }
+
+    /// Remove all cached modules
+    pub fn clear(&self) {
+        self.cache.lock().clear();
+    }
+}
+
+impl CachingFileLoader {
+    /// Add script file extension
+    pub fn add_extension<X: Into<String>>(&mut self, extension: X) -> &mut Self {
+        self.loader.add_extension(extension);
+        self
+    }
+
+    /// Add script file extension
+    #[must_use]
+    pub fn with_extension<X: Into<String>>(mut self, extension: X) -> Self {
+        self.add_extension(extension);
+        self
+    }
+}
+
+impl Default for CachingFileLoader {
+    fn default() -> Self {
+        Self::new(ScriptLoader::default())
+    }
+}
+
+unsafe impl<L> RawLoader for CachingFileLoader<L>
+where
+    L: RawLoader,
+{
+    unsafe fn raw_load<'js>(&mut self, ctx: &Ctx<'js>, path: &str) -> Result<Module<'js>> {
+        let modified = match fs::metadata(path).and_then(|meta| meta.modified()) {
+            Ok(modified) => modified,
+            Err(_) => return self.loader.raw_load(ctx, path),
+        };
+        if let Some(entry) = self.cache.lock().get(path) {
+            if entry.modified == modified {
+                return Module::unsafe_declare_read_object(ctx.clone(), &entry.bytecode);
+            }
+        }
+
+        let module = self.loader.raw_load(ctx, path)?;
+        match module.write_object(false) {
+            Ok(bytecode) => {
+                self.cache
+                    .lock()
+                    .insert(path.into(), CacheEntry { modified, bytecode });
+            }
+            Err(_) => {
+                // Native modules can't be serialized, they are loaded again every time.
+                let _ = ctx.catch();
+            }
+        }
+        Ok(module)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CachingFileLoader;
+    use crate::{
+        loader::{BuiltinResolver, Loader, ScriptLoader},
+        module::ModuleData,
+        Context, Ctx, Result, Runtime,
+    };
+    use std::{cell::Cell, fs, rc::Rc, thread, time::Duration};
+
+    #[derive(Clone)]
+    struct CountingLoader(Rc<Cell<usize>>);
+
+    impl Loader for CountingLoader {
+        fn load<'js>(&mut self, ctx: &Ctx<'js>, path: &str) -> Result<ModuleData> {
+            self.0.set(self.0.get() + 1);
+            ScriptLoader::default().load(ctx, path)
+        }
+    }
+
+    fn load_value(rt: &Runtime, path: &str) -> i32 {
+        let ctx = Context::full(rt).unwrap();
+        ctx.with(|ctx| {
+            ctx.clone()
+                .compile(
+                    "main",
+                    format!("import {{ value }} from {path:?}; globalThis.value = value;"),
+                )
+                .unwrap();
+            ctx.globals().get("value").unwrap()
+        })
+    }
+
+    #[test]
+    fn reload_on_change() {
+        let dir = std::env::temp_dir().join(format!("rquickjs-caching-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("module.js");
+        let path = file.to_str().unwrap().to_owned();
+        fs::write(&file, "export const value = 1;").unwrap();
+
+        let loads = Rc::new(Cell::new(0));
+        let loader = CachingFileLoader::new(CountingLoader(loads.clone()));
+        let rt = Runtime::new().unwrap();
+        rt.set_loader(
+            BuiltinResolver::default().with_module(path.clone()),
+            loader.clone(),
+        );
+
+        assert_eq!(load_value(&rt, &path), 1);
+        assert_eq!(load_value(&rt, &path), 1);
+        assert_eq!(loads.get(), 1);
+
+        // Make sure the modification time actually changes on file systems with a coarse
+        // timestamp resolution.
+        let modified = fs::metadata(&file).unwrap().modified().unwrap();
+        for _ in 0..300 {
+            fs::write(&file, "export const value = 2;").unwrap();
+            if fs::metadata(&file).unwrap().modified().unwrap() != modified {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(load_value(&rt, &path), 2);
+        assert_eq!(load_value(&rt, &path), 2);
+        assert_eq!(loads.get(), 2);
+
+        loader.clear();
+        assert_eq!(load_value(&rt, &path), 2);
+        assert_eq!(loads.get(), 3);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
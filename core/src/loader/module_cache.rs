@@ -0,0 +1,153 @@
+use crate::{loader::RawLoader, Ctx, Module, Mut, Ref, Result};
+use std::collections::HashMap;
+
+/// A loader wrapper which shares compiled modules between all contexts of a runtime.
+///
+/// The first time a module is imported it is loaded with the wrapped loader and its compiled
+/// bytecode is kept keyed by the resolved module name. Importing the same name again, from any
+/// context of the runtime, declares the module from the cached bytecode without consulting the
+/// wrapped loader, so sources are neither read nor parsed again.
+///
+/// Quickjs keeps declared modules per context, every context still gets its own instance of the
+/// module with its own state, only the compiled bytecode is shared. Sharing the bytecode between
+/// contexts of one runtime is sound as it doesn't reference any context bound values. Modules
+/// which can't be written as bytecode, like native modules, are not cached.
+///
+/// ```
+/// # use rquickjs::{Runtime, loader::{BuiltinResolver, ModuleCache, ScriptLoader}};
+/// let rt = Runtime::new().unwrap();
+/// let cache = ModuleCache::new(ScriptLoader::default());
+/// rt.set_loader(BuiltinResolver::default(), cache.clone());
+/// // Later on, for example when the sources changed.
+/// cache.clear();
+/// ```
+///
+/// The cache is shared between clones, so a clone can be kept to inspect or clear the cache after
+/// the loader was passed to the runtime.
+#[derive(Clone)]
+pub struct ModuleCache<L> {
+    loader: L,
+    cache: Ref<Mut<HashMap<String, Vec<u8>>>>,
+}
+
+impl<L> ModuleCache<L> {
+    /// Wrap a loader with an empty cache.
+    #[must_use]
+    pub fn new(loader: L) -> Self {
+        Self {
+            loader,
+            cache: Ref::new(Mut::new(HashMap::new())),
+        }
+    }
+
+    /// Returns true if the module with the given resolved name is cached.
+    pub fn contains(&self, name: &str) -> bool {
+        self.cache.lock().contains_key(name)
+    }
+
+    /// Returns the number of cached modules.
+    pub fn len(&self) -> usize {
+        self.cache.lock().len()
+    }
+
+    /// Returns true if no module is cached.
+    pub fn is_empty(&self) -> bool {
+        self.cache.lock().is_empty()
+    }
+
+    /// Remove the module with the given resolved name from the cache.
+    pub fn remove(&self, name: &str) -> bool {
+        self.cache.lock().remove(name).is_some()
+    }
+
+    /// Remove all cached modules.
+    pub fn clear(&self) {
+        self.cache.lock().clear();
+    }
+}
+
+unsafe impl<L> RawLoader for ModuleCache<L>
+where
+    L: RawLoader,
+{
+    unsafe fn raw_load<'js>(&mut self, ctx: &Ctx<'js>, name: &str) -> Result<Module<'js>> {
+        if let Some(bytecode) = self.cache.lock().get(name) {
+            return Module::unsafe_declare_read_object(ctx.clone(), bytecode);
+        }
+
+        let module = self.loader.raw_load(ctx, name)?;
+        match module.write_object(false) {
+            Ok(bytecode) => {
+                self.cache.lock().insert(name.into(), bytecode);
+            }
+            Err(_) => {
+                // Native modules can't be serialized, they are loaded again every time.
+                let _ = ctx.catch();
+            }
+        }
+        Ok(module)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ModuleCache;
+    use crate::{
+        loader::{BuiltinResolver, Loader},
+        module::ModuleData,
+        Context, Ctx, Result, Runtime,
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    #[derive(Clone)]
+    struct CountingLoader(Rc<Cell<usize>>);
+
+    impl Loader for CountingLoader {
+        fn load<'js>(&mut self, _ctx: &Ctx<'js>, name: &str) -> Result<ModuleData> {
+            self.0.set(self.0.get() + 1);
+            Ok(ModuleData::source(
+                name,
+                "export let count = 0; export function inc() { return ++count; }",
+            ))
+        }
+    }
+
+    #[test]
+    fn shared_between_contexts() {
+        let loads = Rc::new(Cell::new(0));
+        let cache = ModuleCache::new(CountingLoader(loads.clone()));
+        let rt = Runtime::new().unwrap();
+        rt.set_loader(
+            BuiltinResolver::default().with_module("counter"),
+            cache.clone(),
+        );
+
+        let run = |ctx: &Context| -> i32 {
+            ctx.with(|ctx| {
+                ctx.clone()
+                    .compile(
+                        "main",
+                        "import { inc } from 'counter'; globalThis.res = inc() + inc();",
+                    )
+                    .unwrap();
+                ctx.globals().get("res").unwrap()
+            })
+        };
+
+        let ctx_a = Context::full(&rt).unwrap();
+        assert_eq!(run(&ctx_a), 3);
+        assert_eq!(loads.get(), 1);
+        assert!(cache.contains("counter"));
+
+        // The second context shares the bytecode but gets its own module state.
+        let ctx_b = Context::full(&rt).unwrap();
+        assert_eq!(run(&ctx_b), 3);
+        assert_eq!(loads.get(), 1);
+        assert_eq!(cache.len(), 1);
+
+        cache.clear();
+        let ctx_c = Context::full(&rt).unwrap();
+        assert_eq!(run(&ctx_c), 3);
+        assert_eq!(loads.get(), 2);
+    }
+}
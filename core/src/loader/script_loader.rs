@@ -7,7 +7,7 @@ use crate::{
 /// The script module loader
 ///
 /// This loader can be used as the nested backing loader in user-defined loaders.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ScriptLoader {
     extensions: Vec<String>,
 }
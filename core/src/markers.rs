@@ -32,3 +32,16 @@ impl<T> ParallelSend for T {}
 
 #[cfg(feature = "parallel")]
 impl<T: Send> ParallelSend for T {}
+
+/// The marker trait which requires [`Sync`] when `"parallel"` feature is used
+#[cfg(not(feature = "parallel"))]
+pub trait ParallelSync {}
+
+#[cfg(feature = "parallel")]
+pub trait ParallelSync: Sync {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T> ParallelSync for T {}
+
+#[cfg(feature = "parallel")]
+impl<T: Sync> ParallelSync for T {}
@@ -1,9 +1,10 @@
 use crate::{
-    qjs, value::Constructor, Array, BigInt, Ctx, Error, FromJs, Function, IntoJs, Object, Result,
-    String, Symbol, Value,
+    qjs, value::Constructor, Array, Atom, BigInt, Ctx, Error, Exception, FromJs, Function, IntoJs,
+    Object, Result, String, Symbol, Value, WeakRef,
 };
 use std::{
     fmt,
+    marker::PhantomData,
     mem::{self, ManuallyDrop},
 };
 
@@ -57,6 +58,8 @@ outlive_impls! {
     BigInt,
     Function,
     Constructor,
+    Exception,
+    Atom,
 }
 
 macro_rules! impl_outlive{
@@ -147,6 +150,10 @@ impl_outlive!(
 /// assert_eq!(res, 1);
 /// ```
 ///
+/// Any type implementing [`Outlive`] can be saved, this includes all javascript value types
+/// like [`Value`], [`Object`], [`Array`] and [`Function`] as well as containers of them, for
+/// example a `Vec<Persistent<Function>>` can be stored in a struct outside of the context.
+///
 /// It is an error (`Error::UnrelatedRuntime`) to restore the `Persistent` in a
 /// context who isn't part of the original `Runtime`.
 ///
@@ -258,6 +265,88 @@ where
 #[cfg(feature = "parallel")]
 unsafe impl<T> Send for Persistent<T> {}
 
+/// A weak version of [`Persistent`] which doesn't keep the value from GC
+///
+/// The value is referenced through a [`WeakRef`], so once all other references to it are
+/// dropped it can be collected and [`WeakPersistent::restore`] returns `None`. This is useful
+/// for caches and registries on the rust side which should not by themselves keep javascript
+/// objects alive. Like the [`WeakRef`] it is built on it is not affected by scripts which
+/// replace `WeakMap` or its methods.
+/// ```
+/// # use rquickjs::{Runtime, Context, WeakPersistent, Object};
+/// # let rt = Runtime::new().unwrap();
+/// # let ctx = Context::full(&rt).unwrap();
+/// let weak = ctx.with(|ctx| {
+///     let obj: Object = ctx.eval("globalThis.obj = { a: 1 }").unwrap();
+///     WeakPersistent::save(&ctx, obj).unwrap()
+/// });
+/// ctx.with(|ctx| {
+///     let obj = weak.restore(&ctx).unwrap().unwrap();
+///     assert_eq!(obj.get::<_, i32>("a").unwrap(), 1);
+/// });
+/// ```
+///
+/// Only objects can be the target of a weak reference, saving other values returns an error.
+///
+/// Quickjs only collects values when they are no longer referenced or when the cycle collector
+/// runs, see [`Runtime::run_gc`](crate::Runtime::run_gc).
+///
+/// NOTE: The weak reference itself holds javascript objects, the same rules as for
+/// [`Persistent`] apply, it must not outlive the runtime.
+pub struct WeakPersistent<T> {
+    weak: Persistent<WeakRef<'static>>,
+    marker: PhantomData<T>,
+}
+
+impl<T> Clone for WeakPersistent<T> {
+    fn clone(&self) -> Self {
+        WeakPersistent {
+            weak: self.weak.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for WeakPersistent<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("WeakPersistent")
+            .field("rt", &self.weak.rt)
+            .field("weak", &self.weak.value)
+            .finish()
+    }
+}
+
+impl<T> WeakPersistent<T> {
+    /// Save a weak reference to the value
+    pub fn save<'js>(ctx: &Ctx<'js>, val: T) -> Result<WeakPersistent<T::Target<'static>>>
+    where
+        T: Outlive<'js> + AsRef<Value<'js>>,
+    {
+        let weak = WeakRef::new(ctx.clone(), val.as_ref())?;
+        Ok(WeakPersistent {
+            weak: Persistent::save(ctx, weak),
+            marker: PhantomData,
+        })
+    }
+
+    /// Restore the value if it wasn't collected yet
+    ///
+    /// Returns `Ok(None)` if the value was collected.
+    pub fn restore<'js>(&self, ctx: &Ctx<'js>) -> Result<Option<T::Target<'js>>>
+    where
+        T: Outlive<'static>,
+        T::Target<'js>: FromJs<'js>,
+    {
+        let weak = self.weak.clone().restore(ctx)?;
+        weak.deref()
+            .map(|value| T::Target::<'js>::from_js(ctx, value))
+            .transpose()
+    }
+}
+
+#[cfg(feature = "parallel")]
+unsafe impl<T> Send for WeakPersistent<T> {}
+
 #[cfg(test)]
 mod test {
     use crate::*;
@@ -323,6 +412,98 @@ mod test {
         assert_eq!(res, 1);
     }
 
+    #[test]
+    fn stored_in_struct() {
+        struct Handlers {
+            on_event: Persistent<Function<'static>>,
+            state: Persistent<Vec<Object<'static>>>,
+        }
+
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+
+        let handlers = ctx.with(|ctx| {
+            let on_event: Function = ctx.eval("(state, x) => state.count += x").unwrap();
+            let state: Object = ctx.eval("({ count: 1 })").unwrap();
+            Handlers {
+                on_event: Persistent::save(&ctx, on_event),
+                state: Persistent::save(&ctx, vec![state]),
+            }
+        });
+
+        let res: i32 = ctx.with(|ctx| {
+            let on_event = handlers.on_event.clone().restore(&ctx).unwrap();
+            let state = handlers.state.clone().restore(&ctx).unwrap();
+            on_event.call((state[0].clone(), 2)).unwrap()
+        });
+        assert_eq!(res, 3);
+
+        let rt2 = Runtime::new().unwrap();
+        let ctx2 = Context::full(&rt2).unwrap();
+        ctx2.with(|ctx| {
+            let res = handlers.on_event.clone().restore(&ctx);
+            assert!(matches!(res, Err(Error::UnrelatedRuntime)));
+        });
+    }
+
+    #[test]
+    fn weak_persistent_collected() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+
+        let weak = ctx.with(|ctx| {
+            let obj: Object = ctx.eval("globalThis.obj = { a: 1 }").unwrap();
+            WeakPersistent::save(&ctx, obj).unwrap()
+        });
+
+        ctx.with(|ctx| {
+            let obj = weak.restore(&ctx).unwrap().unwrap();
+            assert_eq!(obj.get::<_, i32>("a").unwrap(), 1);
+            ctx.globals().remove("obj").unwrap();
+        });
+
+        rt.run_gc();
+        ctx.with(|ctx| {
+            assert!(weak.restore(&ctx).unwrap().is_none());
+        });
+
+        let rt2 = Runtime::new().unwrap();
+        let ctx2 = Context::full(&rt2).unwrap();
+        ctx2.with(|ctx| {
+            let res = weak.restore(&ctx);
+            assert!(matches!(res, Err(Error::UnrelatedRuntime)));
+        });
+    }
+
+    #[test]
+    fn weak_persistent_with_replaced_weak_map() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+
+        let weak = ctx.with(|ctx| {
+            ctx.eval::<(), _>(
+                r#"
+                WeakMap.prototype.set = function (key, value) { globalThis.leak = value; return this; };
+                globalThis.obj = { a: 1 };
+                "#,
+            )
+            .unwrap();
+            let obj: Object = ctx.globals().get("obj").unwrap();
+            WeakPersistent::save(&ctx, obj).unwrap()
+        });
+
+        ctx.with(|ctx| {
+            assert!(weak.restore(&ctx).unwrap().is_some());
+            ctx.globals().remove("obj").unwrap();
+        });
+
+        rt.run_gc();
+        ctx.with(|ctx| {
+            assert!(weak.restore(&ctx).unwrap().is_none());
+            assert!(ctx.eval::<bool, _>("globalThis.leak === undefined").unwrap());
+        });
+    }
+
     #[test]
     fn persistent_value() {
         let rt = Runtime::new().unwrap();
@@ -8,8 +8,12 @@ use std::{
 };
 
 use crate::{
-    atom::PredefinedAtom, function::This, qjs, safe_ref::Ref, CatchResultExt, CaughtError,
-    CaughtResult, Ctx, Exception, FromJs, Function, IntoJs, Object, Result, ThrowResultExt, Value,
+    atom::PredefinedAtom,
+    function::{IntoJsFunc, This},
+    qjs,
+    safe_ref::Ref,
+    CatchResultExt, CaughtError, CaughtResult, Ctx, Exception, FromJs, Function, IntoJs, Object,
+    Result, ThrowResultExt, Value,
 };
 
 /// Future-aware promise
@@ -45,18 +49,95 @@ impl<'js, T: 'js> State<'js, T> {
 unsafe impl<'js, T> Send for State<'js, T> {}
 unsafe impl<'js, T> Sync for State<'js, T> {}
 
-impl<'js, T> FromJs<'js> for Promise<'js, T>
+impl<'js, T> Promise<'js, T>
 where
-    T: FromJs<'js> + 'js,
+    T: 'js,
 {
-    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self> {
-        let promise = Object::from_js(ctx, value)?;
+    /// Create a promise which is settled with the output of a future.
+    ///
+    /// The future is spawned on the runtime's executor. When it returns `Ok` the promise resolves
+    /// with the converted value, an `Err` rejects the promise with the error thrown as javascript
+    /// exception.
+    pub fn from_future<F>(ctx: Ctx<'js>, future: F) -> Result<Self>
+    where
+        F: Future<Output = Result<T>> + 'js,
+        T: IntoJs<'js>,
+    {
+        let promise = Promised(future).into_js(&ctx)?;
+        Ok(Self::from_object(Object::from_js(&ctx, promise)?))
+    }
+
+    /// Append a rust callback which is called with the value of the promise once it resolves.
+    ///
+    /// Returns the promise created by `Promise.prototype.then` which resolves with the value
+    /// returned from the callback. The callback is run as part of the job queue, so it is only
+    /// called when pending jobs are executed.
+    pub fn then<F, P, U>(&self, on_fulfilled: F) -> Result<Promise<'js, U>>
+    where
+        F: IntoJsFunc<'js, P> + 'js,
+        U: 'js,
+    {
+        self.chain(PredefinedAtom::Then, on_fulfilled)
+    }
+
+    /// Append a rust callback which is called with the reason of the promise once it rejects.
+    ///
+    /// Returns the promise created by `Promise.prototype.catch`, the same rules as for
+    /// [`Promise::then`] apply.
+    pub fn catch<F, P, U>(&self, on_rejected: F) -> Result<Promise<'js, U>>
+    where
+        F: IntoJsFunc<'js, P> + 'js,
+        U: 'js,
+    {
+        self.chain(PredefinedAtom::Catch, on_rejected)
+    }
+
+    fn chain<F, P, U>(&self, method: PredefinedAtom, callback: F) -> Result<Promise<'js, U>>
+    where
+        F: IntoJsFunc<'js, P> + 'js,
+        U: 'js,
+    {
+        let ctx = self.promise.ctx();
+        let method: Function = self.promise.get(method)?;
+        let callback = Function::new(ctx.clone(), callback)?;
+        let promise: Object = method.call((This(self.promise.clone()), callback))?;
+        Ok(Promise::from_object(promise))
+    }
+
+    pub(crate) fn from_object(promise: Object<'js>) -> Self {
         let state = Ref::new(State {
             waker: Cell::new(None),
             result: Cell::new(None),
         });
 
-        Ok(Promise { state, promise })
+        Promise { state, promise }
+    }
+
+    /// Reference as an object
+    #[inline]
+    pub fn as_object(&self) -> &Object<'js> {
+        &self.promise
+    }
+
+    /// Convert into an object
+    #[inline]
+    pub fn into_object(self) -> Object<'js> {
+        self.promise
+    }
+}
+
+impl<'js, T> FromJs<'js> for Promise<'js, T>
+where
+    T: FromJs<'js> + 'js,
+{
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self> {
+        Object::from_js(ctx, value).map(Self::from_object)
+    }
+}
+
+impl<'js, T> IntoJs<'js> for Promise<'js, T> {
+    fn into_js(self, _: &Ctx<'js>) -> Result<Value<'js>> {
+        Ok(self.promise.into_value())
     }
 }
 
@@ -138,3 +219,82 @@ where
         Ok(promise.into_value())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Promise;
+    use crate::{runtime::async_test_case, *};
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    #[test]
+    fn then_chain() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        let result = Rc::new(Cell::new(0));
+
+        ctx.with(|ctx| {
+            let result = result.clone();
+            let promise: Promise<i32> = ctx.eval("Promise.resolve(2)").unwrap();
+            promise
+                .then::<_, _, i32>(|value: i32| value + 1)
+                .unwrap()
+                .then::<_, _, i32>(|value: i32| value * 10)
+                .unwrap()
+                .then::<_, _, ()>(move |value: i32| result.set(value))
+                .unwrap();
+        });
+        assert_eq!(result.get(), 0);
+
+        while rt.execute_pending_job().unwrap() {}
+        assert_eq!(result.get(), 30);
+    }
+
+    #[test]
+    fn catch_rejection() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        let message = Rc::new(RefCell::new(None));
+
+        ctx.with(|ctx| {
+            let message = message.clone();
+            let promise: Promise<()> = ctx.eval("Promise.reject(new Error('oops'))").unwrap();
+            promise
+                .catch::<_, _, ()>(move |error: Exception| *message.borrow_mut() = error.message())
+                .unwrap();
+        });
+
+        while rt.execute_pending_job().unwrap() {}
+        assert_eq!(message.borrow().as_deref(), Some("oops"));
+    }
+
+    async_test_case!(from_future_resolve => (_rt,ctx){
+        async_with!(&ctx => |ctx|{
+            let promise = Promise::from_future(ctx.clone(), async {
+                tokio::task::yield_now().await;
+                Ok(42i32)
+            }).unwrap();
+            ctx.globals().set("p", promise).unwrap();
+
+            let res: Promise<i32> = ctx.eval("(async () => (await p) + 1)()").unwrap();
+            assert_eq!(res.await.unwrap(), 43);
+        }).await;
+    });
+
+    async_test_case!(from_future_reject => (_rt,ctx){
+        async_with!(&ctx => |ctx|{
+            let promise = Promise::<i32>::from_future(ctx.clone(), async {
+                tokio::task::yield_now().await;
+                Err(Error::new_from_js_message("value", "i32", "no value"))
+            }).unwrap();
+            ctx.globals().set("p", promise).unwrap();
+
+            let res: Promise<bool> = ctx.eval(r#"
+                p.then(() => false, (e) => e instanceof Error && e.message.includes("no value"))
+            "#).unwrap();
+            assert!(res.await.unwrap());
+        }).await;
+    });
+}
@@ -12,7 +12,8 @@ use std::{
 #[cfg(feature = "futures")]
 use crate::context::AsyncContext;
 use crate::{
-    atom::PredefinedAtom, qjs, Context, Ctx, Exception, Object, StdResult, StdString, Type, Value,
+    atom::PredefinedAtom, qjs, Context, Ctx, Exception, ExceptionKind, IntoException, Object,
+    StdResult, StdString, Type, Value,
 };
 
 /// Result type used throught the library.
@@ -70,7 +71,12 @@ pub enum Error {
     /// An io error
     Io(IoError),
     /// An error happened while trying to borrow a rust class object.
-    ClassBorrow(BorrowError),
+    BorrowConflict {
+        /// The name of the class which could not be borrowed.
+        class: &'static str,
+        /// Why the class could not be borrowed.
+        error: BorrowError,
+    },
     /// An error happened while trying to borrow a rust function.
     FunctionBorrow(BorrowError),
     /// An exception raised by quickjs itself.
@@ -79,6 +85,12 @@ pub enum Error {
     /// When returned from a callback the javascript will continue to unwind with the current
     /// error.
     Exception,
+    /// Execution was interrupted because the deadline set with
+    /// [`Runtime::set_deadline`](crate::Runtime::set_deadline) passed.
+    ///
+    /// Like [`Error::Exception`] the uncatchable exception raised by quickjs is still pending and
+    /// can be retrieved by calling [`Ctx::catch`].
+    Interrupted,
     /// Error converting from javascript to a rust type.
     FromJs {
         from: &'static str,
@@ -115,6 +127,18 @@ pub enum Error {
     },
     /// Error when restoring a Persistent in a runtime other than the original runtime.
     UnrelatedRuntime,
+    /// Tried to run bytecode which was not compiled by the same version of quickjs.
+    Bytecode {
+        expected: StdString,
+        found: Option<StdString>,
+    },
+    /// An error which is thrown into javascript as an instance of a builtin error class.
+    ///
+    /// Created from rust errors which implement [`IntoException`].
+    Thrown {
+        kind: ExceptionKind,
+        message: StdString,
+    },
     /// An error from quickjs from which the specifics are unknown.
     /// Should eventually be removed as development progresses.
     Unknown,
@@ -188,6 +212,38 @@ impl Error {
         matches!(self, Error::Exception)
     }
 
+    /// Returns the javascript value which was thrown if the error is a pending exception.
+    ///
+    /// Unlike [`Ctx::catch`] this leaves the exception pending, so the error can still be
+    /// returned to javascript afterwards. This also works for values which are not instances of
+    /// `Error`, like in `throw "custom"`, for which the error itself only reports an exception.
+    ///
+    /// Returns `None` for errors which are not raised by javascript and if no exception is pending,
+    /// for example because it was already caught.
+    ///
+    /// # Usage
+    /// ```
+    /// # use rquickjs::{Context, Runtime};
+    /// # let rt = Runtime::new().unwrap();
+    /// # let ctx = Context::full(&rt).unwrap();
+    /// # ctx.with(|ctx|{
+    /// let error = ctx.eval::<(), _>("throw 'custom'").unwrap_err();
+    /// let value = error.thrown_value(&ctx).unwrap();
+    /// assert_eq!(value.as_string().unwrap().to_string().unwrap(), "custom");
+    /// # });
+    /// ```
+    pub fn thrown_value<'js>(&self, ctx: &Ctx<'js>) -> Option<Value<'js>> {
+        if !matches!(self, Error::Exception | Error::Interrupted) {
+            return None;
+        }
+        let value = ctx.catch();
+        if value.is_null() {
+            return None;
+        }
+        ctx.throw(value.clone());
+        Some(value)
+    }
+
     /// Create from JS conversion error
     pub fn new_from_js(from: &'static str, to: &'static str) -> Self {
         Error::FromJs {
@@ -265,7 +321,7 @@ impl Error {
     pub(crate) fn throw(&self, ctx: &Ctx) -> qjs::JSValue {
         use Error::*;
         match self {
-            Exception => qjs::JS_EXCEPTION,
+            Exception | Interrupted => qjs::JS_EXCEPTION,
             Allocation => unsafe { qjs::JS_ThrowOutOfMemory(ctx.as_ptr()) },
             InvalidString(_)
             | Utf8(_)
@@ -285,6 +341,15 @@ impl Error {
                 let message = self.to_cstring();
                 unsafe { qjs::JS_ThrowInternalError(ctx.as_ptr(), message.as_ptr()) }
             }
+            Thrown { kind, message } => {
+                match crate::Exception::from_native(ctx.clone(), kind.native_error(), message) {
+                    Ok(error) => {
+                        ctx.throw(error.into_object().into_value());
+                        qjs::JS_EXCEPTION
+                    }
+                    Err(error) => error.throw(ctx),
+                }
+            }
             error => {
                 unsafe {
                     let value = qjs::JS_NewError(ctx.as_ptr());
@@ -334,6 +399,7 @@ impl Display for Error {
             }
             Unknown => "quickjs library created a unknown error".fmt(f)?,
             Exception => "Exception generated by quickjs".fmt(f)?,
+            Interrupted => "Execution was interrupted after the deadline passed".fmt(f)?,
             FromJs { from, to, message } => {
                 "Error converting from js '".fmt(f)?;
                 from.fmt(f)?;
@@ -409,15 +475,33 @@ impl Display for Error {
                 "IO Error: ".fmt(f)?;
                 error.fmt(f)?;
             }
-            ClassBorrow(x) => {
-                "Error borrowing class: ".fmt(f)?;
-                x.fmt(f)?;
+            BorrowConflict { class, error } => {
+                "Error borrowing class `".fmt(f)?;
+                class.fmt(f)?;
+                "`: ".fmt(f)?;
+                error.fmt(f)?;
+            }
+            Thrown { kind, message } => {
+                kind.name().fmt(f)?;
+                ": ".fmt(f)?;
+                message.fmt(f)?;
             }
             FunctionBorrow(x) => {
                 "Error borrowing function: ".fmt(f)?;
                 x.fmt(f)?;
             }
             UnrelatedRuntime => "Restoring Persistent in an unrelated runtime".fmt(f)?,
+            Bytecode { expected, found } => {
+                if let Some(found) = found {
+                    "Bytecode was compiled for quickjs version '".fmt(f)?;
+                    found.fmt(f)?;
+                    "' but this is version '".fmt(f)?;
+                } else {
+                    "Data is not bytecode compiled by rquickjs, expected version '".fmt(f)?;
+                }
+                expected.fmt(f)?;
+                "'".fmt(f)?;
+            }
         }
         Ok(())
     }
@@ -442,6 +526,15 @@ from_impls! {
     IoError => Io,
 }
 
+impl<E: IntoException> From<E> for Error {
+    fn from(error: E) -> Self {
+        Error::Thrown {
+            kind: error.exception_kind(),
+            message: error.to_string(),
+        }
+    }
+}
+
 impl From<FromUtf8Error> for Error {
     fn from(error: FromUtf8Error) -> Self {
         Error::Utf8(error.utf8_error())
@@ -616,8 +709,22 @@ impl<'js> Ctx<'js> {
             match panic::catch_unwind(f) {
                 Ok(x) => x,
                 Err(e) => {
-                    (*self.get_opaque()).panic = Some(e);
-                    qjs::JS_Throw(self.as_ptr(), qjs::JS_MKVAL(qjs::JS_TAG_EXCEPTION, 0))
+                    let opaque = &mut *self.get_opaque();
+                    if !opaque.panic_to_exception {
+                        opaque.panic = Some(e);
+                        return qjs::JS_Throw(
+                            self.as_ptr(),
+                            qjs::JS_MKVAL(qjs::JS_TAG_EXCEPTION, 0),
+                        );
+                    }
+                    let message = if let Some(message) = e.downcast_ref::<&str>() {
+                        message
+                    } else if let Some(message) = e.downcast_ref::<StdString>() {
+                        message.as_str()
+                    } else {
+                        "rust callback panicked"
+                    };
+                    Exception::throw_message(self, message).throw(self)
                 }
             }
         }
@@ -632,22 +739,64 @@ impl<'js> Ctx<'js> {
         if qjs::JS_VALUE_GET_NORM_TAG(js_val) != qjs::JS_TAG_EXCEPTION {
             Ok(js_val)
         } else {
-            if let Some(x) = (*self.get_opaque()).panic.take() {
+            let opaque = &mut *self.get_opaque();
+            if let Some(x) = opaque.panic.take() {
                 panic::resume_unwind(x)
             }
+            if opaque.deadline_exceeded {
+                return Err(Error::Interrupted);
+            }
             Err(Error::Exception)
         }
     }
 
-    /// Returns Error::Exception if there is no existing panic,
-    /// otherwise continues panicking.
+    /// Returns Error::Exception, or Error::Interrupted if the deadline passed, if there is no
+    /// existing panic, otherwise continues panicking.
     pub(crate) fn raise_exception(&self) -> Error {
         // Safety
         unsafe {
-            if let Some(x) = (*self.get_opaque()).panic.take() {
+            let opaque = &mut *self.get_opaque();
+            if let Some(x) = opaque.panic.take() {
                 panic::resume_unwind(x)
             }
+            if opaque.deadline_exceeded {
+                return Error::Interrupted;
+            }
             Error::Exception
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn thrown_values() {
+        test_with(|ctx| {
+            let error = ctx.eval::<(), _>("throw 'custom'").unwrap_err();
+            let value = error.thrown_value(&ctx).unwrap();
+            assert_eq!(value.get::<StdString>().unwrap(), "custom");
+            // The exception is still pending and can be caught as usual.
+            let caught = CaughtError::from_error(&ctx, error);
+            assert!(matches!(caught, CaughtError::Value(ref v) if v.is_string()));
+
+            let error = ctx
+                .eval::<(), _>("throw { code: 42, reason: 'nope' }")
+                .unwrap_err();
+            let value = error.thrown_value(&ctx).unwrap();
+            let object = value.as_object().unwrap();
+            assert_eq!(object.get::<_, i32>("code").unwrap(), 42);
+            assert_eq!(object.get::<_, StdString>("reason").unwrap(), "nope");
+            assert_eq!(ctx.catch(), value);
+
+            let error = Error::new_from_js("string", "int");
+            assert!(error.thrown_value(&ctx).is_none());
+
+            // Nothing is pending anymore once the exception was caught.
+            let error = ctx.eval::<(), _>("throw 'custom'").unwrap_err();
+            ctx.catch();
+            assert!(error.thrown_value(&ctx).is_none());
+        })
+    }
+}
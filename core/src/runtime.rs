@@ -1,5 +1,7 @@
 //! Quickjs runtime related types.
 
+use crate::{qjs, Value};
+
 pub(crate) mod raw;
 
 mod base;
@@ -12,11 +14,119 @@ pub type InterruptHandler = Box<dyn FnMut() -> bool + 'static>;
 #[cfg(feature = "parallel")]
 pub type InterruptHandler = Box<dyn FnMut() -> bool + Send + 'static>;
 
+/// The type of the promise rejection tracker.
+#[cfg(not(feature = "parallel"))]
+pub type RejectionTracker = Box<dyn for<'js> Fn(Value<'js>, bool) + 'static>;
+/// The type of the promise rejection tracker.
+#[cfg(feature = "parallel")]
+pub type RejectionTracker = Box<dyn for<'js> Fn(Value<'js>, bool) + Send + 'static>;
+
 #[cfg(feature = "futures")]
 mod r#async;
+#[cfg(all(test, feature = "futures"))]
+pub(crate) use r#async::async_test_case;
 #[cfg(feature = "futures")]
 pub use r#async::{AsyncRuntime, AsyncWeakRuntime};
 #[cfg(feature = "futures")]
 mod spawner;
 
-pub use crate::qjs::JSMemoryUsage as MemoryUsage;
+/// Memory usage statistics of a runtime as computed by quickjs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryUsage {
+    /// The amount of bytes allocated by the runtime.
+    pub malloc_size: i64,
+    /// The memory limit of the runtime in bytes, `-1` when unlimited.
+    pub malloc_limit: i64,
+    /// The amount of bytes in use including allocator overhead.
+    pub memory_used_size: i64,
+    /// The number of live allocations.
+    pub malloc_count: i64,
+    /// The number of allocations counted towards `memory_used_size`.
+    pub memory_used_count: i64,
+    /// The number of atoms.
+    pub atom_count: i64,
+    /// The amount of bytes used by atoms.
+    pub atom_size: i64,
+    /// The number of strings.
+    pub str_count: i64,
+    /// The amount of bytes used by strings.
+    pub str_size: i64,
+    /// The number of objects.
+    pub obj_count: i64,
+    /// The amount of bytes used by objects.
+    pub obj_size: i64,
+    /// The number of object properties.
+    pub prop_count: i64,
+    /// The amount of bytes used by object properties.
+    pub prop_size: i64,
+    /// The number of object shapes.
+    pub shape_count: i64,
+    /// The amount of bytes used by object shapes.
+    pub shape_size: i64,
+    /// The number of javascript functions.
+    pub js_func_count: i64,
+    /// The amount of bytes used by javascript functions.
+    pub js_func_size: i64,
+    /// The amount of bytes used by the bytecode of javascript functions.
+    pub js_func_code_size: i64,
+    /// The number of javascript functions with line number information.
+    pub js_func_pc2line_count: i64,
+    /// The amount of bytes used by line number information.
+    pub js_func_pc2line_size: i64,
+    /// The number of native functions.
+    pub c_func_count: i64,
+    /// The number of arrays.
+    pub array_count: i64,
+    /// The number of arrays using the fast array representation.
+    pub fast_array_count: i64,
+    /// The number of elements stored in fast arrays.
+    pub fast_array_elements: i64,
+    /// The number of array buffers and typed arrays.
+    pub binary_object_count: i64,
+    /// The amount of bytes used by array buffers and typed arrays.
+    pub binary_object_size: i64,
+}
+
+impl From<qjs::JSMemoryUsage> for MemoryUsage {
+    fn from(usage: qjs::JSMemoryUsage) -> Self {
+        MemoryUsage {
+            malloc_size: usage.malloc_size,
+            malloc_limit: usage.malloc_limit,
+            memory_used_size: usage.memory_used_size,
+            malloc_count: usage.malloc_count,
+            memory_used_count: usage.memory_used_count,
+            atom_count: usage.atom_count,
+            atom_size: usage.atom_size,
+            str_count: usage.str_count,
+            str_size: usage.str_size,
+            obj_count: usage.obj_count,
+            obj_size: usage.obj_size,
+            prop_count: usage.prop_count,
+            prop_size: usage.prop_size,
+            shape_count: usage.shape_count,
+            shape_size: usage.shape_size,
+            js_func_count: usage.js_func_count,
+            js_func_size: usage.js_func_size,
+            js_func_code_size: usage.js_func_code_size,
+            js_func_pc2line_count: usage.js_func_pc2line_count,
+            js_func_pc2line_size: usage.js_func_pc2line_size,
+            c_func_count: usage.c_func_count,
+            array_count: usage.array_count,
+            fast_array_count: usage.fast_array_count,
+            fast_array_elements: usage.fast_array_elements,
+            binary_object_count: usage.binary_object_count,
+            binary_object_size: usage.binary_object_size,
+        }
+    }
+}
+
+/// Memory usage of a runtime around a garbage collection pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GcStats {
+    /// The amount of bytes in use before the collection.
+    pub bytes_before: i64,
+    /// The amount of bytes in use after the collection.
+    pub bytes_after: i64,
+    /// The amount of bytes reclaimed by the collection.
+    pub freed: i64,
+}
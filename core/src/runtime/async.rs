@@ -1,8 +1,10 @@
 use std::{
+    any::Any,
     ffi::CString,
     ptr::NonNull,
     result::Result as StdResult,
     sync::{Arc, Weak},
+    time::Instant,
 };
 
 use async_lock::Mutex;
@@ -10,13 +12,18 @@ use async_lock::Mutex;
 #[cfg(feature = "allocator")]
 use crate::allocator::Allocator;
 #[cfg(feature = "loader")]
-use crate::loader::{RawLoader, Resolver};
-use crate::{context::AsyncContext, result::AsyncJobException, Ctx, Error, Exception, Result};
+use crate::loader::{ImportMetaHook, RawLoader, Resolver};
+use crate::{
+    context::AsyncContext,
+    markers::{ParallelSend, ParallelSync},
+    result::AsyncJobException,
+    Ctx, Error, Exception, Result,
+};
 
 use super::{
     raw::{Opaque, RawRuntime},
     spawner::DriveFuture,
-    InterruptHandler, MemoryUsage,
+    GcStats, InterruptHandler, MemoryUsage, RejectionTracker,
 };
 
 #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "futures")))]
@@ -103,6 +110,52 @@ impl AsyncRuntime {
         }
     }
 
+    /// Set a closure which is called when a promise is rejected while no rejection handler is
+    /// attached to it.
+    ///
+    /// The closure receives the rejection reason and `false` at the time of the rejection, if a
+    /// handler is attached to the promise later on it is called again with the same reason and
+    /// `true`. This makes it possible to log or fail on rejections which are never handled,
+    /// which would otherwise go unnoticed.
+    ///
+    /// The reason is only valid for the duration of the call, it can be saved with
+    /// [`Persistent`](crate::Persistent) if it is needed afterwards. Passing `None` removes the
+    /// tracker.
+    pub async fn set_promise_rejection_tracker(&self, tracker: Option<RejectionTracker>) {
+        unsafe {
+            self.inner
+                .lock()
+                .await
+                .set_promise_rejection_tracker(tracker);
+        }
+    }
+
+    /// Set whether panics in rust callbacks are turned into javascript errors.
+    ///
+    /// See [`Runtime::set_panic_to_exception`](crate::Runtime::set_panic_to_exception) for
+    /// details.
+    pub async fn set_panic_to_exception(&self, enabled: bool) {
+        unsafe {
+            self.inner.lock().await.get_opaque_mut().panic_to_exception = enabled;
+        }
+    }
+
+    /// Set a point in time after which running javascript code is interrupted.
+    ///
+    /// See [`Runtime::set_deadline`](crate::Runtime::set_deadline) for details.
+    pub async fn set_deadline(&self, deadline: Instant) {
+        unsafe {
+            self.inner.lock().await.set_deadline(Some(deadline));
+        }
+    }
+
+    /// Remove a deadline set with [`AsyncRuntime::set_deadline`].
+    pub async fn clear_deadline(&self) {
+        unsafe {
+            self.inner.lock().await.set_deadline(None);
+        }
+    }
+
     /// Set the module loader
     #[cfg(feature = "loader")]
     #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "loader")))]
@@ -116,6 +169,21 @@ impl AsyncRuntime {
         }
     }
 
+    /// Set a hook which populates the `import.meta` object of modules loaded by the module
+    /// loader.
+    ///
+    /// Before the hook is called `import.meta.url` is set to the resolved name of the module, the
+    /// hook can add more properties or replace the url. An error returned from the hook fails the
+    /// import of the module. Modules which are declared directly instead of being imported
+    /// through the loader are not passed to the hook.
+    #[cfg(feature = "loader")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "loader")))]
+    pub async fn set_import_meta(&self, hook: Option<ImportMetaHook>) {
+        unsafe {
+            self.inner.lock().await.get_opaque_mut().import_meta = hook;
+        }
+    }
+
     /// Set the info of the runtime
     pub async fn set_info<S: Into<Vec<u8>>>(&self, info: S) -> Result<()> {
         let string = CString::new(info)?;
@@ -125,6 +193,39 @@ impl AsyncRuntime {
         Ok(())
     }
 
+    /// Associate a rust value with the runtime.
+    ///
+    /// See [`Runtime::set_userdata`](crate::Runtime::set_userdata) for details.
+    pub async fn set_userdata<T>(&self, data: T) -> StdResult<(), T>
+    where
+        T: Any + ParallelSend + ParallelSync,
+    {
+        unsafe {
+            self.inner
+                .lock()
+                .await
+                .get_opaque_mut()
+                .userdata
+                .insert(data)
+        }
+    }
+
+    /// Returns the value of the given type associated with the runtime, if any.
+    ///
+    /// See [`Runtime::userdata`](crate::Runtime::userdata) for details.
+    pub async fn userdata<T: Any>(&self) -> Option<&T> {
+        let data = unsafe {
+            self.inner
+                .lock()
+                .await
+                .get_opaque_mut()
+                .userdata
+                .get::<T>()?
+        };
+        // Safety: Values are never removed or replaced and live as long as the runtime.
+        Some(unsafe { &*data })
+    }
+
     /// Set a limit on the max amount of memory the runtime will use.
     ///
     /// Setting the limit to 0 is equivalent to unlimited memory.
@@ -140,6 +241,15 @@ impl AsyncRuntime {
     /// Set a limit on the max size of stack the runtime will use.
     ///
     /// The default values is 256x1024 bytes.
+    ///
+    /// When javascript recurses deeper than the limit quickjs throws an `InternalError` with the
+    /// message `stack overflow`, which is returned as a catchable [`Error::Exception`](crate::Error::Exception) instead of
+    /// crashing the process. Setting the limit to 0 disables the check.
+    ///
+    /// The limit is measured from the position of the stack when the runtime was entered, the
+    /// stack of the host thread still has to be large enough to contain it. A limit larger than
+    /// the remaining stack of the thread will overflow the real stack before quickjs notices and
+    /// abort the process.
     pub async fn set_max_stack_size(&self, limit: usize) {
         unsafe {
             self.inner.lock().await.set_max_stack_size(limit);
@@ -147,12 +257,26 @@ impl AsyncRuntime {
     }
 
     /// Set a memory threshold for garbage collection.
+    ///
+    /// The garbage collector runs automatically once the runtime allocated more memory than the
+    /// threshold. Setting the threshold to `usize::MAX` effectively disables automatic garbage
+    /// collection which can be useful for latency sensitive sections, cyclic garbage then has to
+    /// be collected manually with [`AsyncRuntime::run_gc`].
     pub async fn set_gc_threshold(&self, threshold: usize) {
         unsafe {
             self.inner.lock().await.set_gc_threshold(threshold);
         }
     }
 
+    /// Get the memory threshold for garbage collection.
+    ///
+    /// Returns the value last passed to [`AsyncRuntime::set_gc_threshold`] or the default of 256 KiB.
+    /// Note that quickjs raises the threshold after every automatic collection, the returned value
+    /// is the configured threshold not necessarily the one currently in effect.
+    pub async fn gc_threshold(&self) -> usize {
+        self.inner.lock().await.gc_threshold()
+    }
+
     /// Manually run the garbage collection.
     ///
     /// Most of quickjs values are reference counted and
@@ -165,9 +289,17 @@ impl AsyncRuntime {
         }
     }
 
+    /// Manually run the garbage collection and report how much memory it reclaimed.
+    ///
+    /// Works like [`AsyncRuntime::run_gc`] but measures the memory in use before and after the
+    /// collection.
+    pub async fn run_gc_stats(&self) -> GcStats {
+        unsafe { self.inner.lock().await.run_gc_stats() }
+    }
+
     /// Get memory usage stats
     pub async fn memory_usage(&self) -> MemoryUsage {
-        unsafe { self.inner.lock().await.memory_usage() }
+        unsafe { self.inner.lock().await.memory_usage() }.into()
     }
 
     /// Test for pending jobs
@@ -280,6 +412,8 @@ macro_rules! async_test_case {
     }
     };
 }
+#[cfg(test)]
+pub(crate) use async_test_case;
 
 #[cfg(test)]
 mod test {
@@ -1,16 +1,20 @@
 //! Quickjs runtime related types.
 
 #[cfg(feature = "loader")]
-use crate::loader::{RawLoader, Resolver};
-use crate::{result::JobException, Context, Error, Mut, Ref, Result, Weak};
-use std::{ffi::CString, ptr::NonNull, result::Result as StdResult};
+use crate::loader::{ImportMetaHook, RawLoader, Resolver};
+use crate::{
+    markers::{ParallelSend, ParallelSync},
+    result::JobException,
+    Context, Error, Mut, Ref, Result, Weak,
+};
+use std::{any::Any, ffi::CString, ptr::NonNull, result::Result as StdResult, time::Instant};
 
 #[cfg(feature = "allocator")]
 use crate::allocator::Allocator;
 
 use super::{
     raw::{Opaque, RawRuntime},
-    InterruptHandler, MemoryUsage,
+    GcStats, InterruptHandler, MemoryUsage, RejectionTracker,
 };
 
 /// A weak handle to the runtime.
@@ -78,6 +82,62 @@ impl Runtime {
         }
     }
 
+    /// Set a closure which is called when a promise is rejected while no rejection handler is
+    /// attached to it.
+    ///
+    /// The closure receives the rejection reason and `false` at the time of the rejection, if a
+    /// handler is attached to the promise later on it is called again with the same reason and
+    /// `true`. This makes it possible to log or fail on rejections which are never handled,
+    /// which would otherwise go unnoticed.
+    ///
+    /// The reason is only valid for the duration of the call, it can be saved with
+    /// [`Persistent`](crate::Persistent) if it is needed afterwards. Passing `None` removes the
+    /// tracker. A panic in the tracker is resumed once the job or [`Context::with`] call during
+    /// which the promise was rejected returns.
+    pub fn set_promise_rejection_tracker(&self, tracker: Option<RejectionTracker>) {
+        unsafe {
+            self.inner.lock().set_promise_rejection_tracker(tracker);
+        }
+    }
+
+    /// Set whether panics in rust callbacks are turned into javascript errors.
+    ///
+    /// By default a panic in a function called from javascript is caught before it reaches the
+    /// engine and thrown as an `Error` with the panic message, which javascript code can catch
+    /// like any other error. The values held by the callback are dropped while unwinding as
+    /// usual. Note that the panic hook still runs, so the panic is still reported on stderr
+    /// with the default hook.
+    ///
+    /// When disabled the panic is carried through the engine instead and resumed once control
+    /// returns to the rust code which called into javascript.
+    pub fn set_panic_to_exception(&self, enabled: bool) {
+        unsafe {
+            self.inner.lock().get_opaque_mut().panic_to_exception = enabled;
+        }
+    }
+
+    /// Set a point in time after which running javascript code is interrupted.
+    ///
+    /// Once the deadline has passed the interpreter raises an uncatchable exception which is
+    /// returned as [`Error::Interrupted`]. The deadline is checked from the same callback as the
+    /// interrupt handler, which quickjs only calls every so many instructions, so code may run
+    /// slightly past the deadline, especially tight loops which don't allocate.
+    ///
+    /// The deadline is independent of the interrupt handler, setting one does not replace the
+    /// other.
+    pub fn set_deadline(&self, deadline: Instant) {
+        unsafe {
+            self.inner.lock().set_deadline(Some(deadline));
+        }
+    }
+
+    /// Remove a deadline set with [`Runtime::set_deadline`].
+    pub fn clear_deadline(&self) {
+        unsafe {
+            self.inner.lock().set_deadline(None);
+        }
+    }
+
     /// Set the module loader
     #[cfg(feature = "loader")]
     #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "loader")))]
@@ -91,6 +151,21 @@ impl Runtime {
         }
     }
 
+    /// Set a hook which populates the `import.meta` object of modules loaded by the module
+    /// loader.
+    ///
+    /// Before the hook is called `import.meta.url` is set to the resolved name of the module, the
+    /// hook can add more properties or replace the url. An error returned from the hook fails the
+    /// import of the module. Modules which are declared directly instead of being imported
+    /// through the loader are not passed to the hook.
+    #[cfg(feature = "loader")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "loader")))]
+    pub fn set_import_meta(&self, hook: Option<ImportMetaHook>) {
+        unsafe {
+            self.inner.lock().get_opaque_mut().import_meta = hook;
+        }
+    }
+
     /// Set the info of the runtime
     pub fn set_info<S: Into<Vec<u8>>>(&self, info: S) -> Result<()> {
         let string = CString::new(info)?;
@@ -100,6 +175,34 @@ impl Runtime {
         Ok(())
     }
 
+    /// Associate a rust value with the runtime.
+    ///
+    /// One value can be stored per type, it can be retrieved with [`Runtime::userdata`] or with
+    /// [`Ctx::runtime_userdata`](crate::Ctx::runtime_userdata) from within callbacks. A value
+    /// can't be replaced once it is set, so references to it stay valid for the lifetime of the
+    /// runtime. If a value of the same type is already stored the given value is returned back,
+    /// use interior mutability for data which has to change.
+    ///
+    /// Quickjs runtimes are single threaded, so the value is only ever accessed by the thread
+    /// which currently holds the runtime. With the `parallel` feature the runtime can move
+    /// between threads and be shared, which is why the value then has to be `Send` and `Sync`.
+    pub fn set_userdata<T>(&self, data: T) -> StdResult<(), T>
+    where
+        T: Any + ParallelSend + ParallelSync,
+    {
+        unsafe { self.inner.lock().get_opaque_mut().userdata.insert(data) }
+    }
+
+    /// Returns the value of the given type associated with the runtime, if any.
+    ///
+    /// This locks the runtime, from within callbacks use
+    /// [`Ctx::runtime_userdata`](crate::Ctx::runtime_userdata) instead.
+    pub fn userdata<T: Any>(&self) -> Option<&T> {
+        let data = unsafe { self.inner.lock().get_opaque_mut().userdata.get::<T>()? };
+        // Safety: Values are never removed or replaced and live as long as the runtime.
+        Some(unsafe { &*data })
+    }
+
     /// Set a limit on the max amount of memory the runtime will use.
     ///
     /// Setting the limit to 0 is equivalent to unlimited memory.
@@ -115,6 +218,15 @@ impl Runtime {
     /// Set a limit on the max size of stack the runtime will use.
     ///
     /// The default values is 256x1024 bytes.
+    ///
+    /// When javascript recurses deeper than the limit quickjs throws an `InternalError` with the
+    /// message `stack overflow`, which is returned as a catchable [`Error::Exception`] instead of
+    /// crashing the process. Setting the limit to 0 disables the check.
+    ///
+    /// The limit is measured from the position of the stack when the runtime was entered, the
+    /// stack of the host thread still has to be large enough to contain it. A limit larger than
+    /// the remaining stack of the thread will overflow the real stack before quickjs notices and
+    /// abort the process.
     pub fn set_max_stack_size(&self, limit: usize) {
         unsafe {
             self.inner.lock().set_max_stack_size(limit);
@@ -122,12 +234,26 @@ impl Runtime {
     }
 
     /// Set a memory threshold for garbage collection.
+    ///
+    /// The garbage collector runs automatically once the runtime allocated more memory than the
+    /// threshold. Setting the threshold to `usize::MAX` effectively disables automatic garbage
+    /// collection which can be useful for latency sensitive sections, cyclic garbage then has to
+    /// be collected manually with [`Runtime::run_gc`].
     pub fn set_gc_threshold(&self, threshold: usize) {
         unsafe {
             self.inner.lock().set_gc_threshold(threshold);
         }
     }
 
+    /// Get the memory threshold for garbage collection.
+    ///
+    /// Returns the value last passed to [`Runtime::set_gc_threshold`] or the default of 256 KiB.
+    /// Note that quickjs raises the threshold after every automatic collection, the returned value
+    /// is the configured threshold not necessarily the one currently in effect.
+    pub fn gc_threshold(&self) -> usize {
+        self.inner.lock().gc_threshold()
+    }
+
     /// Manually run the garbage collection.
     ///
     /// Most of quickjs values are reference counted and
@@ -140,9 +266,17 @@ impl Runtime {
         }
     }
 
+    /// Manually run the garbage collection and report how much memory it reclaimed.
+    ///
+    /// Works like [`Runtime::run_gc`] but measures the memory in use before and after the
+    /// collection.
+    pub fn run_gc_stats(&self) -> GcStats {
+        unsafe { self.inner.lock().run_gc_stats() }
+    }
+
     /// Get memory usage stats
     pub fn memory_usage(&self) -> MemoryUsage {
-        unsafe { self.inner.lock().memory_usage() }
+        unsafe { self.inner.lock().memory_usage() }.into()
     }
 
     /// Test for pending jobs
@@ -185,6 +319,46 @@ unsafe impl Sync for WeakRuntime {}
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::{CatchResultExt, CaughtError, Exception};
+    #[test]
+    fn userdata() {
+        use crate::Function;
+
+        struct Config {
+            name: &'static str,
+            limit: u32,
+        }
+
+        let rt = Runtime::new().unwrap();
+        assert!(rt.userdata::<Config>().is_none());
+        assert!(rt
+            .set_userdata(Config {
+                name: "tenant",
+                limit: 3,
+            })
+            .is_ok());
+        assert!(rt
+            .set_userdata(Config {
+                name: "other",
+                limit: 0
+            })
+            .is_err());
+        assert_eq!(rt.userdata::<Config>().unwrap().limit, 3);
+
+        let ctx = Context::full(&rt).unwrap();
+        ctx.with(|ctx| {
+            let describe = Function::new(ctx.clone(), |ctx: crate::Ctx| {
+                let config = ctx.runtime_userdata::<Config>().unwrap();
+                format!("{}:{}", config.name, config.limit)
+            })
+            .unwrap();
+            ctx.globals().set("describe", describe).unwrap();
+            let res: String = ctx.eval("describe()").unwrap();
+            assert_eq!(res, "tenant:3");
+            assert!(ctx.runtime_userdata::<u32>().is_none());
+        });
+    }
+
     #[test]
     fn base_runtime() {
         let rt = Runtime::new().unwrap();
@@ -193,4 +367,192 @@ mod test {
         rt.set_gc_threshold(0xFF);
         rt.run_gc();
     }
+
+    #[test]
+    fn promise_rejection_tracker() {
+        use std::sync::{Arc, Mutex};
+
+        let rt = Runtime::new().unwrap();
+        let tracked = Arc::new(Mutex::new(Vec::new()));
+        let sink = tracked.clone();
+        rt.set_promise_rejection_tracker(Some(Box::new(move |reason, handled| {
+            let exception = reason.into_object().and_then(Exception::from_object);
+            let message = exception.unwrap().message().unwrap();
+            sink.lock().unwrap().push((message, handled));
+        })));
+
+        let ctx = Context::full(&rt).unwrap();
+        ctx.with(|ctx| {
+            ctx.eval::<(), _>(
+                r#"
+                globalThis.unhandled = Promise.reject(new Error("boom"));
+                let reject;
+                new Promise((_, r) => reject = r).catch(() => {});
+                reject(new Error("handled before rejection"));
+                "#,
+            )
+            .unwrap();
+        });
+        while rt.execute_pending_job().unwrap() {}
+        assert_eq!(*tracked.lock().unwrap(), [("boom".to_string(), false)]);
+
+        ctx.with(|ctx| {
+            ctx.eval::<(), _>("unhandled.catch(() => {})").unwrap();
+        });
+        while rt.execute_pending_job().unwrap() {}
+        assert_eq!(
+            *tracked.lock().unwrap(),
+            [("boom".to_string(), false), ("boom".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn panic_in_rejection_tracker() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let rt = Runtime::new().unwrap();
+        rt.set_promise_rejection_tracker(Some(Box::new(|_, _| panic!("tracker panic"))));
+        let ctx = Context::full(&rt).unwrap();
+
+        // Rejected during `with`, resumed once the closure returns.
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            ctx.with(|ctx| {
+                ctx.eval::<(), _>("Promise.reject(1)").unwrap();
+            })
+        }));
+        let panic = res.unwrap_err();
+        assert_eq!(panic.downcast_ref::<&str>(), Some(&"tracker panic"));
+
+        // Rejected during a job, resumed once the job returns.
+        ctx.with(|ctx| {
+            ctx.eval::<(), _>("Promise.resolve().then(() => { throw 1 })")
+                .unwrap();
+        });
+        let res = panic::catch_unwind(AssertUnwindSafe(|| rt.execute_pending_job()));
+        let panic = res.unwrap_err();
+        assert_eq!(panic.downcast_ref::<&str>(), Some(&"tracker panic"));
+
+        // The runtime is still usable afterwards.
+        while rt.execute_pending_job().unwrap() {}
+        ctx.with(|ctx| assert_eq!(ctx.eval::<i32, _>("1 + 1").unwrap(), 2));
+    }
+
+    #[test]
+    fn stack_overflow() {
+        let rt = Runtime::new().unwrap();
+        rt.set_max_stack_size(128 * 1024);
+        let ctx = Context::full(&rt).unwrap();
+        ctx.with(|ctx| {
+            let res = ctx
+                .eval::<(), _>("function recurse(n) { return recurse(n + 1) + 1; } recurse(0)")
+                .catch(&ctx);
+            match res {
+                Err(CaughtError::Exception(e)) => {
+                    assert_eq!(e.message().as_deref(), Some("stack overflow"))
+                }
+                _ => panic!("expected a stack overflow exception"),
+            }
+
+            // The context is still usable after the error.
+            let res: i32 = ctx.eval("1 + 1").unwrap();
+            assert_eq!(res, 2);
+        });
+    }
+
+    #[test]
+    fn gc_threshold() {
+        let rt = Runtime::new().unwrap();
+        assert_eq!(rt.gc_threshold(), 256 * 1024);
+        rt.set_gc_threshold(usize::MAX);
+        assert_eq!(rt.gc_threshold(), usize::MAX);
+
+        let ctx = Context::full(&rt).unwrap();
+        ctx.with(|ctx| {
+            ctx.eval::<(), _>(
+                r#"
+                for (let i = 0; i < 10000; i++) {
+                    const a = {};
+                    const b = { a };
+                    a.b = b;
+                }
+                "#,
+            )
+            .unwrap();
+        });
+        // The cycles are only reclaimed by the collector, so they are all still alive.
+        let before = rt.memory_usage();
+        let stats = rt.run_gc_stats();
+        let after = rt.memory_usage();
+        assert!(stats.freed > 0);
+        assert!(before.obj_count - after.obj_count >= 20000);
+    }
+
+    #[test]
+    fn memory_usage() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        let before = rt.memory_usage();
+        ctx.with(|ctx| {
+            // Keep the allocations alive until the second measurement.
+            ctx.eval::<(), _>(
+                r#"
+                globalThis.buffer = new ArrayBuffer(1 << 20);
+                globalThis.objects = [{}, {}, {}];
+            "#,
+            )
+            .unwrap();
+        });
+        let after = rt.memory_usage();
+        assert!(after.malloc_size >= before.malloc_size + (1 << 20));
+        assert!(after.obj_count > before.obj_count);
+    }
+
+    #[test]
+    fn gc_stats() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        ctx.with(|ctx| {
+            ctx.eval::<(), _>(
+                r#"
+                let items = [];
+                for (let i = 0; i < 1000; i++) {
+                    const item = { data: new Array(64).fill(i) };
+                    item.self = item;
+                    items.push(item);
+                }
+                items = null;
+            "#,
+            )
+            .unwrap();
+        });
+
+        let stats = rt.run_gc_stats();
+        assert!(stats.freed > 0);
+        assert_eq!(stats.freed, stats.bytes_before - stats.bytes_after);
+    }
+
+    #[test]
+    fn deadline() {
+        use std::time::Duration;
+
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+
+        let start = Instant::now();
+        rt.set_deadline(start + Duration::from_millis(50));
+        ctx.with(|ctx| {
+            let res = ctx.eval::<(), _>("while(true){}");
+            assert!(matches!(res, Err(Error::Interrupted)));
+        });
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(50));
+        assert!(elapsed < Duration::from_secs(2));
+
+        rt.clear_deadline();
+        ctx.with(|ctx| {
+            let _ = ctx.catch();
+            let res: i32 = ctx.eval("let i = 0; while(i < 100000){ i++ }; i").unwrap();
+            assert_eq!(res, 100000);
+        });
+    }
 }
@@ -1,29 +1,66 @@
 use std::{
-    any::Any, ffi::CString, marker::PhantomData, mem, panic, ptr::NonNull,
+    any::Any,
+    collections::HashMap,
+    ffi::CString,
+    marker::PhantomData,
+    mem,
+    panic::{self, AssertUnwindSafe},
+    ptr::NonNull,
     result::Result as StdResult,
+    time::Instant,
 };
 
 #[cfg(feature = "allocator")]
 use crate::allocator::{Allocator, AllocatorHolder};
 #[cfg(feature = "loader")]
-use crate::loader::{LoaderHolder, RawLoader, Resolver};
-use crate::qjs;
+use crate::loader::{ImportMetaHook, LoaderHolder, RawLoader, Resolver};
+use crate::{qjs, userdata::UserDataMap, Ctx, Value};
 
 #[cfg(feature = "futures")]
 use super::spawner::Spawner;
-use super::InterruptHandler;
+use super::{GcStats, InterruptHandler, RejectionTracker};
 
 /// Opaque book keeping data for rust.
 pub(crate) struct Opaque<'js> {
     /// Used to carry a panic if a callback triggered one.
     pub panic: Option<Box<dyn Any + Send + 'static>>,
+    /// Whether panics in callbacks are turned into javascript errors instead of being carried.
+    pub panic_to_exception: bool,
 
     /// The user provided interrupt handler, if any.
     pub interrupt_handler: Option<InterruptHandler>,
 
+    /// The deadline after which execution is interrupted, if any.
+    pub deadline: Option<Instant>,
+    /// Set when execution was interrupted because the deadline passed.
+    pub deadline_exceeded: bool,
+
+    /// The user provided promise rejection tracker, if any.
+    pub rejection_tracker: Option<RejectionTracker>,
+
+    /// The user provided hook to populate `import.meta` of loaded modules, if any.
+    #[cfg(feature = "loader")]
+    pub import_meta: Option<ImportMetaHook>,
+
+    /// The module loader, if any.
+    #[cfg(feature = "loader")]
+    pub loader: Option<LoaderHolder>,
+    /// A loader which replaces the current one once no loader callback is running anymore.
+    #[cfg(feature = "loader")]
+    pub pending_loader: Option<LoaderHolder>,
+    /// The number of loader callbacks which are currently running.
+    #[cfg(feature = "loader")]
+    pub loader_depth: usize,
+
     #[cfg(feature = "futures")]
     pub spawner: Option<Spawner<'js>>,
 
+    /// The rust values associated with the runtime.
+    pub userdata: UserDataMap,
+
+    /// The class id of the base class of rust classes which extend another class.
+    pub class_bases: HashMap<qjs::JSClassID, qjs::JSClassID>,
+
     _marker: PhantomData<&'js ()>,
 }
 
@@ -31,9 +68,23 @@ impl<'js> Opaque<'js> {
     pub fn new() -> Self {
         Opaque {
             panic: None,
+            panic_to_exception: true,
             interrupt_handler: None,
+            deadline: None,
+            deadline_exceeded: false,
+            rejection_tracker: None,
+            #[cfg(feature = "loader")]
+            import_meta: None,
+            #[cfg(feature = "loader")]
+            loader: None,
+            #[cfg(feature = "loader")]
+            pending_loader: None,
+            #[cfg(feature = "loader")]
+            loader_depth: 0,
             #[cfg(feature = "futures")]
             spawner: None,
+            userdata: UserDataMap::default(),
+            class_bases: HashMap::new(),
             _marker: PhantomData,
         }
     }
@@ -42,9 +93,23 @@ impl<'js> Opaque<'js> {
     pub fn with_spawner() -> Self {
         Opaque {
             panic: None,
+            panic_to_exception: true,
             interrupt_handler: None,
+            deadline: None,
+            deadline_exceeded: false,
+            rejection_tracker: None,
+            #[cfg(feature = "loader")]
+            import_meta: None,
+            #[cfg(feature = "loader")]
+            loader: None,
+            #[cfg(feature = "loader")]
+            pending_loader: None,
+            #[cfg(feature = "loader")]
+            loader_depth: 0,
             #[cfg(feature = "futures")]
             spawner: Some(Spawner::new()),
+            userdata: UserDataMap::default(),
+            class_bases: HashMap::new(),
             _marker: PhantomData,
         }
     }
@@ -57,6 +122,9 @@ impl<'js> Opaque<'js> {
     }
 }
 
+/// The threshold quickjs uses for new runtimes.
+const DEFAULT_GC_THRESHOLD: usize = 256 * 1024;
+
 pub(crate) struct RawRuntime {
     pub(crate) rt: NonNull<qjs::JSRuntime>,
 
@@ -64,12 +132,12 @@ pub(crate) struct RawRuntime {
     #[allow(dead_code)]
     pub info: Option<CString>,
 
+    /// The last threshold passed to `JS_SetGCThreshold`, quickjs provides no getter.
+    pub gc_threshold: usize,
+
     #[cfg(feature = "allocator")]
     #[allow(dead_code)]
     pub allocator: Option<AllocatorHolder>,
-    #[cfg(feature = "loader")]
-    #[allow(dead_code)]
-    pub loader: Option<LoaderHolder>,
 }
 
 #[cfg(feature = "parallel")]
@@ -106,10 +174,9 @@ impl RawRuntime {
         Some(RawRuntime {
             rt,
             info: None,
+            gc_threshold: DEFAULT_GC_THRESHOLD,
             #[cfg(feature = "allocator")]
             allocator: None,
-            #[cfg(feature = "loader")]
-            loader: None,
         })
     }
 
@@ -131,9 +198,8 @@ impl RawRuntime {
         Some(RawRuntime {
             rt,
             info: None,
+            gc_threshold: DEFAULT_GC_THRESHOLD,
             allocator: Some(allocator),
-            #[cfg(feature = "loader")]
-            loader: None,
         })
     }
 
@@ -148,6 +214,14 @@ impl RawRuntime {
         &mut *(qjs::JS_GetRuntimeOpaque(self.rt.as_ptr()) as *mut _)
     }
 
+    /// Continue a panic of a callback which could not be reported as an exception, like one in
+    /// the promise rejection tracker.
+    pub fn resume_panic(&mut self) {
+        if let Some(panic) = unsafe { self.get_opaque_mut() }.panic.take() {
+            panic::resume_unwind(panic)
+        }
+    }
+
     pub fn is_job_pending(&self) -> bool {
         0 != unsafe { qjs::JS_IsJobPending(self.rt.as_ptr()) }
     }
@@ -156,6 +230,7 @@ impl RawRuntime {
         let mut ctx_ptr = mem::MaybeUninit::<*mut qjs::JSContext>::uninit();
         self.update_stack_top();
         let result = unsafe { qjs::JS_ExecutePendingJob(self.rt.as_ptr(), ctx_ptr.as_mut_ptr()) };
+        self.resume_panic();
         if result == 0 {
             // no jobs executed
             return Ok(false);
@@ -173,9 +248,7 @@ impl RawRuntime {
         R: Resolver + 'static,
         L: RawLoader + 'static,
     {
-        let loader = LoaderHolder::new(resolver, loader);
-        loader.set_to_runtime(self.rt.as_ptr());
-        self.loader = Some(loader);
+        LoaderHolder::new(resolver, loader).install(self.rt.as_ptr());
     }
 
     /// Set the info of the runtime
@@ -202,8 +275,14 @@ impl RawRuntime {
     }
 
     /// Set a memory threshold for garbage collection.
-    pub unsafe fn set_gc_threshold(&self, threshold: usize) {
+    pub unsafe fn set_gc_threshold(&mut self, threshold: usize) {
         qjs::JS_SetGCThreshold(self.rt.as_ptr(), threshold as _);
+        self.gc_threshold = threshold;
+    }
+
+    /// Get the last set memory threshold for garbage collection.
+    pub fn gc_threshold(&self) -> usize {
+        self.gc_threshold
     }
 
     /// Manually run the garbage collection.
@@ -216,6 +295,18 @@ impl RawRuntime {
         qjs::JS_RunGC(self.rt.as_ptr());
     }
 
+    /// Run the garbage collection and report the memory usage before and after.
+    pub unsafe fn run_gc_stats(&mut self) -> GcStats {
+        let bytes_before = self.memory_usage().memory_used_size;
+        self.run_gc();
+        let bytes_after = self.memory_usage().memory_used_size;
+        GcStats {
+            bytes_before,
+            bytes_after,
+            freed: bytes_before - bytes_after,
+        }
+    }
+
     /// Get memory usage stats
     pub unsafe fn memory_usage(&mut self) -> qjs::JSMemoryUsage {
         let mut stats = mem::MaybeUninit::uninit();
@@ -227,10 +318,77 @@ impl RawRuntime {
     /// If the provided closure returns `true` the interpreter will raise and uncatchable
     /// exception and return control flow to the caller.
     pub unsafe fn set_interrupt_handler(&mut self, handler: Option<InterruptHandler>) {
+        self.get_opaque_mut().interrupt_handler = handler;
+        self.update_interrupt_handler();
+    }
+
+    /// Set a deadline after which the interpreter will raise an uncatchable exception.
+    pub unsafe fn set_deadline(&mut self, deadline: Option<Instant>) {
+        let opaque = self.get_opaque_mut();
+        opaque.deadline = deadline;
+        opaque.deadline_exceeded = false;
+        self.update_interrupt_handler();
+    }
+
+    /// Set a closure which is called when a promise is rejected without a handler, with `false`,
+    /// and when a handler is later attached to such a promise, with `true`.
+    pub unsafe fn set_promise_rejection_tracker(&mut self, tracker: Option<RejectionTracker>) {
+        unsafe extern "C" fn rejection_tracker_trampoline(
+            ctx: *mut qjs::JSContext,
+            _promise: qjs::JSValue,
+            reason: qjs::JSValue,
+            is_handled: qjs::c_int,
+            opaque: *mut qjs::c_void,
+        ) {
+            let tracker = match (*(opaque as *const Opaque)).rejection_tracker.as_ref() {
+                Some(tracker) => tracker,
+                None => return,
+            };
+            let ctx = Ctx::from_ptr(ctx);
+            let reason = Value::from_js_value_const(ctx, reason);
+            // Quickjs doesn't check for exceptions after calling the tracker, so a panic is kept
+            // and resumed once the job or `with` call which triggered it returns.
+            if let Err(panic) =
+                panic::catch_unwind(AssertUnwindSafe(|| tracker(reason, is_handled != 0)))
+            {
+                (*(opaque as *mut Opaque)).panic = Some(panic);
+            }
+        }
+
+        let has_tracker = tracker.is_some();
+        self.get_opaque_mut().rejection_tracker = tracker;
+        qjs::JS_SetHostPromiseRejectionTracker(
+            self.rt.as_ptr(),
+            has_tracker.then_some(rejection_tracker_trampoline as _),
+            qjs::JS_GetRuntimeOpaque(self.rt.as_ptr()),
+        );
+    }
+
+    unsafe fn update_interrupt_handler(&mut self) {
+        Self::update_interrupt_handler_raw(self.rt.as_ptr());
+    }
+
+    /// Install or remove the interrupt callback depending on whether an interrupt handler or a
+    /// deadline is set.
+    ///
+    /// # Safety
+    /// The runtime must be a runtime created by rquickjs which is currently locked.
+    pub(crate) unsafe fn update_interrupt_handler_raw(rt: *mut qjs::JSRuntime) {
         unsafe extern "C" fn interrupt_handler_trampoline(
             _rt: *mut qjs::JSRuntime,
             opaque: *mut ::std::os::raw::c_void,
         ) -> ::std::os::raw::c_int {
+            let opaque_ref = &mut *(opaque as *mut Opaque);
+            if let Some(deadline) = opaque_ref.deadline {
+                if Instant::now() >= deadline {
+                    opaque_ref.deadline_exceeded = true;
+                    return true as _;
+                }
+            }
+            if opaque_ref.interrupt_handler.is_none() {
+                return false as _;
+            }
+
             let should_interrupt = match panic::catch_unwind(move || {
                 let opaque = &mut *(opaque as *mut Opaque);
                 opaque.interrupt_handler.as_mut().expect("handler is set")()
@@ -248,11 +406,12 @@ impl RawRuntime {
             should_interrupt as _
         }
 
+        let opaque = &*(qjs::JS_GetRuntimeOpaque(rt) as *mut Opaque);
+        let has_handler = opaque.interrupt_handler.is_some() || opaque.deadline.is_some();
         qjs::JS_SetInterruptHandler(
-            self.rt.as_ptr(),
-            handler.as_ref().map(|_| interrupt_handler_trampoline as _),
-            qjs::JS_GetRuntimeOpaque(self.rt.as_ptr()),
+            rt,
+            has_handler.then_some(interrupt_handler_trampoline as _),
+            qjs::JS_GetRuntimeOpaque(rt),
         );
-        self.get_opaque_mut().interrupt_handler = handler;
     }
 }
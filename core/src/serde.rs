@@ -0,0 +1,133 @@
+//! Conversion between javascript values and rust types implementing the serde traits.
+//!
+//! Values are mapped to javascript like the json representation of serde would map them:
+//! structs and maps become objects, sequences and tuples become arrays and `None` and unit
+//! values become `null`. Enums use the externally tagged representation, unit variants are
+//! strings and all other variants are objects with the variant name as the single key.
+//!
+//! ```
+//! # use rquickjs::{Runtime, Context, serde::{from_value, to_value}};
+//! # let rt = Runtime::new().unwrap();
+//! # let ctx = Context::full(&rt).unwrap();
+//! # ctx.with(|ctx| {
+//! let value = to_value(ctx.clone(), &vec![(1, "a"), (2, "b")]).unwrap();
+//! ctx.globals().set("list", value).unwrap();
+//! let value = ctx.eval("list.map(([n, s]) => [n * 2, s])").unwrap();
+//! let list: Vec<(u32, String)> = from_value(value).unwrap();
+//! assert_eq!(list, vec![(2, "a".to_string()), (4, "b".to_string())]);
+//! # })
+//! ```
+
+use std::fmt::Display;
+
+use crate::Error;
+
+mod de;
+mod ser;
+
+pub use de::{from_value, Deserializer};
+pub use ser::{to_value, Serializer};
+
+impl ::serde::ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::new_into_js_message("value", "value", msg.to_string())
+    }
+}
+
+impl ::serde::de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::new_from_js_message("value", "value", msg.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{from_value, to_value};
+    use crate::{test_with, StdString, Value};
+    use ::serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Rect { width: u32, height: u32 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Config {
+        name: StdString,
+        retries: Option<u32>,
+        tags: Vec<StdString>,
+        shapes: Vec<Shape>,
+        nested: Option<Box<Config>>,
+    }
+
+    #[test]
+    fn round_trip() {
+        test_with(|ctx| {
+            let config = Config {
+                name: "outer".into(),
+                retries: Some(3),
+                tags: vec!["a".into(), "b".into()],
+                shapes: vec![
+                    Shape::Empty,
+                    Shape::Circle(1.5),
+                    Shape::Rect {
+                        width: 2,
+                        height: 4,
+                    },
+                ],
+                nested: Some(Box::new(Config {
+                    name: "inner".into(),
+                    retries: None,
+                    tags: Vec::new(),
+                    shapes: Vec::new(),
+                    nested: None,
+                })),
+            };
+            let value = to_value(ctx.clone(), &config).unwrap();
+            ctx.globals().set("config", value).unwrap();
+
+            let res: bool = ctx
+                .eval(
+                    r#"
+                    config.name === "outer" &&
+                    config.retries === 3 &&
+                    config.tags.join() === "a,b" &&
+                    config.shapes[0] === "Empty" &&
+                    config.shapes[1].Circle === 1.5 &&
+                    config.shapes[2].Rect.height === 4 &&
+                    config.nested.retries === null
+                "#,
+                )
+                .unwrap();
+            assert!(res);
+
+            let value: Value = ctx.eval("config.retries = 2 ** 40; config").unwrap();
+            let res: Result<Config, _> = from_value(value);
+            assert!(res.is_err());
+
+            let value: Value = ctx.eval("config.retries = 3; config").unwrap();
+            assert_eq!(from_value::<Config>(value).unwrap(), config);
+        })
+    }
+
+    #[test]
+    fn numbers() {
+        test_with(|ctx| {
+            let value = to_value(ctx.clone(), &u64::MAX).unwrap();
+            assert!(value.is_float());
+            let value = to_value(ctx.clone(), &(i32::MAX as i64)).unwrap();
+            assert!(value.is_int());
+            assert!(to_value(ctx.clone(), &f64::NAN).is_err());
+            assert!(to_value(ctx.clone(), &f64::INFINITY).is_err());
+
+            let value: Value = ctx.eval("2 ** 40").unwrap();
+            assert_eq!(from_value::<u64>(value).unwrap(), 1u64 << 40);
+            let value: Value = ctx.eval("0.5").unwrap();
+            assert_eq!(from_value::<f64>(value).unwrap(), 0.5);
+            let value: Value = ctx.eval("0.5").unwrap();
+            assert!(from_value::<u32>(value).is_err());
+        })
+    }
+}
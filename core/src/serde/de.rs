@@ -0,0 +1,252 @@
+use ::serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+
+use crate::{Array, Error, Object, Result, StdString, Type, Value};
+
+/// Convert a javascript value into a type implementing [`Deserialize`](::serde::Deserialize).
+pub fn from_value<'js, T>(value: Value<'js>) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(Deserializer::from(value))
+}
+
+/// The largest integer which can be represented exactly as a javascript number.
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+/// A serde deserializer which reads javascript values.
+pub struct Deserializer<'js> {
+    value: Value<'js>,
+}
+
+impl<'js> From<Value<'js>> for Deserializer<'js> {
+    fn from(value: Value<'js>) -> Self {
+        Deserializer { value }
+    }
+}
+
+impl<'js> Deserializer<'js> {
+    fn unsupported(&self) -> Error {
+        Error::new_from_js_message(
+            self.value.type_name(),
+            "value",
+            "type can't be deserialized",
+        )
+    }
+}
+
+impl<'de, 'js> de::Deserializer<'de> for Deserializer<'js> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value.type_of() {
+            Type::Uninitialized | Type::Undefined | Type::Null => visitor.visit_unit(),
+            Type::Bool => visitor.visit_bool(self.value.as_bool().unwrap()),
+            Type::Int => visitor.visit_i32(self.value.as_int().unwrap()),
+            Type::Float => {
+                let v = self.value.as_float().unwrap();
+                // Numbers without a fractional part are visited as integers so they can be
+                // deserialized into integer types.
+                if v.fract() == 0.0 && v.abs() <= MAX_SAFE_INTEGER {
+                    visitor.visit_i64(v as i64)
+                } else {
+                    visitor.visit_f64(v)
+                }
+            }
+            Type::String => visitor.visit_string(self.value.get::<StdString>()?),
+            Type::Array => {
+                let array = Array::from_value(self.value)?;
+                visitor.visit_seq(SeqAccess {
+                    len: array.len(),
+                    array,
+                    index: 0,
+                })
+            }
+            Type::Object => {
+                let object = Object::from_value(self.value)?;
+                let keys = object.keys::<StdString>().collect::<Result<Vec<_>>>()?;
+                visitor.visit_map(MapAccess {
+                    object,
+                    keys: keys.into_iter(),
+                    value: None,
+                })
+            }
+            _ => Err(self.unsupported()),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.value.type_of().is_void() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.value.is_string() {
+            let variant = self.value.get::<StdString>()?;
+            return visitor.visit_enum(variant.into_deserializer());
+        }
+        let object = match self.value.into_object() {
+            Some(object) if !object.is_array() => object,
+            _ => {
+                return Err(Error::new_from_js_message(
+                    "value",
+                    "enum",
+                    "expected a string or an object with a single key",
+                ))
+            }
+        };
+        let mut keys = object.keys::<StdString>();
+        let variant = match (keys.next(), keys.next()) {
+            (Some(variant), None) => variant?,
+            _ => {
+                return Err(Error::new_from_js_message(
+                    "object",
+                    "enum",
+                    "expected an object with a single key",
+                ))
+            }
+        };
+        let value = object.get(variant.as_str())?;
+        visitor.visit_enum(EnumAccess { variant, value })
+    }
+
+    ::serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct SeqAccess<'js> {
+    array: Array<'js>,
+    index: usize,
+    len: usize,
+}
+
+impl<'de, 'js> de::SeqAccess<'de> for SeqAccess<'js> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        let value: Value = self.array.get(self.index)?;
+        self.index += 1;
+        seed.deserialize(Deserializer::from(value)).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.index)
+    }
+}
+
+struct MapAccess<'js> {
+    object: Object<'js>,
+    keys: std::vec::IntoIter<StdString>,
+    value: Option<Value<'js>>,
+}
+
+impl<'de, 'js> de::MapAccess<'de> for MapAccess<'js> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let Some(key) = self.keys.next() else {
+            return Ok(None);
+        };
+        self.value = Some(self.object.get(key.as_str())?);
+        seed.deserialize(IntoDeserializer::<Error>::into_deserializer(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(Deserializer::from(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.keys.len())
+    }
+}
+
+struct EnumAccess<'js> {
+    variant: StdString,
+    value: Value<'js>,
+}
+
+impl<'de, 'js> de::EnumAccess<'de> for EnumAccess<'js> {
+    type Error = Error;
+    type Variant = Deserializer<'js>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Deserializer<'js>)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant =
+            seed.deserialize(IntoDeserializer::<Error>::into_deserializer(self.variant))?;
+        Ok((variant, Deserializer::from(self.value)))
+    }
+}
+
+impl<'de, 'js> de::VariantAccess<'de> for Deserializer<'js> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
@@ -0,0 +1,409 @@
+use ::serde::ser::{self, Serialize};
+
+use crate::{Array, Atom, Ctx, Error, Object, Result, String, Value};
+
+/// Convert a value implementing [`Serialize`] into a javascript value.
+pub fn to_value<'js, T>(ctx: Ctx<'js>, value: &T) -> Result<Value<'js>>
+where
+    T: Serialize + ?Sized,
+{
+    value.serialize(Serializer::new(ctx))
+}
+
+/// A serde serializer which produces javascript values.
+pub struct Serializer<'js> {
+    ctx: Ctx<'js>,
+}
+
+impl<'js> Serializer<'js> {
+    /// Create a serializer for values of the given context.
+    pub fn new(ctx: Ctx<'js>) -> Self {
+        Serializer { ctx }
+    }
+
+    fn int(self, value: i64) -> Value<'js> {
+        match i32::try_from(value) {
+            Ok(value) => Value::new_int(self.ctx, value),
+            Err(_) => Value::new_float(self.ctx, value as f64),
+        }
+    }
+
+    fn tagged(&self, variant: &'static str, value: Value<'js>) -> Result<Value<'js>> {
+        let object = Object::new(self.ctx.clone())?;
+        object.set(variant, value)?;
+        Ok(object.into_value())
+    }
+}
+
+impl<'js> ser::Serializer for Serializer<'js> {
+    type Ok = Value<'js>;
+    type Error = Error;
+
+    type SerializeSeq = SerializeArray<'js>;
+    type SerializeTuple = SerializeArray<'js>;
+    type SerializeTupleStruct = SerializeArray<'js>;
+    type SerializeTupleVariant = SerializeArray<'js>;
+    type SerializeMap = SerializeObject<'js>;
+    type SerializeStruct = SerializeObject<'js>;
+    type SerializeStructVariant = SerializeObject<'js>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value<'js>> {
+        Ok(Value::new_bool(self.ctx, v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value<'js>> {
+        Ok(Value::new_int(self.ctx, v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value<'js>> {
+        Ok(Value::new_int(self.ctx, v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value<'js>> {
+        Ok(Value::new_int(self.ctx, v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value<'js>> {
+        Ok(self.int(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value<'js>> {
+        Ok(Value::new_int(self.ctx, v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value<'js>> {
+        Ok(Value::new_int(self.ctx, v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value<'js>> {
+        Ok(self.int(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value<'js>> {
+        match i64::try_from(v) {
+            Ok(v) => Ok(self.int(v)),
+            Err(_) => Ok(Value::new_float(self.ctx, v as f64)),
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value<'js>> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value<'js>> {
+        if !v.is_finite() {
+            return Err(Error::new_into_js_message(
+                "f64",
+                "number",
+                "non-finite numbers can't be serialized",
+            ));
+        }
+        Ok(Value::new_float(self.ctx, v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value<'js>> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value<'js>> {
+        String::from_str(self.ctx, v).map(String::into_value)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value<'js>> {
+        let array = Array::new(self.ctx)?;
+        for (idx, byte) in v.iter().enumerate() {
+            array.set(idx, *byte)?;
+        }
+        Ok(array.into_value())
+    }
+
+    fn serialize_none(self) -> Result<Value<'js>> {
+        Ok(Value::new_null(self.ctx))
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value<'js>>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value<'js>> {
+        Ok(Value::new_null(self.ctx))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value<'js>> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value<'js>> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Value<'js>>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value<'js>>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = value.serialize(Serializer::new(self.ctx.clone()))?;
+        self.tagged(variant, value)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<SerializeArray<'js>> {
+        Ok(SerializeArray {
+            array: Array::new(self.ctx)?,
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray<'js>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray<'js>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<SerializeArray<'js>> {
+        Ok(SerializeArray {
+            array: Array::new(self.ctx)?,
+            variant: Some(variant),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeObject<'js>> {
+        Ok(SerializeObject {
+            object: Object::new(self.ctx)?,
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeObject<'js>> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<SerializeObject<'js>> {
+        Ok(SerializeObject {
+            object: Object::new(self.ctx)?,
+            key: None,
+            variant: Some(variant),
+        })
+    }
+}
+
+/// Serializer for sequences, tuples and tuple variants.
+pub struct SerializeArray<'js> {
+    array: Array<'js>,
+    variant: Option<&'static str>,
+}
+
+impl<'js> SerializeArray<'js> {
+    fn push<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = value.serialize(Serializer::new(self.array.ctx().clone()))?;
+        self.array.set(self.array.len(), value)
+    }
+
+    fn finish(self) -> Result<Value<'js>> {
+        let serializer = Serializer::new(self.array.ctx().clone());
+        match self.variant {
+            Some(variant) => serializer.tagged(variant, self.array.into_value()),
+            None => Ok(self.array.into_value()),
+        }
+    }
+}
+
+impl<'js> ser::SerializeSeq for SerializeArray<'js> {
+    type Ok = Value<'js>;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value<'js>> {
+        self.finish()
+    }
+}
+
+impl<'js> ser::SerializeTuple for SerializeArray<'js> {
+    type Ok = Value<'js>;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value<'js>> {
+        self.finish()
+    }
+}
+
+impl<'js> ser::SerializeTupleStruct for SerializeArray<'js> {
+    type Ok = Value<'js>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value<'js>> {
+        self.finish()
+    }
+}
+
+impl<'js> ser::SerializeTupleVariant for SerializeArray<'js> {
+    type Ok = Value<'js>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value<'js>> {
+        self.finish()
+    }
+}
+
+/// Serializer for maps, structs and struct variants.
+pub struct SerializeObject<'js> {
+    object: Object<'js>,
+    key: Option<Atom<'js>>,
+    variant: Option<&'static str>,
+}
+
+impl<'js> SerializeObject<'js> {
+    fn value<T>(&self, value: &T) -> Result<Value<'js>>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(Serializer::new(self.object.ctx().clone()))
+    }
+
+    fn finish(self) -> Result<Value<'js>> {
+        let serializer = Serializer::new(self.object.ctx().clone());
+        match self.variant {
+            Some(variant) => serializer.tagged(variant, self.object.into_value()),
+            None => Ok(self.object.into_value()),
+        }
+    }
+}
+
+impl<'js> ser::SerializeMap for SerializeObject<'js> {
+    type Ok = Value<'js>;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self.value(key)?;
+        if !(key.is_string() || key.is_number()) {
+            return Err(Error::new_into_js_message(
+                key.type_name(),
+                "property key",
+                "map keys must be strings or numbers",
+            ));
+        }
+        self.key = Some(Atom::from_value(self.object.ctx().clone(), &key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+        let value = self.value(value)?;
+        self.object.set(key, value)
+    }
+
+    fn end(self) -> Result<Value<'js>> {
+        self.finish()
+    }
+}
+
+impl<'js> ser::SerializeStruct for SerializeObject<'js> {
+    type Ok = Value<'js>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = self.value(value)?;
+        self.object.set(key, value)
+    }
+
+    fn end(self) -> Result<Value<'js>> {
+        self.finish()
+    }
+}
+
+impl<'js> ser::SerializeStructVariant for SerializeObject<'js> {
+    type Ok = Value<'js>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = self.value(value)?;
+        self.object.set(key, value)
+    }
+
+    fn end(self) -> Result<Value<'js>> {
+        self.finish()
+    }
+}
@@ -0,0 +1,346 @@
+//! `queueMicrotask` and a `setTimeout` shim which hands timers to the host.
+//!
+//! Quickjs has no event loop and this module doesn't provide one either. [`install`] defines
+//! `queueMicrotask`, which enqueues the callback into the job queue of the runtime, and
+//! `setTimeout`, `setInterval`, `clearTimeout` and `clearInterval`, which only pass the timers on
+//! to a [`TimerHost`]. The host is responsible for waiting and calling the callback of a timer
+//! once it is due, repeatedly for intervals, until the timer is cleared.
+//!
+//! The callbacks of pending timers keep the context alive. When the runtime is dropped the hosts
+//! are dropped first, releasing the timers they still own, so pending timers don't keep the
+//! runtime from being freed. Timers which are moved out of the host or a host which is shared
+//! with the application, like the one below, must be dropped before the runtime like any
+//! [`Persistent`] value.
+//!
+//! ```
+//! # use std::{cell::RefCell, rc::Rc};
+//! # use rquickjs::{Runtime, Context, Function, timers::{self, Timer, TimerHost, TimerId}};
+//! #[derive(Clone, Default)]
+//! struct Host(Rc<RefCell<Vec<Timer>>>);
+//!
+//! impl TimerHost for Host {
+//!     fn register(&self, timer: Timer) {
+//!         self.0.borrow_mut().push(timer);
+//!     }
+//!
+//!     fn clear(&self, id: TimerId) {
+//!         self.0.borrow_mut().retain(|timer| timer.id != id);
+//!     }
+//! }
+//!
+//! # let rt = Runtime::new().unwrap();
+//! # let ctx = Context::full(&rt).unwrap();
+//! let host = Host::default();
+//! ctx.with(|ctx| {
+//!     timers::install(&ctx, host.clone()).unwrap();
+//!     ctx.eval::<(), _>("globalThis.fired = false; setTimeout(() => { fired = true }, 10)")
+//!         .unwrap();
+//!
+//!     // A real host would wait until the timers are due.
+//!     for timer in host.0.take() {
+//!         let callback = timer.callback.restore(&ctx).unwrap();
+//!         callback.call::<_, ()>(()).unwrap();
+//!     }
+//!     assert!(ctx.globals().get::<_, bool>("fired").unwrap());
+//! });
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+    time::Duration,
+};
+
+use crate::{
+    convert::Coerced,
+    function::{Opt, Rest},
+    Ctx, Exception, Function, Persistent, Result, Value,
+};
+
+/// The id of a timer, returned to javascript from `setTimeout` and `setInterval`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimerId(pub u32);
+
+/// A timer registered by a call to `setTimeout` or `setInterval`.
+pub struct Timer {
+    /// The id of the timer, unique per installation of the timers.
+    pub id: TimerId,
+    /// The delay after which the timer is due.
+    pub delay: Duration,
+    /// Whether the timer was created by `setInterval` and should be called every `delay`.
+    pub repeat: bool,
+    /// The callback to call once the timer is due, with the extra arguments passed to
+    /// `setTimeout` already bound.
+    pub callback: Persistent<Function<'static>>,
+}
+
+/// The host which keeps track of timers and calls them once they are due.
+pub trait TimerHost {
+    /// Called when a script registers a new timer.
+    fn register(&self, timer: Timer);
+
+    /// Called when a script clears a timer with `clearTimeout` or `clearInterval`.
+    ///
+    /// The id may belong to a timer which was already fired or cleared or not exist at all.
+    fn clear(&self, id: TimerId);
+}
+
+/// Install `queueMicrotask`, `setTimeout`, `setInterval`, `clearTimeout` and `clearInterval` on
+/// the global object of the context.
+///
+/// Timers are passed to `host`, negative or invalid delays are clamped to zero and delays are
+/// limited to `u32::MAX` milliseconds. Existing globals with these names are replaced.
+///
+/// The host is owned by the installed functions and dropped at the latest when the runtime is
+/// dropped, before the runtime is freed.
+pub fn install<'js, H>(ctx: &Ctx<'js>, host: H) -> Result<()>
+where
+    H: TimerHost + 'static,
+{
+    let globals = ctx.globals();
+
+    let queue = Function::new(ctx.clone(), |ctx: Ctx<'js>, callback: Value<'js>| {
+        callback_function(&ctx, callback, "queueMicrotask")?.defer(())
+    })?
+    .with_name("queueMicrotask")?;
+    globals.set("queueMicrotask", queue)?;
+
+    let host = Rc::new(HostSlot(RefCell::new(Some(host))));
+    unsafe {
+        let userdata = &mut (*ctx.get_opaque()).userdata;
+        if userdata.get::<TimerHosts>().is_none() {
+            let _ = userdata.insert(TimerHosts::default());
+        }
+        let hosts = &*userdata.get::<TimerHosts>().unwrap();
+        hosts
+            .0
+            .borrow_mut()
+            .push(Rc::downgrade(&host) as Weak<dyn DropHost>);
+    }
+    let next_id = Rc::new(Cell::new(1u32));
+    for (name, repeat) in [("setTimeout", false), ("setInterval", true)] {
+        let host = host.clone();
+        let next_id = next_id.clone();
+        let set = Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'js>,
+                  callback: Value<'js>,
+                  delay: Opt<Coerced<f64>>,
+                  args: Rest<Value<'js>>| {
+                let callback = callback_function(&ctx, callback, name)?;
+                let callback = if args.is_empty() {
+                    callback
+                } else {
+                    callback.bind(Value::new_undefined(ctx.clone()), args.0)?
+                };
+                let delay = delay.0.map_or(0.0, |delay| delay.0);
+                let delay = if delay.is_finite() && delay > 0.0 {
+                    Duration::from_secs_f64(delay.min(u32::MAX as f64) / 1000.0)
+                } else {
+                    Duration::ZERO
+                };
+                let id = TimerId(next_id.get());
+                next_id.set(id.0.wrapping_add(1).max(1));
+                if let Some(host) = host.0.borrow().as_ref() {
+                    host.register(Timer {
+                        id,
+                        delay,
+                        repeat,
+                        callback: Persistent::save(&ctx, callback),
+                    });
+                }
+                Result::Ok(id.0)
+            },
+        )?
+        .with_name(name)?;
+        globals.set(name, set)?;
+    }
+
+    for name in ["clearTimeout", "clearInterval"] {
+        let host = host.clone();
+        let clear = Function::new(ctx.clone(), move |id: Opt<Value<'js>>| {
+            // Like in browsers ids which aren't timers are silently ignored.
+            if let Some(id) = id.0.and_then(|id| id.as_number()) {
+                if id >= 1.0 && id <= u32::MAX as f64 && id.fract() == 0.0 {
+                    if let Some(host) = host.0.borrow().as_ref() {
+                        host.clear(TimerId(id as u32));
+                    }
+                }
+            }
+        })?
+        .with_name(name)?;
+        globals.set(name, clear)?;
+    }
+    Ok(())
+}
+
+/// The host of one installation, emptied when the runtime is dropped.
+struct HostSlot<H>(RefCell<Option<H>>);
+
+trait DropHost {
+    fn drop_host(&self);
+}
+
+impl<H> DropHost for HostSlot<H> {
+    fn drop_host(&self) {
+        let host = self.0.borrow_mut().take();
+        drop(host);
+    }
+}
+
+/// The hosts of all installations in a runtime, kept in the userdata of the runtime.
+///
+/// A host owns the callbacks of its pending timers, which keep the context alive, while it is
+/// owned by the functions of that context. The garbage collector can't see this cycle, so the
+/// hosts are dropped together with the userdata, before the runtime is freed.
+#[derive(Default)]
+struct TimerHosts(RefCell<Vec<Weak<dyn DropHost>>>);
+
+impl Drop for TimerHosts {
+    fn drop(&mut self) {
+        for host in self.0.take() {
+            if let Some(host) = host.upgrade() {
+                host.drop_host();
+            }
+        }
+    }
+}
+
+fn callback_function<'js>(
+    ctx: &Ctx<'js>,
+    callback: Value<'js>,
+    name: &str,
+) -> Result<Function<'js>> {
+    callback.into_function().ok_or_else(|| {
+        Exception::throw_type(ctx, &format!("{}: the callback must be a function", name))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Timer, TimerHost, TimerId};
+    use crate::*;
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+        time::Duration,
+    };
+
+    #[derive(Default)]
+    struct Host {
+        timers: RefCell<Vec<Timer>>,
+        cleared: RefCell<Vec<TimerId>>,
+    }
+
+    impl TimerHost for Rc<Host> {
+        fn register(&self, timer: Timer) {
+            self.timers.borrow_mut().push(timer);
+        }
+
+        fn clear(&self, id: TimerId) {
+            self.cleared.borrow_mut().push(id);
+        }
+    }
+
+    #[test]
+    fn microtask_after_current_job() {
+        test_with(|ctx| {
+            timers::install(&ctx, Rc::new(Host::default())).unwrap();
+            ctx.eval::<(), _>(
+                r#"
+                globalThis.order = [];
+                queueMicrotask(() => order.push("microtask"));
+                order.push("script");
+            "#,
+            )
+            .unwrap();
+            let order: Vec<StdString> = ctx.globals().get("order").unwrap();
+            assert_eq!(order, ["script"]);
+
+            ctx.run_jobs().unwrap();
+            let order: Vec<StdString> = ctx.globals().get("order").unwrap();
+            assert_eq!(order, ["script", "microtask"]);
+
+            let res: bool = ctx
+                .eval("try { queueMicrotask(1); false } catch (e) { e instanceof TypeError }")
+                .unwrap();
+            assert!(res);
+        })
+    }
+
+    #[test]
+    fn timers_are_passed_to_host() {
+        let host = Rc::new(Host::default());
+        test_with(|ctx| {
+            timers::install(&ctx, host.clone()).unwrap();
+            let ids: Vec<u32> = ctx
+                .eval(
+                    r#"
+                globalThis.calls = [];
+                const timeout = setTimeout((a, b) => calls.push(a + b), 25, 1, 2);
+                const interval = setInterval(() => calls.push("tick"), -5);
+                clearInterval(interval);
+                clearTimeout(undefined);
+                [timeout, interval]
+            "#,
+                )
+                .unwrap();
+            assert_eq!(ids, [1, 2]);
+            assert_eq!(*host.cleared.borrow(), [TimerId(2)]);
+
+            let timers = host.timers.take();
+            assert_eq!(timers[0].id, TimerId(1));
+            assert_eq!(timers[0].delay, Duration::from_millis(25));
+            assert!(!timers[0].repeat);
+            assert_eq!(timers[1].delay, Duration::ZERO);
+            assert!(timers[1].repeat);
+
+            for timer in timers {
+                let callback = timer.callback.restore(&ctx).unwrap();
+                callback.call::<_, ()>(()).unwrap();
+            }
+            let calls: Vec<Value> = ctx.globals().get("calls").unwrap();
+            assert_eq!(calls[0].as_int(), Some(3));
+            assert_eq!(calls[1].as_string().unwrap().to_string().unwrap(), "tick");
+        })
+    }
+
+    #[test]
+    fn runtime_dropped_with_pending_timers() {
+        struct Pending {
+            timers: RefCell<Vec<Timer>>,
+            dropped: Rc<Cell<bool>>,
+        }
+
+        impl TimerHost for Pending {
+            fn register(&self, timer: Timer) {
+                self.timers.borrow_mut().push(timer);
+            }
+
+            fn clear(&self, _id: TimerId) {}
+        }
+
+        impl Drop for Pending {
+            fn drop(&mut self) {
+                self.dropped.set(true);
+            }
+        }
+
+        let dropped = Rc::new(Cell::new(false));
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        ctx.with(|ctx| {
+            let host = Pending {
+                timers: RefCell::default(),
+                dropped: dropped.clone(),
+            };
+            timers::install(&ctx, host).unwrap();
+            ctx.eval::<(), _>("setTimeout(() => {}, 1000); setInterval(() => {}, 10);")
+                .unwrap();
+        });
+        drop(ctx);
+        assert!(!dropped.get());
+        drop(rt);
+        assert!(dropped.get());
+    }
+}
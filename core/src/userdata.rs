@@ -0,0 +1,97 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    ptr::NonNull,
+};
+
+use crate::{
+    class::{ClassId, JsClass, Readable, Trace, Tracer},
+    value::Constructor,
+    Ctx, Object, Result, StdResult,
+};
+
+/// A store of rust values keyed by their type.
+///
+/// Values can only be inserted once and are only dropped together with the store, so references
+/// to the stored values stay valid for as long as the store lives. The values are boxed
+/// separately and only kept as pointers, so mutating the store doesn't invalidate them.
+#[derive(Default)]
+pub(crate) struct UserDataMap {
+    map: HashMap<TypeId, NonNull<dyn Any>>,
+}
+
+impl UserDataMap {
+    /// Insert a value, returning it back if a value of the same type is already stored.
+    pub fn insert<T: Any>(&mut self, data: T) -> StdResult<(), T> {
+        let id = TypeId::of::<T>();
+        if self.map.contains_key(&id) {
+            return Err(data);
+        }
+        let data: Box<dyn Any> = Box::new(data);
+        self.map.insert(id, NonNull::from(Box::leak(data)));
+        Ok(())
+    }
+
+    /// Returns a pointer to the stored value of the given type, if any.
+    ///
+    /// The pointer stays valid until the store is dropped.
+    pub fn get<T: Any>(&self) -> Option<*const T> {
+        let data = self.map.get(&TypeId::of::<T>())?;
+        unsafe { data.as_ref() }
+            .downcast_ref::<T>()
+            .map(|data| data as *const T)
+    }
+}
+
+impl Drop for UserDataMap {
+    fn drop(&mut self) {
+        for (_, data) in self.map.drain() {
+            unsafe { drop(Box::from_raw(data.as_ptr())) }
+        }
+    }
+}
+
+/// The owner of the userdata of a context.
+///
+/// Quickjs has no callback for when a context is freed, so the userdata is owned by an object
+/// which is only referenced from the global object of the context and dropped with it.
+pub(crate) struct ContextUserData(NonNull<UserDataMap>);
+
+impl ContextUserData {
+    pub fn new() -> Self {
+        ContextUserData(NonNull::from(Box::leak(Box::default())))
+    }
+
+    pub fn as_ptr(&self) -> *mut UserDataMap {
+        self.0.as_ptr()
+    }
+}
+
+impl Drop for ContextUserData {
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(self.0.as_ptr())) }
+    }
+}
+
+impl<'js> Trace<'js> for ContextUserData {
+    fn trace<'a>(&self, _tracer: Tracer<'a, 'js>) {}
+}
+
+impl<'js> JsClass<'js> for ContextUserData {
+    const NAME: &'static str = "UserData";
+
+    type Mutable = Readable;
+
+    fn class_id() -> &'static ClassId {
+        static ID: ClassId = ClassId::new();
+        &ID
+    }
+
+    fn prototype(_ctx: &Ctx<'js>) -> Result<Option<Object<'js>>> {
+        Ok(None)
+    }
+
+    fn constructor(_ctx: &Ctx<'js>) -> Result<Option<Constructor<'js>>> {
+        Ok(None)
+    }
+}
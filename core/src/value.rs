@@ -1,13 +1,23 @@
 pub mod array;
 pub mod atom;
 mod bigint;
+mod builtin;
 pub mod convert;
+mod date;
+mod deep_equal;
 mod exception;
 pub mod function;
+mod inspect;
+pub mod map;
 pub mod module;
 pub mod object;
+pub mod proxy;
+mod regexp;
+pub mod set;
 mod string;
+mod structured_clone;
 mod symbol;
+mod weak_ref;
 
 #[cfg(feature = "array-buffer")]
 mod array_buffer;
@@ -20,12 +30,18 @@ pub use array::Array;
 pub use atom::Atom;
 pub use bigint::BigInt;
 pub use convert::{Coerced, FromAtom, FromIteratorJs, FromJs, IntoAtom, IntoJs, IteratorJs};
-pub use exception::Exception;
+pub use date::Date;
+pub use exception::{Exception, ExceptionKind, IntoException};
 pub use function::{Constructor, Function};
+pub use map::Map;
 pub use module::Module;
 pub use object::{Filter, Object};
-pub use string::String;
+pub use proxy::{Proxy, ProxyHandler};
+pub use regexp::RegExp;
+pub use set::Set;
+pub use string::{String, StringRef};
 pub use symbol::Symbol;
+pub use weak_ref::WeakRef;
 
 #[cfg(feature = "array-buffer")]
 pub use array_buffer::ArrayBuffer;
@@ -366,6 +382,22 @@ impl<'js> Value<'js> {
         0 != unsafe { qjs::JS_IsError(self.ctx.as_ptr(), self.value) }
     }
 
+    /// Check if the value is an instance of the given constructor, like `instanceof`.
+    ///
+    /// Works for any value, not only objects, unlike [`Object::is_instance_of`].
+    ///
+    /// A `Symbol.hasInstance` method of the constructor is called if present, errors thrown by it
+    /// or a constructor which is not callable result in an exception.
+    pub fn instance_of(&self, constructor: &Function<'js>) -> Result<bool> {
+        let res = unsafe {
+            qjs::JS_IsInstanceOf(self.ctx.as_ptr(), self.value, constructor.as_js_value())
+        };
+        if res < 0 {
+            return Err(self.ctx.raise_exception());
+        }
+        Ok(res != 0)
+    }
+
     /// Reference as value
     #[inline]
     pub fn as_value(&self) -> &Self {
@@ -512,6 +544,65 @@ type_impls! {
     BigInt: big_int => JS_TAG_BIG_INT,
 }
 
+/// The type of a javascript value as distinguished by the `typeof` operator.
+///
+/// Unlike [`Type`] this only knows the kinds of values which javascript itself distinguishes,
+/// except that `null` has its own variant instead of being an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsType {
+    Undefined,
+    Null,
+    Bool,
+    Number,
+    BigInt,
+    String,
+    Symbol,
+    Object,
+    Function,
+}
+
+impl JsType {
+    /// Returns the string the `typeof` operator returns for values of the type.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            JsType::Undefined => "undefined",
+            JsType::Null | JsType::Object => "object",
+            JsType::Bool => "boolean",
+            JsType::Number => "number",
+            JsType::BigInt => "bigint",
+            JsType::String => "string",
+            JsType::Symbol => "symbol",
+            JsType::Function => "function",
+        }
+    }
+}
+
+impl fmt::Display for JsType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl<'js> Value<'js> {
+    /// Get the type of the value like the `typeof` operator.
+    ///
+    /// Uninitialized values are reported as `undefined`.
+    pub fn js_type(&self) -> JsType {
+        let tag = unsafe { qjs::JS_VALUE_GET_NORM_TAG(self.value) };
+        match tag {
+            qjs::JS_TAG_NULL => JsType::Null,
+            qjs::JS_TAG_BOOL => JsType::Bool,
+            qjs::JS_TAG_INT | qjs::JS_TAG_FLOAT64 => JsType::Number,
+            qjs::JS_TAG_BIG_INT => JsType::BigInt,
+            qjs::JS_TAG_STRING => JsType::String,
+            qjs::JS_TAG_SYMBOL => JsType::Symbol,
+            qjs::JS_TAG_OBJECT if self.is_function() => JsType::Function,
+            qjs::JS_TAG_OBJECT | qjs::JS_TAG_MODULE => JsType::Object,
+            _ => JsType::Undefined,
+        }
+    }
+}
+
 macro_rules! sub_types {
     ($( $head:ident$(->$sub_type:ident)* $as:ident $ref:ident $into:ident $try_into:ident $from:ident,)*) => {
         $(
@@ -700,6 +791,109 @@ sub_types! {
     BigInt->Value as_big_int ref_big_int into_big_int try_into_big_int from_big_int,
 }
 
+/// Object wrappers for builtin classes which quickjs has no value type for.
+///
+/// Wrappers with the name of their constructor can be converted from values by checking that
+/// the object is an instance of it.
+macro_rules! object_types {
+    ($($type:ident $(=> $class:literal)?,)*) => {
+        $(
+            impl<'js> $type<'js> {
+                /// Reference to value
+                #[inline]
+                pub fn as_value(&self) -> &Value<'js> {
+                    self.0.as_value()
+                }
+
+                /// Convert into value
+                #[inline]
+                pub fn into_value(self) -> Value<'js> {
+                    self.0.into_value()
+                }
+
+                /// Reference as an object
+                #[inline]
+                pub fn as_object(&self) -> &Object<'js> {
+                    &self.0
+                }
+
+                /// Convert into an object
+                #[inline]
+                pub fn into_object(self) -> Object<'js> {
+                    self.0
+                }
+            }
+
+            impl<'js> Deref for $type<'js> {
+                type Target = Object<'js>;
+
+                fn deref(&self) -> &Self::Target {
+                    &self.0
+                }
+            }
+
+            impl<'js> AsRef<Object<'js>> for $type<'js> {
+                fn as_ref(&self) -> &Object<'js> {
+                    &self.0
+                }
+            }
+
+            impl<'js> AsRef<Value<'js>> for $type<'js> {
+                fn as_ref(&self) -> &Value<'js> {
+                    self.0.as_ref()
+                }
+            }
+
+            impl<'js> From<$type<'js>> for Value<'js> {
+                fn from(value: $type<'js>) -> Self {
+                    value.into_value()
+                }
+            }
+
+            impl<'js> IntoJs<'js> for $type<'js> {
+                fn into_js(self, _ctx: &Ctx<'js>) -> Result<Value<'js>> {
+                    Ok(self.into_value())
+                }
+            }
+
+            $(object_types!(@from $type $class);)?
+        )*
+    };
+
+    (@from $type:ident $class:literal) => {
+        impl<'js> $type<'js> {
+            /// Convert from value
+            pub fn from_value(value: Value<'js>) -> Result<Self> {
+                Self::from_object(Object::from_value(value)?)
+            }
+
+            /// Convert from an object
+            pub fn from_object(object: Object<'js>) -> Result<Self> {
+                let class: Function = object.ctx.globals().get($class)?;
+                if object.is_instance_of(class)? {
+                    Ok(Self(object))
+                } else {
+                    Err(Error::new_from_js("Object", $class))
+                }
+            }
+        }
+
+        impl<'js> FromJs<'js> for $type<'js> {
+            fn from_js(_: &Ctx<'js>, value: Value<'js>) -> Result<Self> {
+                Self::from_value(value)
+            }
+        }
+    };
+}
+
+object_types! {
+    Date => "Date",
+    RegExp => "RegExp",
+    Map => "Map",
+    Set => "Set",
+    Proxy,
+}
+
 macro_rules! void_types {
     ($($(#[$meta:meta])* $type:ident $new:ident;)*) => {
         $(
@@ -766,4 +960,54 @@ mod test {
 
         assert!(!Type::Bool.interpretable_as(Type::Int));
     }
+
+    #[test]
+    fn instance_of() {
+        test_with(|ctx| {
+            let date: Value = ctx.eval("new Date(0)").unwrap();
+            let date_ctor: Function = ctx.globals().get("Date").unwrap();
+            let array_ctor: Function = ctx.globals().get("Array").unwrap();
+            assert!(date.instance_of(&date_ctor).unwrap());
+            assert!(!date.instance_of(&array_ctor).unwrap());
+
+            let even: Function = ctx
+                .eval("(class Even { static [Symbol.hasInstance](x) { return x % 2 === 0 } })")
+                .unwrap();
+            let two: Value = ctx.eval("2").unwrap();
+            assert!(two.instance_of(&even).unwrap());
+
+            let thrower: Function = ctx
+                .eval("(class { static [Symbol.hasInstance]() { throw new Error('no') } })")
+                .unwrap();
+            assert!(matches!(two.instance_of(&thrower), Err(Error::Exception)));
+            ctx.catch();
+        });
+    }
+
+    #[test]
+    fn js_types() {
+        test_with(|ctx| {
+            let cases = [
+                ("undefined", JsType::Undefined),
+                ("null", JsType::Null),
+                ("true", JsType::Bool),
+                ("1", JsType::Number),
+                ("1.5", JsType::Number),
+                ("1n", JsType::BigInt),
+                ("'str'", JsType::String),
+                ("Symbol('sym')", JsType::Symbol),
+                ("({})", JsType::Object),
+                ("[]", JsType::Object),
+                ("(() => {})", JsType::Function),
+                ("Date", JsType::Function),
+            ];
+            for (source, expected) in cases {
+                let value: Value = ctx.eval(source).unwrap();
+                assert_eq!(value.js_type(), expected, "{}", source);
+                ctx.globals().set("v", value).unwrap();
+                let type_of: StdString = ctx.eval("typeof v").unwrap();
+                assert_eq!(type_of, expected.as_str(), "{}", source);
+            }
+        });
+    }
 }
@@ -1,6 +1,10 @@
 //! Javascript array types.
 
-use crate::{atom::PredefinedAtom, qjs, Ctx, FromJs, IntoJs, Object, Result, Value};
+use crate::{
+    atom::PredefinedAtom,
+    function::{Opt, Rest},
+    qjs, Ctx, FromJs, IntoJs, Object, Result, Value,
+};
 use std::{
     iter::{DoubleEndedIterator, ExactSizeIterator, FusedIterator, IntoIterator, Iterator},
     marker::PhantomData,
@@ -71,6 +75,9 @@ impl<'js> Array<'js> {
     }
 
     /// Get iterator over elments of an array
+    ///
+    /// The length of the array is read once when the iterator is created, elements added to the
+    /// array while iterating are not visited and removed elements are read as `undefined`.
     pub fn iter<T: FromJs<'js>>(&self) -> ArrayIter<'js, T> {
         let count = self.len() as _;
         ArrayIter {
@@ -81,6 +88,61 @@ impl<'js> Array<'js> {
         }
     }
 
+    /// Append a value to the end of the array, returning the new length.
+    ///
+    /// Like the other array methods this calls the method of the same name on the array, so it
+    /// behaves exactly like `array.push(value)` in javascript.
+    pub fn push<V: IntoJs<'js>>(&self, value: V) -> Result<usize> {
+        self.0.call_method("push", (value,))
+    }
+
+    /// Remove the last value of the array and return it, `undefined` if the array is empty.
+    pub fn pop<V: FromJs<'js>>(&self) -> Result<V> {
+        self.0.call_method("pop", ())
+    }
+
+    /// Remove the first value of the array and return it, `undefined` if the array is empty.
+    pub fn shift<V: FromJs<'js>>(&self) -> Result<V> {
+        self.0.call_method("shift", ())
+    }
+
+    /// Insert a value at the start of the array, returning the new length.
+    pub fn unshift<V: IntoJs<'js>>(&self, value: V) -> Result<usize> {
+        self.0.call_method("unshift", (value,))
+    }
+
+    /// Remove `delete_count` values starting at `start` and insert `items` in their place.
+    ///
+    /// Returns the removed values as a new array.
+    pub fn splice<V: IntoJs<'js>>(
+        &self,
+        start: usize,
+        delete_count: usize,
+        items: Vec<V>,
+    ) -> Result<Array<'js>> {
+        self.0
+            .call_method("splice", (start, delete_count, Rest(items)))
+    }
+
+    /// Returns a new array with the values from `start` up to but excluding `end`, or to the end
+    /// of the array if `end` is `None`.
+    pub fn slice(&self, start: usize, end: Option<usize>) -> Result<Array<'js>> {
+        self.0.call_method("slice", (start, Opt(end)))
+    }
+
+    /// Returns a new array with the values of this array followed by the given values.
+    ///
+    /// Like in javascript values which are arrays are flattened by one level.
+    pub fn concat<V: IntoJs<'js>>(&self, values: Vec<V>) -> Result<Array<'js>> {
+        self.0.call_method("concat", (Rest(values),))
+    }
+
+    /// Returns the index of the first value strictly equal to `value`, if any.
+    pub fn index_of<V: IntoJs<'js>>(&self, value: V) -> Result<Option<usize>> {
+        let idx: i64 = self.0.call_method("indexOf", (value,))?;
+        Ok(usize::try_from(idx).ok())
+    }
+
     pub fn into_object(self) -> Object<'js> {
         self.0
     }
@@ -161,6 +223,11 @@ impl<'js> IntoIterator for Array<'js> {
     }
 }
 
+/// Build an array by converting and setting the items while the iterator is consumed, without
+/// collecting them into an intermediate `Vec` first.
+///
+/// Conversion stops at the first item which fails to convert and returns its error, the rest of
+/// the iterator is not consumed.
 impl<'js, A> FromIteratorJs<'js, A> for Array<'js>
 where
     A: IntoJs<'js>,
@@ -182,7 +249,7 @@ where
 
 #[cfg(test)]
 mod test {
-    use convert::IteratorJs;
+    use convert::{FromIteratorJs, IteratorJs};
 
     use crate::*;
     #[test]
@@ -258,6 +325,24 @@ mod test {
         })
     }
 
+    #[test]
+    fn iter_sum() {
+        test_with(|ctx| {
+            let val: Array = ctx.eval("[1, 2.5, 3, 4.5]").unwrap();
+            let iter = val.iter::<f64>();
+            assert_eq!(iter.len(), 4);
+            let sum = iter.sum::<Result<f64>>().unwrap();
+            assert_eq!(sum, 11.0);
+
+            let mut iter = val.iter::<Option<f64>>();
+            assert_eq!(iter.next().unwrap().unwrap(), Some(1.0));
+            val.set(4, 5.0).unwrap();
+            val.as_object().set("length", 2).unwrap();
+            let rest = iter.collect::<Result<Vec<_>>>().unwrap();
+            assert_eq!(rest, [Some(2.5), None, None]);
+        })
+    }
+
     #[test]
     fn collect_js() {
         test_with(|ctx| {
@@ -272,4 +357,86 @@ mod test {
             assert_eq!(i32::from_js(&ctx, array.get(2).unwrap()).unwrap(), 3);
         })
     }
+
+    #[test]
+    fn from_iter_js() {
+        test_with(|ctx| {
+            let array = Array::from_iter_js(&ctx, (0..1000).map(|x| x * x)).unwrap();
+            assert_eq!(array.len(), 1000);
+            assert_eq!(array.get::<i32>(0).unwrap(), 0);
+            assert_eq!(array.get::<i32>(7).unwrap(), 49);
+            assert_eq!(array.get::<i32>(999).unwrap(), 998001);
+
+            /// Fails to convert odd numbers.
+            struct Even(i32);
+
+            impl<'js> IntoJs<'js> for Even {
+                fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+                    if self.0 % 2 != 0 {
+                        return Err(Error::new_into_js("Even", "value"));
+                    }
+                    self.0.into_js(ctx)
+                }
+            }
+
+            let mut consumed = 0;
+            let items = [0, 2, 3, 4].into_iter().map(|x| {
+                consumed += 1;
+                Even(x)
+            });
+            let res = Array::from_iter_js(&ctx, items);
+            assert!(matches!(res, Err(Error::IntoJs { from: "Even", .. })));
+            assert_eq!(consumed, 3);
+        })
+    }
+
+    #[test]
+    fn push_and_pop() {
+        test_with(|ctx| {
+            let array = Array::new(ctx.clone()).unwrap();
+            assert_eq!(array.push(1).unwrap(), 1);
+            assert_eq!(array.push("two").unwrap(), 2);
+            assert_eq!(array.unshift(0).unwrap(), 3);
+            assert_eq!(array.len(), 3);
+            assert_eq!(array.index_of("two").unwrap(), Some(2));
+            assert_eq!(array.index_of(5).unwrap(), None);
+
+            assert_eq!(array.pop::<StdString>().unwrap(), "two");
+            assert_eq!(array.shift::<i32>().unwrap(), 0);
+            assert_eq!(array.pop::<Option<i32>>().unwrap(), Some(1));
+            assert_eq!(array.pop::<Option<i32>>().unwrap(), None);
+            assert!(array.is_empty());
+        })
+    }
+
+    #[test]
+    fn splice_slice_concat() {
+        test_with(|ctx| {
+            let array: Array = ctx.eval("[1, 2, 3, 4, 5]").unwrap();
+            let removed = array.splice(1, 2, vec![20]).unwrap();
+            assert_eq!(
+                removed.iter::<i32>().collect::<Result<Vec<_>>>().unwrap(),
+                [2, 3]
+            );
+            assert_eq!(
+                array.iter::<i32>().collect::<Result<Vec<_>>>().unwrap(),
+                [1, 20, 4, 5]
+            );
+
+            let part = array.slice(1, Some(3)).unwrap();
+            assert_eq!(
+                part.iter::<i32>().collect::<Result<Vec<_>>>().unwrap(),
+                [20, 4]
+            );
+            let tail = array.slice(2, None).unwrap();
+            assert_eq!(tail.len(), 2);
+
+            let joined = part.concat(vec![tail]).unwrap();
+            assert_eq!(
+                joined.iter::<i32>().collect::<Result<Vec<_>>>().unwrap(),
+                [20, 4, 4, 5]
+            );
+            assert_eq!(array.len(), 4);
+        })
+    }
 }
@@ -5,6 +5,7 @@ use std::{
     ops::Deref,
     os::raw::c_void,
     slice,
+    sync::Arc,
 };
 
 use super::typed_array::TypedArrayItem;
@@ -29,6 +30,10 @@ impl<'js> ArrayBuffer<'js> {
         let size = src.len() * size_of::<T>();
 
         extern "C" fn drop_raw<T>(_rt: *mut qjs::JSRuntime, opaque: *mut c_void, ptr: *mut c_void) {
+            // quickjs calls this again with a null pointer when a detached buffer is finalized.
+            if ptr.is_null() {
+                return;
+            }
             let ptr = ptr as *mut T;
             let capacity = opaque as usize;
             // reconstruct vector in order to free data
@@ -45,15 +50,64 @@ impl<'js> ArrayBuffer<'js> {
                 capacity as _,
                 0,
             );
-            ctx.handle_exception(val).map_err(|error| {
+            if let Err(error) = ctx.handle_exception(val) {
                 // don't forget to free data when error occurred
                 Vec::from_raw_parts(ptr, capacity, capacity);
-                error
-            })?;
+                return Err(error);
+            }
             Value::from_js_value(ctx, val)
         })))
     }
 
+    /// Create an array buffer viewing shared bytes without copying them.
+    ///
+    /// The buffer keeps a clone of the `Arc` which is dropped once the buffer is collected or
+    /// detached, so the bytes stay alive as long as either rust or javascript still references
+    /// them. Typed arrays created from the buffer view the same memory.
+    ///
+    /// The clone is dropped from within the garbage collector of the runtime, so dropping it must
+    /// not call back into the runtime. This is never a problem for plain bytes, but it means the
+    /// last reference to the data may be released at any point where the runtime frees values,
+    /// on whatever thread that currently uses the runtime.
+    ///
+    /// # Safety
+    /// Javascript sees the buffer as any other array buffer and is able to write to it, but the
+    /// bytes behind an `Arc` must not be mutated. The caller must ensure that no javascript code
+    /// writes to the buffer or to views of it, for example by only handing out the buffer to
+    /// trusted code which only reads from it.
+    pub unsafe fn from_bytes_shared(ctx: Ctx<'js>, data: Arc<[u8]>) -> Result<Self> {
+        let ptr = data.as_ptr() as *mut u8;
+        let size = data.len();
+        let opaque = Box::into_raw(Box::new(data));
+
+        extern "C" fn drop_shared(_rt: *mut qjs::JSRuntime, opaque: *mut c_void, ptr: *mut c_void) {
+            // called again with a null pointer when a detached buffer is finalized
+            if ptr.is_null() {
+                return;
+            }
+            // release the reference held by the buffer
+            unsafe { mem::drop(Box::from_raw(opaque as *mut Arc<[u8]>)) };
+        }
+
+        let val = qjs::JS_NewArrayBuffer(
+            ctx.as_ptr(),
+            ptr,
+            size as _,
+            Some(drop_shared),
+            opaque as _,
+            0,
+        );
+        let val = match ctx.handle_exception(val) {
+            Ok(val) => val,
+            Err(error) => {
+                // the buffer wasn't created so it doesn't own the reference
+                mem::drop(Box::from_raw(opaque));
+                return Err(error);
+            }
+        };
+        Ok(Self(Object(Value::from_js_value(ctx, val))))
+    }
+
     /// Create array buffer from slice
     pub fn new_copy<T: Copy>(ctx: Ctx<'js>, src: impl AsRef<[T]>) -> Result<Self> {
         let src = src.as_ref();
@@ -98,8 +152,32 @@ impl<'js> ArrayBuffer<'js> {
     }
 
     /// Detach array buffer
-    pub fn detach(&mut self) {
-        unsafe { qjs::JS_DetachArrayBuffer(self.0.ctx.as_ptr(), self.0.as_js_value()) }
+    ///
+    /// The backing store of the buffer is released and typed arrays viewing the buffer will no
+    /// longer be able to access it. Reading a detached buffer from javascript behaves like
+    /// reading an empty buffer, `byteLength` and the `length` of all views become zero.
+    pub fn detach(&self) -> Result<()> {
+        unsafe { qjs::JS_DetachArrayBuffer(self.0.ctx.as_ptr(), self.0.as_js_value()) };
+        Ok(())
+    }
+
+    /// Returns wether the array buffer is detached.
+    pub fn is_detached(&self) -> bool {
+        Self::get_raw(&self.0).is_none()
+    }
+
+    /// Copy the contents of the buffer into a new array buffer and detach this buffer.
+    ///
+    /// This is not a zero-copy transfer: quickjs has no way to hand the backing store of a
+    /// buffer to another buffer and frees it when the buffer is detached, so the bytes are copied
+    /// before the old buffer is detached. Returns an error if the buffer is already detached.
+    pub fn copy_and_detach(&self) -> Result<ArrayBuffer<'js>> {
+        let bytes = self.as_bytes().ok_or_else(|| {
+            Error::new_from_js_message("ArrayBuffer", "ArrayBuffer", "Buffer is detached")
+        })?;
+        let res = Self::new_copy(self.0.ctx.clone(), bytes)?;
+        self.detach()?;
+        Ok(res)
     }
 
     /// Reference to value
@@ -144,7 +222,16 @@ impl<'js> ArrayBuffer<'js> {
         let ctx = val.ctx();
         let val = val.as_js_value();
         let mut size = MaybeUninit::<qjs::size_t>::uninit();
+        // Quickjs throws a type error if the value is not a buffer or is detached, which would
+        // replace an exception which is already pending, so that one is put back afterwards.
+        let pending = ctx.catch();
         let ptr = unsafe { qjs::JS_GetArrayBuffer(ctx.as_ptr(), size.as_mut_ptr(), val) };
+        if ptr.is_null() {
+            let _ = ctx.catch();
+        }
+        if !pending.is_null() {
+            ctx.throw(pending);
+        }
 
         if ptr.is_null() {
             None
@@ -278,6 +365,50 @@ mod test {
         })
     }
 
+    #[test]
+    fn shared_bytes() {
+        use std::sync::Arc;
+
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        let data: Arc<[u8]> = Arc::from(vec![1u8, 2, 3, 4]);
+
+        ctx.with(|ctx| {
+            let buffer =
+                unsafe { ArrayBuffer::from_bytes_shared(ctx.clone(), data.clone()) }.unwrap();
+            assert_eq!(buffer.as_bytes().unwrap().as_ptr(), data.as_ptr());
+            ctx.globals().set("buffer", buffer).unwrap();
+            let sum: i32 = ctx
+                .eval("new Uint8Array(buffer).reduce((a, b) => a + b, 0)")
+                .unwrap();
+            assert_eq!(sum, 10);
+        });
+        assert_eq!(Arc::strong_count(&data), 2);
+
+        ctx.with(|ctx| {
+            ctx.globals().remove("buffer").unwrap();
+        });
+        rt.run_gc();
+        assert_eq!(Arc::strong_count(&data), 1);
+    }
+
+    #[test]
+    fn detach_owned_and_shared() {
+        use std::sync::Arc;
+
+        let data: Arc<[u8]> = Arc::from(vec![1u8, 2, 3, 4]);
+        test_with(|ctx| {
+            let owned = ArrayBuffer::new(ctx.clone(), vec![1u8, 2, 3]).unwrap();
+            owned.detach().unwrap();
+            let shared =
+                unsafe { ArrayBuffer::from_bytes_shared(ctx.clone(), data.clone()) }.unwrap();
+            shared.detach().unwrap();
+            assert_eq!(Arc::strong_count(&data), 1);
+        });
+        // Finalizing the detached buffers must not free the data again.
+        assert_eq!(Arc::strong_count(&data), 1);
+    }
+
     #[test]
     fn as_bytes() {
         test_with(|ctx| {
@@ -297,4 +428,57 @@ mod test {
             assert_eq!(val.as_bytes().unwrap(), &res)
         });
     }
+
+    #[test]
+    fn detach() {
+        test_with(|ctx| {
+            let val: ArrayBuffer = ctx
+                .eval(
+                    r#"
+                        var a = new Uint8Array([1, 2, 3, 4]);
+                        a.buffer
+                    "#,
+                )
+                .unwrap();
+            let view: TypedArray<u8> = ctx.eval("a").unwrap();
+            assert!(!val.is_detached());
+            assert_eq!(view.as_slice().unwrap(), &[1, 2, 3, 4]);
+
+            val.detach().unwrap();
+            assert!(val.is_detached());
+            assert!(val.as_bytes().is_none());
+            assert!(view.as_slice().is_err());
+            let _ = ctx.catch();
+
+            let res: usize = ctx.eval("a.buffer.byteLength + a.length").unwrap();
+            assert_eq!(res, 0);
+        });
+    }
+
+    #[test]
+    fn copy_and_detach() {
+        test_with(|ctx| {
+            let val = ArrayBuffer::new(ctx.clone(), [1u8, 2, 3]).unwrap();
+            let moved = val.copy_and_detach().unwrap();
+            assert!(val.is_detached());
+            assert_eq!(moved.as_bytes().unwrap(), &[1, 2, 3]);
+            assert!(val.copy_and_detach().is_err());
+            assert!(ctx.catch().is_null());
+        });
+    }
+
+    #[test]
+    fn keep_pending_exception() {
+        test_with(|ctx| {
+            let val = ArrayBuffer::new(ctx.clone(), [1u8, 2, 3]).unwrap();
+            val.detach().unwrap();
+            let object = Object::new(ctx.clone()).unwrap();
+            ctx.throw("pending".into_js(&ctx).unwrap());
+            assert!(val.is_detached());
+            assert!(val.as_bytes().is_none());
+            assert!(ArrayBuffer::from_object(object).is_none());
+            let pending: StdString = ctx.catch().get().unwrap();
+            assert_eq!(pending, "pending");
+        });
+    }
 }
@@ -21,7 +21,7 @@ pub use predefined::PredefinedAtom;
 #[derive(Debug)]
 pub struct Atom<'js> {
     pub(crate) atom: qjs::JSAtom,
-    ctx: Ctx<'js>,
+    pub(crate) ctx: Ctx<'js>,
 }
 
 impl<'js> PartialEq for Atom<'js> {
@@ -86,6 +86,11 @@ impl<'js> Atom<'js> {
     }
 
     /// Create an atom from a rust string
+    ///
+    /// An ASCII string which doesn't start with a digit and is already interned is found in the
+    /// atom table of the runtime without allocating. Other strings, like ones containing non
+    /// ASCII characters or integer keys, are first converted into a javascript string which is
+    /// then interned.
     pub fn from_str(ctx: Ctx<'js>, name: &str) -> Result<Atom<'js>> {
         unsafe {
             let ptr = name.as_ptr() as *const std::os::raw::c_char;
@@ -131,8 +136,14 @@ impl<'js> Atom<'js> {
     }
 
     /// Convert the atom to a javascript value.
+    ///
+    /// Atoms of symbols are converted to the symbol, other atoms to a string.
     pub fn to_value(&self) -> Result<Value<'js>> {
-        self.to_js_string().map(|String(value)| value)
+        unsafe {
+            let val = qjs::JS_AtomToValue(self.ctx.as_ptr(), self.atom);
+            let val = self.ctx.handle_exception(val)?;
+            Ok(Value::from_js_value(self.ctx.clone(), val))
+        }
     }
 
     pub(crate) unsafe fn from_atom_val(ctx: Ctx<'js>, val: qjs::JSAtom) -> Self {
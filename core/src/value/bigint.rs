@@ -1,4 +1,4 @@
-use crate::{qjs, Ctx, Error, Result, Value};
+use crate::{convert::Coerced, qjs, Ctx, Error, FromJs, Function, Result, StdString, Value};
 
 /// Rust representation of a javascript big int.
 #[derive(Debug, Clone, PartialEq)]
@@ -20,6 +20,42 @@ impl<'js> BigInt<'js> {
         }
     }
 
+    /// Create a big int from an i128.
+    ///
+    /// The whole range of i128 is represented exactly, unlike converting the value to a javascript
+    /// number which loses precision for values above 2^53 in magnitude. Values which don't fit in
+    /// an i64 are created with the `BigInt` constructor.
+    pub fn from_i128(ctx: Ctx<'js>, v: i128) -> Result<Self> {
+        if let Ok(v) = i64::try_from(v) {
+            return Self::from_i64(ctx, v);
+        }
+        Self::from_decimal_str(ctx, &v.to_string())
+    }
+
+    /// Create a big int from an u128.
+    ///
+    /// The whole range of u128 is represented exactly, unlike converting the value to a javascript
+    /// number which loses precision for values above 2^53. Values which don't fit in an u64 are
+    /// created with the `BigInt` constructor.
+    pub fn from_u128(ctx: Ctx<'js>, v: u128) -> Result<Self> {
+        if let Ok(v) = u64::try_from(v) {
+            return Self::from_u64(ctx, v);
+        }
+        Self::from_decimal_str(ctx, &v.to_string())
+    }
+
+    /// Create a big int from a decimal string by calling the `BigInt` constructor.
+    pub(crate) fn from_decimal_str(ctx: Ctx<'js>, v: &str) -> Result<Self> {
+        let ctor: Function = ctx.globals().get("BigInt")?;
+        ctor.call((v,))
+    }
+
+    /// Returns the decimal string representation of the big int.
+    pub(crate) fn to_decimal_string(&self) -> Result<StdString> {
+        let Coerced(res) = Coerced::<StdString>::from_js(&self.0.ctx, self.0.clone())?;
+        Ok(res)
+    }
+
     pub fn to_i64(self) -> Result<i64> {
         unsafe {
             let mut res: i64 = 0;
@@ -29,6 +65,20 @@ impl<'js> BigInt<'js> {
             Ok(res)
         }
     }
+
+    /// Convert the big int into an i128, returns an error if the value doesn't fit.
+    pub fn to_i128(self) -> Result<i128> {
+        self.to_decimal_string()?
+            .parse()
+            .map_err(|_| Error::new_from_js_message("BigInt", "i128", "Value out of range"))
+    }
+
+    /// Convert the big int into an u128, returns an error if the value doesn't fit.
+    pub fn to_u128(self) -> Result<u128> {
+        self.to_decimal_string()?
+            .parse()
+            .map_err(|_| Error::new_from_js_message("BigInt", "u128", "Value out of range"))
+    }
 }
 
 #[cfg(test)]
@@ -59,4 +109,44 @@ mod test {
             func.call::<_, ()>((bigint,)).unwrap();
         })
     }
+
+    #[test]
+    fn i128_round_trip() {
+        test_with(|ctx| {
+            for v in [i128::MIN, -1, 0, i64::MAX as i128 + 1, i128::MAX] {
+                let bigint = BigInt::from_i128(ctx.clone(), v).unwrap();
+                assert_eq!(bigint.to_i128().unwrap(), v);
+            }
+            ctx.globals().set("v", i128::MIN).unwrap();
+            let res: bool = ctx.eval(format!("v === {}n", i128::MIN)).unwrap();
+            assert!(res);
+            let res: i128 = ctx.eval("v").unwrap();
+            assert_eq!(res, i128::MIN);
+        })
+    }
+
+    #[test]
+    fn u128_round_trip() {
+        test_with(|ctx| {
+            for v in [0, u64::MAX as u128 + 1, u128::MAX] {
+                let bigint = BigInt::from_u128(ctx.clone(), v).unwrap();
+                assert_eq!(bigint.to_u128().unwrap(), v);
+            }
+            ctx.globals().set("v", u128::MAX).unwrap();
+            let res: bool = ctx.eval(format!("v === {}n", u128::MAX)).unwrap();
+            assert!(res);
+            let res: u128 = ctx.eval("v").unwrap();
+            assert_eq!(res, u128::MAX);
+        })
+    }
+
+    #[test]
+    fn u128_overflow() {
+        test_with(|ctx| {
+            let res = ctx.eval::<u128, _>("-1n");
+            assert!(matches!(res, Err(Error::FromJs { .. })));
+            let res = ctx.eval::<i128, _>(format!("{}n + 1n", i128::MAX));
+            assert!(matches!(res, Err(Error::FromJs { .. })));
+        })
+    }
 }
@@ -0,0 +1,144 @@
+//! Detection of the builtin class of objects.
+
+use crate::{qjs, Array, Ctx, Object, Result, Value};
+use std::mem::MaybeUninit;
+
+/// The builtin class an object was created with.
+///
+/// Unlike `instanceof`, `Array.isArray` on proxies or `Symbol.toStringTag` the class is recorded
+/// by quickjs when the object is created, so scripts can't change or fake it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BuiltinClass {
+    Array,
+    Error,
+    Date,
+    RegExp,
+    Map,
+    Set,
+    ArrayBuffer,
+    /// A typed array or `DataView`, with the class id of its type.
+    View(qjs::JSClassID),
+    /// `Promise`, `WeakMap` and `WeakSet`, which have state which can't be read.
+    Opaque,
+    /// Plain objects and objects of all other classes.
+    Other,
+}
+
+/// A typed array or `DataView` with the range of its buffer it views.
+pub(crate) struct View<'js> {
+    pub class_id: qjs::JSClassID,
+    pub buffer: Object<'js>,
+    pub offset: usize,
+    pub len: usize,
+    pub element_size: usize,
+}
+
+impl<'js> Object<'js> {
+    pub(crate) fn builtin_class(&self) -> BuiltinClass {
+        match unsafe { qjs::JS_GetClassID(self.as_js_value()) } {
+            qjs::JS_CLASS_ARRAY => BuiltinClass::Array,
+            qjs::JS_CLASS_ERROR => BuiltinClass::Error,
+            qjs::JS_CLASS_DATE => BuiltinClass::Date,
+            qjs::JS_CLASS_REGEXP => BuiltinClass::RegExp,
+            qjs::JS_CLASS_MAP => BuiltinClass::Map,
+            qjs::JS_CLASS_SET => BuiltinClass::Set,
+            qjs::JS_CLASS_ARRAY_BUFFER => BuiltinClass::ArrayBuffer,
+            id @ qjs::JS_CLASS_UINT8C_ARRAY..=qjs::JS_CLASS_DATAVIEW => BuiltinClass::View(id),
+            qjs::JS_CLASS_PROMISE | qjs::JS_CLASS_WEAKMAP | qjs::JS_CLASS_WEAKSET => {
+                BuiltinClass::Opaque
+            }
+            _ => BuiltinClass::Other,
+        }
+    }
+
+    /// Returns the time value of a `Date` in milliseconds since the unix epoch.
+    pub(crate) fn date_value(&self) -> Result<f64> {
+        let ctx = self.ctx();
+        let mut time = 0.0;
+        if unsafe { qjs::JS_GetDateValue(ctx.as_ptr(), self.as_js_value(), &mut time) } < 0 {
+            return Err(ctx.raise_exception());
+        }
+        Ok(time)
+    }
+
+    /// Returns the entries of a `Map` or `Set` in insertion order, the values of a set are
+    /// `undefined`.
+    pub(crate) fn map_entries(&self) -> Result<Vec<(Value<'js>, Value<'js>)>> {
+        let ctx = self.ctx();
+        let entries = unsafe {
+            let entries = qjs::JS_GetMapEntries(ctx.as_ptr(), self.as_js_value());
+            Array::from_js_value(ctx.clone(), ctx.handle_exception(entries)?)
+        };
+        (0..entries.len() / 2)
+            .map(|idx| Ok((entries.get(idx * 2)?, entries.get(idx * 2 + 1)?)))
+            .collect()
+    }
+
+    /// Returns the value of the key of a `Map` or `Set` or `None` if the key is not in it.
+    pub(crate) fn map_get(&self, key: &Value<'js>) -> Result<Option<Value<'js>>> {
+        let ctx = self.ctx();
+        let mut value = qjs::JS_UNDEFINED;
+        unsafe {
+            match qjs::JS_MapGetValue(
+                ctx.as_ptr(),
+                self.as_js_value(),
+                key.as_js_value(),
+                &mut value,
+            ) {
+                0 => Ok(None),
+                res if res < 0 => Err(ctx.raise_exception()),
+                _ => Ok(Some(Value::from_js_value(ctx.clone(), value))),
+            }
+        }
+    }
+
+    /// Returns the buffer and range of a typed array or `DataView`.
+    pub(crate) fn view(&self) -> Result<View<'js>> {
+        let ctx = self.ctx();
+        let mut offset = MaybeUninit::<qjs::size_t>::uninit();
+        let mut len = MaybeUninit::<qjs::size_t>::uninit();
+        let mut element_size = MaybeUninit::<qjs::size_t>::uninit();
+        unsafe {
+            let buffer = qjs::JS_GetArrayBufferView(
+                ctx.as_ptr(),
+                self.as_js_value(),
+                offset.as_mut_ptr(),
+                len.as_mut_ptr(),
+                element_size.as_mut_ptr(),
+            );
+            let buffer = Object::from_js_value(ctx.clone(), ctx.handle_exception(buffer)?);
+            Ok(View {
+                class_id: qjs::JS_GetClassID(self.as_js_value()),
+                buffer,
+                offset: offset.assume_init() as _,
+                len: len.assume_init() as _,
+                element_size: element_size.assume_init() as _,
+            })
+        }
+    }
+}
+
+impl<'js> View<'js> {
+    /// Create a typed array or `DataView` of the given class on a byte range of a buffer.
+    pub(crate) fn create(
+        ctx: &Ctx<'js>,
+        class_id: qjs::JSClassID,
+        buffer: &Object<'js>,
+        offset: usize,
+        len: usize,
+    ) -> Result<Object<'js>> {
+        unsafe {
+            let view = qjs::JS_NewArrayBufferView(
+                ctx.as_ptr(),
+                class_id,
+                buffer.as_js_value(),
+                offset as _,
+                len as _,
+            );
+            Ok(Object::from_js_value(
+                ctx.clone(),
+                ctx.handle_exception(view)?,
+            ))
+        }
+    }
+}
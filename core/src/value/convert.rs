@@ -97,3 +97,29 @@ where
 /// A helper type for turning a tuple into a javascript array.
 /// Implementes [`IntoJs`] and [`FromJs`] for tuples of various lengths
 pub struct List<T>(pub T);
+
+/// A helper type for throwing any value as javascript exception.
+///
+/// When the error of a [`Result`](std::result::Result) is wrapped in `Throw`, converting the
+/// result into javascript throws the converted error value instead of turning it into an
+/// [`Error`](crate::Error). This allows rust callbacks to throw values which are not errors,
+/// just like `throw` in javascript.
+///
+/// ```
+/// # use rquickjs::{Runtime, Context, Function, convert::Throw};
+/// # let rt = Runtime::new().unwrap();
+/// # let ctx = Context::full(&rt).unwrap();
+/// # ctx.with(|ctx| {
+/// let half = Function::new(ctx.clone(), |x: i32| -> Result<i32, Throw<&str>> {
+///     if x % 2 != 0 {
+///         return Err(Throw("odd"));
+///     }
+///     Ok(x / 2)
+/// })
+/// .unwrap();
+/// ctx.globals().set("half", half).unwrap();
+/// let res: String = ctx.eval("try { half(3) } catch (e) { e }").unwrap();
+/// assert_eq!(res, "odd");
+/// # })
+/// ```
+pub struct Throw<T>(pub T);
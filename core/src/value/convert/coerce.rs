@@ -95,3 +95,93 @@ impl<'js> FromJs<'js> for Coerced<bool> {
         }))
     }
 }
+
+/// Coerce a value to a `u32` in the same way javascript would coerce values
+impl<'js> FromJs<'js> for Coerced<u32> {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self> {
+        // ToUint32 results in the same bits as ToInt32, only interpreted as unsigned.
+        <Coerced<i32>>::from_js(ctx, value).map(|Coerced(value)| Coerced(value as u32))
+    }
+}
+
+/// The javascript abstract type conversion operations.
+///
+/// Unlike the [`FromJs`] implementations for the corresponding rust types these never fail
+/// because of the type of the value, objects are converted by calling their `valueOf` or
+/// `toString` methods exactly like the specification prescribes. An error is only returned when
+/// one of these methods throws or the value can't be converted at all, like converting a symbol to
+/// a number.
+impl<'js> Value<'js> {
+    /// Convert the value to a number like the `ToNumber` operation, `Number(value)` in
+    /// javascript.
+    pub fn to_number(&self) -> Result<f64> {
+        self.get::<Coerced<f64>>().map(|Coerced(value)| value)
+    }
+
+    /// Convert the value to a signed 32 bit integer like the `ToInt32` operation, `value | 0` in
+    /// javascript.
+    pub fn to_int32(&self) -> Result<i32> {
+        self.get::<Coerced<i32>>().map(|Coerced(value)| value)
+    }
+
+    /// Convert the value to an unsigned 32 bit integer like the `ToUint32` operation,
+    /// `value >>> 0` in javascript.
+    pub fn to_uint32(&self) -> Result<u32> {
+        self.get::<Coerced<u32>>().map(|Coerced(value)| value)
+    }
+
+    /// Convert the value to a boolean like the `ToBoolean` operation, `!!value` in javascript.
+    pub fn to_bool(&self) -> Result<bool> {
+        self.get::<Coerced<bool>>().map(|Coerced(value)| value)
+    }
+
+    /// Convert the value to a string like the `ToString` operation, `String(value)` in
+    /// javascript.
+    pub fn to_js_string(&self) -> Result<String<'js>> {
+        self.get::<Coerced<String>>().map(|Coerced(value)| value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn abstract_operations() {
+        test_with(|ctx| {
+            let value: Value = ctx.eval("'42'").unwrap();
+            assert_eq!(value.to_number().unwrap(), 42.0);
+            assert_eq!(value.to_int32().unwrap(), 42);
+            assert!(value.to_bool().unwrap());
+            assert!(value.get::<f64>().is_err());
+
+            let value: Value = ctx.eval("true").unwrap();
+            assert_eq!(value.to_number().unwrap(), 1.0);
+            assert_eq!(value.to_js_string().unwrap().to_string().unwrap(), "true");
+
+            let value: Value = ctx.eval("-1").unwrap();
+            assert_eq!(value.to_uint32().unwrap(), u32::MAX);
+            let value: Value = ctx.eval("2 ** 32 + 5.5").unwrap();
+            assert_eq!(value.to_int32().unwrap(), 5);
+
+            let value: Value = ctx
+                .eval("({ valueOf() { return 7; }, toString() { return 'seven'; } })")
+                .unwrap();
+            assert_eq!(value.to_number().unwrap(), 7.0);
+            assert_eq!(value.to_uint32().unwrap(), 7);
+            assert_eq!(value.to_js_string().unwrap().to_string().unwrap(), "seven");
+            assert!(value.to_bool().unwrap());
+
+            let value: Value = ctx
+                .eval("({ valueOf() { throw new Error('nope'); } })")
+                .unwrap();
+            assert!(matches!(value.to_number(), Err(Error::Exception)));
+            let _ = ctx.catch();
+
+            let value: Value = ctx.eval("Symbol()").unwrap();
+            assert!(value.to_number().is_err());
+            let _ = ctx.catch();
+            assert!(!Value::new_null(ctx.clone()).to_bool().unwrap());
+        });
+    }
+}
@@ -1,11 +1,14 @@
 use crate::{
-    convert::List, Array, Ctx, Error, FromAtom, FromJs, Object, Result, StdString, String, Type,
-    Value,
+    convert::List, Array, BigInt, Ctx, Date, Error, FromAtom, FromJs, Object, Result, StdString,
+    String, Type, Value,
 };
 use std::{
+    borrow::Cow,
     cell::{Cell, RefCell},
     collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque},
     hash::{BuildHasher, Hash},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    path::PathBuf,
     rc::Rc,
     sync::{Arc, Mutex, RwLock},
     time::{Duration, SystemTime},
@@ -29,6 +32,94 @@ impl<'js> FromJs<'js> for StdString {
     }
 }
 
+/// Convert a string which consists of exactly one unicode scalar value.
+///
+/// Empty strings, strings with more than one character and unpaired surrogates, which can't be
+/// represented as a `char`, return an error.
+impl<'js> FromJs<'js> for char {
+    fn from_js(_ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self> {
+        let string = match String::from_value(value)?.to_string() {
+            Ok(string) => string,
+            Err(Error::Utf8(_)) => {
+                return Err(Error::new_from_js_message(
+                    "string",
+                    "char",
+                    "string contains an unpaired surrogate",
+                ))
+            }
+            Err(error) => return Err(error),
+        };
+        let mut chars = string.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            (None, _) => Err(Error::new_from_js_message(
+                "string",
+                "char",
+                "string is empty",
+            )),
+            (Some(_), Some(_)) => Err(Error::new_from_js_message(
+                "string",
+                "char",
+                "string contains more than one character",
+            )),
+        }
+    }
+}
+
+/// Always returns [`Cow::Owned`].
+///
+/// Quickjs doesn't necessarily store strings as utf-8 and the string data is owned by the
+/// javascript value, which is dropped after the conversion, so the result can't borrow from it.
+impl<'js> FromJs<'js> for Cow<'_, str> {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self> {
+        StdString::from_js(ctx, value).map(Cow::Owned)
+    }
+}
+
+impl<'js> FromJs<'js> for i128 {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self> {
+        BigInt::from_js(ctx, value)?.to_i128()
+    }
+}
+
+impl<'js> FromJs<'js> for u128 {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self> {
+        BigInt::from_js(ctx, value)?.to_u128()
+    }
+}
+
+impl<'js> FromJs<'js> for PathBuf {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self> {
+        StdString::from_js(ctx, value).map(PathBuf::from)
+    }
+}
+
+macro_rules! from_js_parse_impls {
+    ($($type:ident,)*) => {
+        $(
+            /// Convert from a javascript string using the [`FromStr`](std::str::FromStr)
+            /// implementation of the type
+            impl<'js> FromJs<'js> for $type {
+                fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self> {
+                    StdString::from_js(ctx, value)?.parse().map_err(|error: std::net::AddrParseError| {
+                        Error::new_from_js_message("string", stringify!($type), error.to_string())
+                    })
+                }
+            }
+        )*
+    };
+}
+
+// Note that std only supports numeric IPv6 scope ids, only as part of a socket address.
+from_js_parse_impls! {
+    IpAddr,
+    Ipv4Addr,
+    Ipv6Addr,
+    SocketAddr,
+    SocketAddrV4,
+    SocketAddrV6,
+}
+
 /// Convert from JS as any
 impl<'js> FromJs<'js> for () {
     fn from_js(_: &Ctx<'js>, _: Value<'js>) -> Result<Self> {
@@ -322,40 +413,72 @@ impl<'js> FromJs<'js> for f32 {
     }
 }
 
-fn date_to_millis<'js>(ctx: &Ctx<'js>, value: Value<'js>) -> Result<i64> {
-    let global = ctx.globals();
-    let date_ctor: Object = global.get("Date")?;
+impl<'js> FromJs<'js> for SystemTime {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<SystemTime> {
+        Date::from_js(ctx, value)?.to_system_time()
+    }
+}
 
-    let value = Object::from_value(value)?;
+/// Convert from a javascript number of milliseconds
+impl<'js> FromJs<'js> for Duration {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Duration> {
+        let millis = f64::from_js(ctx, value)?;
+
+        if !millis.is_finite() {
+            return Err(Error::new_from_js_message(
+                "number",
+                "Duration",
+                "Duration is not finite",
+            ));
+        }
+        if millis < 0.0 {
+            return Err(Error::new_from_js_message(
+                "number",
+                "Duration",
+                "Duration is negative",
+            ));
+        }
+        let secs = millis / 1000.0;
+        if secs >= u64::MAX as f64 {
+            return Err(Error::new_from_js_message(
+                "number",
+                "Duration",
+                "Duration too big",
+            ));
+        }
 
-    if !value.is_instance_of(&date_ctor) {
-        return Err(Error::new_from_js("Object", "Date"));
+        Ok(Duration::from_secs_f64(secs))
     }
+}
 
-    let get_time_fn: crate::Function = value.get("getTime")?;
-
-    get_time_fn.call((crate::function::This(value),))
+/// Convert from a javascript big int
+#[cfg(feature = "num-bigint")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "num-bigint")))]
+impl<'js> FromJs<'js> for num_bigint::BigInt {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self> {
+        let decimal = BigInt::from_js(ctx, value)?.to_decimal_string()?;
+        decimal.parse().map_err(|_| {
+            Error::new_from_js_message("BigInt", "num_bigint::BigInt", "Invalid decimal string")
+        })
+    }
 }
 
-impl<'js> FromJs<'js> for SystemTime {
-    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<SystemTime> {
-        let millis = date_to_millis(ctx, value)?;
-
-        if millis >= 0 {
-            // since unix epoch
-            SystemTime::UNIX_EPOCH
-                .checked_add(Duration::from_millis(millis as _))
-                .ok_or_else(|| {
-                    Error::new_from_js_message("Date", "SystemTime", "Timestamp too big")
-                })
-        } else {
-            // before unix epoch
-            SystemTime::UNIX_EPOCH
-                .checked_sub(Duration::from_millis((-millis) as _))
-                .ok_or_else(|| {
-                    Error::new_from_js_message("Date", "SystemTime", "Timestamp too small")
-                })
+/// Convert from a javascript big int
+#[cfg(feature = "num-bigint")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "num-bigint")))]
+impl<'js> FromJs<'js> for num_bigint::BigUint {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<Self> {
+        let decimal = BigInt::from_js(ctx, value)?.to_decimal_string()?;
+        if decimal.starts_with('-') {
+            return Err(Error::new_from_js_message(
+                "BigInt",
+                "num_bigint::BigUint",
+                "Value is negative",
+            ));
         }
+        decimal.parse().map_err(|_| {
+            Error::new_from_js_message("BigInt", "num_bigint::BigUint", "Invalid decimal string")
+        })
     }
 }
 
@@ -367,7 +490,7 @@ macro_rules! chrono_from_js_impls {
                 fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> Result<chrono::DateTime<chrono::$type>> {
                     use chrono::TimeZone;
 
-                    let millis = date_to_millis(ctx, value)?;
+                    let millis = Date::from_js(ctx, value)?.to_millis()?;
 
                     chrono::$type.timestamp_millis_opt(millis).single()
                         .ok_or_else(|| {
@@ -409,6 +532,170 @@ mod test {
         });
     }
 
+    #[test]
+    fn js_to_duration() {
+        use crate::{Context, Error, Runtime};
+        use std::time::Duration;
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+
+        ctx.with(|ctx| {
+            let res: Duration = ctx.eval("1500").unwrap();
+            assert_eq!(Duration::from_millis(1500), res);
+
+            let res: Duration = ctx.eval("0.25").unwrap();
+            assert_eq!(Duration::from_micros(250), res);
+
+            let res = ctx.eval::<Duration, _>("-1");
+            assert!(matches!(res, Err(Error::FromJs { .. })));
+
+            let res = ctx.eval::<Duration, _>("Infinity");
+            assert!(matches!(res, Err(Error::FromJs { .. })));
+
+            let res = ctx.eval::<Duration, _>("NaN");
+            assert!(matches!(res, Err(Error::FromJs { .. })));
+        });
+    }
+
+    #[test]
+    fn js_to_char() {
+        use crate::{Context, Error, Runtime, StdString};
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+
+        ctx.with(|ctx| {
+            assert_eq!(ctx.eval::<char, _>("'a'").unwrap(), 'a');
+            assert_eq!(ctx.eval::<char, _>("'\\u{1F980}'").unwrap(), '🦀');
+            for source in ["''", "'ab'", "'a\\u0301'", "'\\uD83E'", "'\\uDD80'", "1"] {
+                let res = ctx.eval::<char, _>(source);
+                assert!(matches!(res, Err(Error::FromJs { .. })), "{}", source);
+            }
+
+            for c in ['a', 'é', '🦀'] {
+                ctx.globals().set("c", c).unwrap();
+                let res: StdString = ctx.eval("c").unwrap();
+                assert_eq!(res, c.to_string());
+                let len: usize = ctx.eval("c.length").unwrap();
+                assert_eq!(len, c.len_utf16());
+                assert_eq!(ctx.eval::<char, _>("c").unwrap(), c);
+            }
+        });
+    }
+
+    #[test]
+    fn js_to_cow_str() {
+        use crate::{Context, Function, Runtime};
+        use std::borrow::Cow;
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+
+        ctx.with(|ctx| {
+            let reverse: Function = ctx.eval("s => [...s].reverse().join('')").unwrap();
+            let borrowed: Cow<str> = Cow::Borrowed("héllo");
+            let res: Cow<str> = reverse.call((borrowed,)).unwrap();
+            assert!(matches!(res, Cow::Owned(_)));
+            assert_eq!(res, "olléh");
+
+            let owned: Cow<str> = Cow::Owned("wörld".to_string());
+            let res: Cow<'static, str> = reverse.call((&owned,)).unwrap();
+            assert_eq!(res, "dlröw");
+
+            let res: Cow<str> = ctx.eval("'a' + 'b'").unwrap();
+            assert_eq!(res, "ab");
+        });
+    }
+
+    #[test]
+    fn js_to_hash_map_with_hasher() {
+        use crate::{Context, Runtime};
+        use std::{
+            collections::HashMap,
+            hash::{BuildHasherDefault, Hasher},
+        };
+
+        /// FNV-1a, only there to have a hasher which isn't the default one.
+        struct Fnv(u64);
+
+        impl Default for Fnv {
+            fn default() -> Self {
+                Fnv(0xcbf29ce484222325)
+            }
+        }
+
+        impl Hasher for Fnv {
+            fn finish(&self) -> u64 {
+                self.0
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                for byte in bytes {
+                    self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x100000001b3);
+                }
+            }
+        }
+
+        type FnvMap<K, V> = HashMap<K, V, BuildHasherDefault<Fnv>>;
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+
+        ctx.with(|ctx| {
+            let res: FnvMap<String, i32> = ctx
+                .eval(
+                    r#"
+                    const obj = Object.create({ inherited: 0 }, { hidden: { value: 0 } });
+                    obj.a = 1;
+                    obj.b = 2;
+                    obj[Symbol("c")] = 3;
+                    obj
+                    "#,
+                )
+                .unwrap();
+            let mut entries: Vec<_> = res.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+            entries.sort();
+            assert_eq!(entries, [("a", 1), ("b", 2)]);
+
+            ctx.globals().set("map", res).unwrap();
+            let res: bool = ctx
+                .eval(
+                    "Object.getPrototypeOf(map) === Object.prototype && map.a === 1 && map.b === 2",
+                )
+                .unwrap();
+            assert!(res);
+        });
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn js_to_num_bigint() {
+        use crate::{Context, Error, Runtime};
+        use num_bigint::{BigInt, BigUint};
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+
+        ctx.with(|ctx| {
+            let res: BigInt = ctx.eval("2n ** 511n + 12345n").unwrap();
+            let expected = (BigInt::from(1) << 511usize) + BigInt::from(12345);
+            assert_eq!(res, expected);
+
+            let res: BigInt = ctx.eval("-(2n ** 511n)").unwrap();
+            assert_eq!(res, -(BigInt::from(1) << 511usize));
+
+            let res: BigInt = ctx.eval("0n").unwrap();
+            assert_eq!(res, BigInt::from(0));
+
+            let res: BigUint = ctx.eval("2n ** 512n - 1n").unwrap();
+            assert_eq!(res, (BigUint::from(1u32) << 512usize) - 1u32);
+
+            let res = ctx.eval::<BigUint, _>("-1n");
+            assert!(matches!(res, Err(Error::FromJs { .. })));
+        });
+    }
+
     #[cfg(feature = "chrono")]
     #[test]
     fn js_to_chrono() {
@@ -437,4 +724,36 @@ mod test {
             assert_eq!(1654309010000, res.timestamp_millis());
         });
     }
+
+    #[test]
+    fn js_to_net_addrs() {
+        use crate::{Context, Error, Runtime};
+        use std::net::{IpAddr, Ipv6Addr, SocketAddr, SocketAddrV6};
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+
+        ctx.with(|ctx| {
+            let addr: SocketAddr = ctx.eval("'127.0.0.1:8080'").unwrap();
+            assert_eq!(addr, SocketAddr::from(([127, 0, 0, 1], 8080)));
+            ctx.globals().set("addr", addr).unwrap();
+            assert!(ctx.eval::<bool, _>("addr === '127.0.0.1:8080'").unwrap());
+
+            let addr: SocketAddr = ctx.eval("'[::1]:443'").unwrap();
+            assert_eq!(addr, SocketAddr::from((Ipv6Addr::LOCALHOST, 443)));
+            ctx.globals().set("addr", addr).unwrap();
+            assert!(ctx.eval::<bool, _>("addr === '[::1]:443'").unwrap());
+
+            let addr = SocketAddrV6::new("fe80::1".parse().unwrap(), 80, 0, 3);
+            ctx.globals().set("addr", addr).unwrap();
+            assert!(ctx.eval::<bool, _>("addr === '[fe80::1%3]:80'").unwrap());
+            assert_eq!(ctx.eval::<SocketAddrV6, _>("addr").unwrap(), addr);
+
+            let ip: IpAddr = ctx.eval("'::ffff:10.0.0.1'").unwrap();
+            assert_eq!(ip, "::ffff:10.0.0.1".parse::<IpAddr>().unwrap());
+
+            let res = ctx.eval::<IpAddr, _>("'256.0.0.1'");
+            assert!(matches!(res, Err(Error::FromJs { to: "IpAddr", .. })));
+        });
+    }
 }
@@ -1,13 +1,16 @@
 use crate::{
-    convert::{IteratorJs, List},
-    value::Constructor,
-    Array, Ctx, Error, IntoAtom, IntoJs, Object, Result, StdResult, StdString, String, Value,
+    convert::{IteratorJs, List, Throw},
+    Array, BigInt, Ctx, Date, Error, IntoAtom, IntoJs, Object, Result, StdResult, StdString,
+    String, Value,
 };
 use std::{
+    borrow::Cow,
     cell::{Cell, RefCell},
     collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    path::{Path, PathBuf},
     sync::{Mutex, RwLock},
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
 #[cfg(feature = "either")]
@@ -46,6 +49,96 @@ impl<'js> IntoJs<'js> for &str {
     }
 }
 
+/// Convert into a javascript string with a single character.
+impl<'js> IntoJs<'js> for char {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        (&*self.encode_utf8(&mut [0; 4])).into_js(ctx)
+    }
+}
+
+impl<'js> IntoJs<'js> for &char {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        (*self).into_js(ctx)
+    }
+}
+
+impl<'js> IntoJs<'js> for Cow<'_, str> {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        self.as_ref().into_js(ctx)
+    }
+}
+
+impl<'js> IntoJs<'js> for &Cow<'_, str> {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        self.as_ref().into_js(ctx)
+    }
+}
+
+impl<'js> IntoJs<'js> for i128 {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        BigInt::from_i128(ctx.clone(), self).map(|BigInt(value)| value)
+    }
+}
+
+impl<'js> IntoJs<'js> for u128 {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        BigInt::from_u128(ctx.clone(), self).map(|BigInt(value)| value)
+    }
+}
+
+/// Convert into a javascript string
+///
+/// Paths which are not valid UTF-8 are converted lossily, invalid sequences are replaced with
+/// `U+FFFD REPLACEMENT CHARACTER`.
+impl<'js> IntoJs<'js> for &Path {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        self.to_string_lossy().as_ref().into_js(ctx)
+    }
+}
+
+/// Convert into a javascript string
+///
+/// See the implementation for [`Path`] on how non UTF-8 paths are handled.
+impl<'js> IntoJs<'js> for PathBuf {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        self.as_path().into_js(ctx)
+    }
+}
+
+impl<'js> IntoJs<'js> for &PathBuf {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        self.as_path().into_js(ctx)
+    }
+}
+
+macro_rules! into_js_display_impls {
+    ($($type:ident,)*) => {
+        $(
+            /// Convert into a javascript string in the canonical form of the address
+            impl<'js> IntoJs<'js> for $type {
+                fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+                    (&self).into_js(ctx)
+                }
+            }
+
+            impl<'js> IntoJs<'js> for &$type {
+                fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+                    self.to_string().into_js(ctx)
+                }
+            }
+        )*
+    };
+}
+
+into_js_display_impls! {
+    IpAddr,
+    Ipv4Addr,
+    Ipv6Addr,
+    SocketAddr,
+    SocketAddrV4,
+    SocketAddrV6,
+}
+
 impl<'js, T> IntoJs<'js> for &[T]
 where
     for<'a> &'a T: IntoJs<'js>,
@@ -104,6 +197,19 @@ where
     }
 }
 
+impl<'js, T, E> IntoJs<'js> for StdResult<T, Throw<E>>
+where
+    T: IntoJs<'js>,
+    E: IntoJs<'js>,
+{
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        match self {
+            Ok(value) => value.into_js(ctx),
+            Err(Throw(error)) => Err(ctx.throw(error.into_js(ctx)?)),
+        }
+    }
+}
+
 impl<'js, T, E> IntoJs<'js> for &StdResult<T, E>
 where
     for<'a> &'a T: IntoJs<'js>,
@@ -447,58 +553,90 @@ into_js_impls! {
     i32 f64 => i64 u32 u64 usize isize,
 }
 
-fn millis_to_date<'js>(ctx: &Ctx<'js>, millis: i64) -> Result<Value<'js>> {
-    let date_ctor: Constructor = ctx.globals().get("Date")?;
+impl<'js> IntoJs<'js> for SystemTime {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        Date::new(ctx.clone(), self).map(Date::into_value)
+    }
+}
 
-    date_ctor.construct((millis,))
+/// Convert into a javascript number of milliseconds
+impl<'js> IntoJs<'js> for Duration {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        let millis = self.as_secs() as f64 * 1000.0 + self.subsec_nanos() as f64 / 1_000_000.0;
+        millis.into_js(ctx)
+    }
 }
 
-impl<'js> IntoJs<'js> for SystemTime {
+/// Convert into a javascript big int
+#[cfg(feature = "num-bigint")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "num-bigint")))]
+impl<'js> IntoJs<'js> for &num_bigint::BigInt {
     fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
-        let millis = match self.duration_since(SystemTime::UNIX_EPOCH) {
-            // since unix epoch
-            Ok(duration) => {
-                let millis = duration.as_millis();
-
-                if millis > i64::MAX as _ {
-                    return Err(Error::new_into_js_message(
-                        "SystemTime",
-                        "Date",
-                        "Timestamp too big",
-                    ));
-                }
+        BigInt::from_decimal_str(ctx.clone(), &self.to_string()).map(|BigInt(value)| value)
+    }
+}
 
-                millis as i64
-            }
-            // before unix epoch
-            Err(error) => {
-                let millis = error.duration().as_millis();
-
-                if millis > -(i64::MIN as i128) as _ {
-                    return Err(Error::new_into_js_message(
-                        "SystemTime",
-                        "Date",
-                        "Timestamp too small",
-                    ));
-                }
+/// Convert into a javascript big int
+#[cfg(feature = "num-bigint")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "num-bigint")))]
+impl<'js> IntoJs<'js> for num_bigint::BigInt {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        (&self).into_js(ctx)
+    }
+}
 
-                (-(millis as i128)) as i64
-            }
-        };
+/// Convert into a javascript big int
+#[cfg(feature = "num-bigint")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "num-bigint")))]
+impl<'js> IntoJs<'js> for &num_bigint::BigUint {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        BigInt::from_decimal_str(ctx.clone(), &self.to_string()).map(|BigInt(value)| value)
+    }
+}
 
-        millis_to_date(ctx, millis)
+/// Convert into a javascript big int
+#[cfg(feature = "num-bigint")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "num-bigint")))]
+impl<'js> IntoJs<'js> for num_bigint::BigUint {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        (&self).into_js(ctx)
     }
 }
 
 #[cfg(feature = "chrono")]
 impl<'js, Tz: chrono::TimeZone> IntoJs<'js> for chrono::DateTime<Tz> {
     fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
-        millis_to_date(ctx, self.timestamp_millis())
+        Date::from_millis(ctx.clone(), self.timestamp_millis()).map(Date::into_value)
     }
 }
 
 #[cfg(test)]
 mod test {
+    #[test]
+    fn throw_any_value() {
+        use crate::{convert::Throw, test_with, Function};
+
+        test_with(|ctx| {
+            let func = Function::new(ctx.clone(), |fail: bool| -> Result<&str, Throw<i32>> {
+                if fail {
+                    return Err(Throw(42));
+                }
+                Ok("fine")
+            })
+            .unwrap();
+            ctx.globals().set("f", func).unwrap();
+
+            let res: i32 = ctx.eval("try { f(true); 0 } catch (e) { e }").unwrap();
+            assert_eq!(res, 42);
+            let res: bool = ctx
+                .eval("try { f(true) } catch (e) { typeof e === 'number' }")
+                .unwrap();
+            assert!(res);
+            let res: crate::StdString = ctx.eval("f(false)").unwrap();
+            assert_eq!(res, "fine");
+        });
+    }
+
     #[test]
     fn system_time_to_js() {
         use crate::{Context, IntoJs, Runtime};
@@ -534,6 +672,97 @@ mod test {
         });
     }
 
+    #[test]
+    fn path_to_js() {
+        use crate::{Context, Runtime};
+        use std::path::{Path, PathBuf};
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+
+        ctx.with(|ctx| {
+            let globs = ctx.globals();
+            globs.set("p", PathBuf::from("/tmp/x")).unwrap();
+            let res: PathBuf = ctx.eval("p").unwrap();
+            assert_eq!(res, PathBuf::from("/tmp/x"));
+
+            globs.set("p", Path::new("/tmp/y")).unwrap();
+            let res: bool = ctx.eval("p === '/tmp/y'").unwrap();
+            assert!(res);
+        });
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn non_utf8_path_to_js() {
+        use crate::{Context, Runtime, StdString};
+        use std::{ffi::OsString, os::windows::ffi::OsStringExt, path::PathBuf};
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+
+        ctx.with(|ctx| {
+            // 'a', an unpaired surrogate, 'b'
+            let path = PathBuf::from(OsString::from_wide(&[0x61, 0xD800, 0x62]));
+            ctx.globals().set("p", path).unwrap();
+            let res: StdString = ctx.eval("p").unwrap();
+            assert_eq!(res, "a\u{FFFD}b");
+        });
+    }
+
+    #[test]
+    fn duration_round_trip() {
+        use crate::{Context, IntoJs, Runtime};
+        use std::time::Duration;
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+
+        ctx.with(|ctx| {
+            let globs = ctx.globals();
+            globs
+                .set("d", Duration::from_millis(1500).into_js(&ctx).unwrap())
+                .unwrap();
+            let res: f64 = ctx.eval("d").unwrap();
+            assert_eq!(res, 1500.0);
+
+            let duration = Duration::from_micros(2500);
+            globs.set("d", duration).unwrap();
+            let res: f64 = ctx.eval("d").unwrap();
+            assert_eq!(res, 2.5);
+            let res: Duration = ctx.eval("d").unwrap();
+            assert_eq!(res, duration);
+        });
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn num_bigint_to_js() {
+        use crate::{Context, Runtime};
+        use num_bigint::BigInt;
+
+        let runtime = Runtime::new().unwrap();
+        let ctx = Context::full(&runtime).unwrap();
+
+        ctx.with(|ctx| {
+            let globs = ctx.globals();
+            let value = (BigInt::from(1) << 511usize) + BigInt::from(12345);
+            globs.set("v", &value).unwrap();
+            let res: bool = ctx.eval("v === 2n ** 511n + 12345n").unwrap();
+            assert!(res);
+            let res: BigInt = ctx.eval("v").unwrap();
+            assert_eq!(res, value);
+
+            globs.set("v", -value).unwrap();
+            let res: bool = ctx.eval("v === -(2n ** 511n + 12345n)").unwrap();
+            assert!(res);
+
+            globs.set("v", BigInt::from(0)).unwrap();
+            let res: bool = ctx.eval("v === 0n").unwrap();
+            assert!(res);
+        });
+    }
+
     #[cfg(feature = "chrono")]
     #[test]
     fn chrono_to_js() {
@@ -0,0 +1,144 @@
+use crate::{function::This, value::Constructor, Ctx, Error, Function, Object, Outlive, Result};
+use std::time::{Duration, SystemTime};
+
+/// Rust representation of a javascript object of class Date.
+#[derive(Debug, PartialEq, Clone)]
+#[repr(transparent)]
+pub struct Date<'js>(pub(crate) Object<'js>);
+
+unsafe impl<'js> Outlive<'js> for Date<'js> {
+    type Target<'to> = Date<'to>;
+}
+
+impl<'js> Date<'js> {
+    /// Create a new date from a system time.
+    pub fn new(ctx: Ctx<'js>, time: SystemTime) -> Result<Self> {
+        let millis = match time.duration_since(SystemTime::UNIX_EPOCH) {
+            // since unix epoch
+            Ok(duration) => {
+                let millis = duration.as_millis();
+
+                if millis > i64::MAX as _ {
+                    return Err(Error::new_into_js_message(
+                        "SystemTime",
+                        "Date",
+                        "Timestamp too big",
+                    ));
+                }
+
+                millis as i64
+            }
+            // before unix epoch
+            Err(error) => {
+                let millis = error.duration().as_millis();
+
+                if millis > -(i64::MIN as i128) as _ {
+                    return Err(Error::new_into_js_message(
+                        "SystemTime",
+                        "Date",
+                        "Timestamp too small",
+                    ));
+                }
+
+                (-(millis as i128)) as i64
+            }
+        };
+        Self::from_millis(ctx, millis)
+    }
+
+    /// Create a new date from the number of milliseconds since the unix epoch.
+    pub fn from_millis(ctx: Ctx<'js>, millis: i64) -> Result<Self> {
+        let ctor: Constructor = ctx.globals().get("Date")?;
+        ctor.construct((millis,)).map(Date)
+    }
+
+    /// Create a new date with the current time.
+    pub fn now(ctx: Ctx<'js>) -> Result<Self> {
+        let ctor: Constructor = ctx.globals().get("Date")?;
+        ctor.construct(()).map(Date)
+    }
+
+    /// Returns the number of milliseconds since the unix epoch.
+    ///
+    /// Returns an error if the date is an `Invalid Date`.
+    pub fn to_millis(&self) -> Result<i64> {
+        let get_time: Function = self.0.get("getTime")?;
+        let millis: f64 = get_time.call((This(self.0.clone()),))?;
+        if millis.is_nan() {
+            return Err(Error::new_from_js_message("Date", "i64", "Invalid Date"));
+        }
+        Ok(millis as i64)
+    }
+
+    /// Convert the date into a system time.
+    ///
+    /// Returns an error if the date is an `Invalid Date`.
+    pub fn to_system_time(&self) -> Result<SystemTime> {
+        let millis = self.to_millis()?;
+
+        if millis >= 0 {
+            // since unix epoch
+            SystemTime::UNIX_EPOCH
+                .checked_add(Duration::from_millis(millis as _))
+                .ok_or_else(|| {
+                    Error::new_from_js_message("Date", "SystemTime", "Timestamp too big")
+                })
+        } else {
+            // before unix epoch
+            SystemTime::UNIX_EPOCH
+                .checked_sub(Duration::from_millis((-millis) as _))
+                .ok_or_else(|| {
+                    Error::new_from_js_message("Date", "SystemTime", "Timestamp too small")
+                })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn from_system_time() {
+        test_with(|ctx| {
+            let time = SystemTime::UNIX_EPOCH + Duration::from_millis(1654287410000);
+            let date = Date::new(ctx.clone(), time).unwrap();
+            assert_eq!(date.to_millis().unwrap(), 1654287410000);
+            assert_eq!(date.to_system_time().unwrap(), time);
+
+            ctx.globals().set("d", date).unwrap();
+            let res: i64 = ctx.eval("d.getTime()").unwrap();
+            assert_eq!(res, 1654287410000);
+        });
+    }
+
+    #[test]
+    fn before_epoch() {
+        test_with(|ctx| {
+            let date: Date = ctx.eval("new Date(-123456789)").unwrap();
+            assert_eq!(
+                SystemTime::UNIX_EPOCH
+                    .duration_since(date.to_system_time().unwrap())
+                    .unwrap(),
+                Duration::from_millis(123456789)
+            );
+        });
+    }
+
+    #[test]
+    fn invalid_date() {
+        test_with(|ctx| {
+            let date: Date = ctx.eval("new Date(NaN)").unwrap();
+            assert!(matches!(date.to_system_time(), Err(Error::FromJs { .. })));
+        });
+    }
+
+    #[test]
+    fn not_a_date() {
+        test_with(|ctx| {
+            let res: Result<Date> = ctx.eval("({})");
+            assert!(matches!(res, Err(Error::FromJs { .. })));
+        });
+    }
+}
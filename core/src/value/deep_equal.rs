@@ -0,0 +1,262 @@
+use std::collections::HashSet;
+
+use crate::{
+    atom::PredefinedAtom,
+    qjs,
+    value::builtin::{BuiltinClass, View},
+    Object, Result, StdString, Type, Value,
+};
+
+impl<'js> Value<'js> {
+    /// Compare two values for structural equality.
+    ///
+    /// Primitives are compared like with the `===` operator, so integers and floats with the same
+    /// value are equal and strings are compared by their contents. Note that this means `NaN` is
+    /// not equal to itself, also not when nested in an object, which mirrors `===` rather than
+    /// `Object.is`.
+    ///
+    /// Objects are equal if they are the same object or if they have the same shape:
+    /// - Arrays must have the same length and equal elements.
+    /// - Dates must have the same time value.
+    /// - Typed arrays and data views must be of the same type and have equal elements.
+    /// - Maps must have the same keys, compared by identity, with equal values, sets must have
+    ///   the same values, also compared by identity.
+    /// - Other objects must have the same own enumerable string keys with equal values.
+    ///
+    /// The kind of objects is detected by the builtin class they were created with, scripts which
+    /// replace prototype methods or `Symbol.toStringTag` don't change the comparison.
+    ///
+    /// Prototypes are not compared and functions, errors and symbols are only equal to themselves.
+    /// References which lead back to an object which is already being compared are assumed to be
+    /// equal, so self-referential structures can be compared without looping forever.
+    pub fn deep_equal(&self, other: &Value<'js>) -> Result<bool> {
+        Comparer::default().equal(self, other)
+    }
+}
+
+#[derive(Default)]
+struct Comparer {
+    /// Pairs of objects which are currently compared or were found to be equal.
+    visited: HashSet<(*mut qjs::c_void, *mut qjs::c_void)>,
+}
+
+impl Comparer {
+    fn equal<'js>(&mut self, a: &Value<'js>, b: &Value<'js>) -> Result<bool> {
+        if let (Some(a), Some(b)) = (a.as_number(), b.as_number()) {
+            return Ok(a == b);
+        }
+        let type_ = a.type_of();
+        if type_ != b.type_of() {
+            return Ok(false);
+        }
+        match type_ {
+            Type::Uninitialized | Type::Undefined | Type::Null => Ok(true),
+            Type::Bool => Ok(a.as_bool() == b.as_bool()),
+            Type::String => Ok(a.get::<StdString>()? == b.get::<StdString>()?),
+            Type::BigInt => {
+                let (a, b) = (a.to_js_string()?, b.to_js_string()?);
+                Ok(a.to_string()? == b.to_string()?)
+            }
+            Type::Array | Type::Object => {
+                if a == b {
+                    return Ok(true);
+                }
+                let key = unsafe { (a.get_ptr(), b.get_ptr()) };
+                if !self.visited.insert(key) {
+                    return Ok(true);
+                }
+                self.object(a.as_object().unwrap(), b.as_object().unwrap())
+            }
+            _ => Ok(a == b),
+        }
+    }
+
+    fn object<'js>(&mut self, a: &Object<'js>, b: &Object<'js>) -> Result<bool> {
+        let class = a.builtin_class();
+        if class != b.builtin_class() {
+            return Ok(false);
+        }
+
+        match class {
+            BuiltinClass::Array => return self.array(a, b),
+            BuiltinClass::Date => return Ok(a.date_value()? == b.date_value()?),
+            BuiltinClass::Map => {
+                let entries = a.map_entries()?;
+                if entries.len() != b.map_entries()?.len() {
+                    return Ok(false);
+                }
+                for (key, value) in entries {
+                    match b.map_get(&key)? {
+                        Some(other) if self.equal(&value, &other)? => {}
+                        _ => return Ok(false),
+                    }
+                }
+                return Ok(true);
+            }
+            BuiltinClass::Set => {
+                let entries = a.map_entries()?;
+                if entries.len() != b.map_entries()?.len() {
+                    return Ok(false);
+                }
+                for (key, _) in entries {
+                    if b.map_get(&key)?.is_none() {
+                        return Ok(false);
+                    }
+                }
+                return Ok(true);
+            }
+            BuiltinClass::View(_) => return self.view(a, b),
+            _ => {}
+        }
+
+        let keys_a = a.keys::<StdString>().collect::<Result<Vec<_>>>()?;
+        let mut keys_b = b.keys::<StdString>().collect::<Result<HashSet<_>>>()?;
+        if keys_a.len() != keys_b.len() {
+            return Ok(false);
+        }
+        for key in keys_a {
+            if !keys_b.remove(&key) {
+                return Ok(false);
+            }
+            let (value_a, value_b) = (a.get(key.as_str())?, b.get(key.as_str())?);
+            if !self.equal(&value_a, &value_b)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn array<'js>(&mut self, a: &Object<'js>, b: &Object<'js>) -> Result<bool> {
+        let len: usize = a.get(PredefinedAtom::Length)?;
+        if len != b.get::<_, usize>(PredefinedAtom::Length)? {
+            return Ok(false);
+        }
+        for idx in 0..len as u32 {
+            if !self.equal(&a.get(idx)?, &b.get(idx)?)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn view<'js>(&mut self, a: &Object<'js>, b: &Object<'js>) -> Result<bool> {
+        let (view_a, view_b) = (a.view()?, b.view()?);
+        if view_a.len != view_b.len {
+            return Ok(false);
+        }
+        if view_a.class_id == qjs::JS_CLASS_DATAVIEW {
+            // Compare the viewed bytes through byte views of the same range.
+            let bytes = |view: View<'js>| {
+                let class_id = qjs::JS_CLASS_UINT8_ARRAY;
+                View::create(a.ctx(), class_id, &view.buffer, view.offset, view.len)
+            };
+            return self.view(&bytes(view_a)?, &bytes(view_b)?);
+        }
+        for idx in 0..(view_a.len / view_a.element_size) as u32 {
+            if !self.equal(&a.get(idx)?, &b.get(idx)?)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn nested_structures() {
+        test_with(|ctx| {
+            let build = r#"
+                (() => {
+                    const value = {
+                        name: "root",
+                        list: [1, 2.5, "three", [4n]],
+                        date: new Date(1000),
+                        bytes: new Uint8Array([1, 2, 3]),
+                        map: new Map([["key", { nested: true }]]),
+                    };
+                    value.self = value;
+                    return value;
+                })()
+            "#;
+            let a: Value = ctx.eval(build).unwrap();
+            let b: Value = ctx.eval(build).unwrap();
+            assert!(a != b);
+            assert!(a.deep_equal(&b).unwrap());
+            assert!(a.deep_equal(&a).unwrap());
+
+            let one: Value = ctx.eval("1").unwrap();
+            let float: Value = ctx.eval("1.0 + 0.0").unwrap();
+            assert!(one.deep_equal(&float).unwrap());
+
+            ctx.globals().set("b", b.clone()).unwrap();
+            ctx.eval::<(), _>("b.map.get('key').nested = false")
+                .unwrap();
+            assert!(!a.deep_equal(&b).unwrap());
+        });
+    }
+
+    #[test]
+    fn replaced_builtins() {
+        test_with(|ctx| {
+            ctx.eval::<(), _>(
+                r#"
+                globalThis.bytes = [new Uint8Array([1]), new Int8Array([1])];
+                const fail = () => { throw new Error("replaced builtin used"); };
+                Map.prototype.get = Map.prototype.has = Map.prototype.entries = fail;
+                Set.prototype.has = Set.prototype.values = fail;
+                Date.prototype.valueOf = Date.prototype.getTime = fail;
+                for (const ta of [Uint8Array, Int8Array]) {
+                    Object.defineProperty(ta.prototype, Symbol.toStringTag, { get: () => "Tag" });
+                    Object.defineProperty(ta.prototype, "length", { get: fail });
+                }
+                globalThis.ArrayBuffer = globalThis.Uint8Array = fail;
+                "#,
+            )
+            .unwrap();
+            let build = r#"
+                [
+                    new Map([["key", 1]]),
+                    new Set([1]),
+                    new Date(1),
+                    new Int8Array([1, 2]),
+                    new DataView(new Int8Array([1, 2, 3]).buffer, 1),
+                ]
+            "#;
+            let a: Value = ctx.eval(build).unwrap();
+            let b: Value = ctx.eval(build).unwrap();
+            assert!(a.deep_equal(&b).unwrap());
+
+            let a: Value = ctx.eval("bytes[0]").unwrap();
+            let b: Value = ctx.eval("bytes[1]").unwrap();
+            assert!(a.deep_equal(&a).unwrap());
+            assert!(!a.deep_equal(&b).unwrap());
+        });
+    }
+
+    #[test]
+    fn differences() {
+        test_with(|ctx| {
+            let pairs = [
+                ("({ a: 1 })", "({ a: 1, b: 2 })"),
+                ("({ a: 1 })", "({ b: 1 })"),
+                ("[1, 2]", "[1, 2, 3]"),
+                ("[1, 2]", "({ 0: 1, 1: 2 })"),
+                ("new Date(1)", "new Date(2)"),
+                ("new Uint8Array([1])", "new Int8Array([1])"),
+                ("new Set([{}])", "new Set([{}])"),
+                ("'1'", "1"),
+                ("NaN", "NaN"),
+                ("[NaN]", "[NaN]"),
+                ("null", "undefined"),
+            ];
+            for (a, b) in pairs {
+                let a: Value = ctx.eval(a).unwrap();
+                let b: Value = ctx.eval(b).unwrap();
+                assert!(!a.deep_equal(&b).unwrap(), "{:?} == {:?}", a, b);
+            }
+        });
+    }
+}
@@ -1,6 +1,8 @@
 use std::{error::Error as ErrorTrait, fmt};
 
-use crate::{atom::PredefinedAtom, convert::Coerced, qjs, Ctx, Error, Object, Result, Value};
+use crate::{
+    atom::PredefinedAtom, convert::Coerced, qjs, Atom, Ctx, Error, IntoJs, Object, Result, Value,
+};
 
 /// A javascript instance of Error
 ///
@@ -53,6 +55,33 @@ impl<'js> Exception<'js> {
         Ok(Exception(obj))
     }
 
+    /// Creates a new exception of a native error class with a given message.
+    ///
+    /// The kind is one of the `JS_*_ERROR` constants or -1 for a generic error. The error is
+    /// created with the intrinsic prototype of its class, so scripts which replace the global
+    /// error constructors don't affect it.
+    pub(crate) fn from_native(ctx: Ctx<'js>, kind: qjs::c_int, message: &str) -> Result<Self> {
+        let obj = unsafe {
+            let value = ctx.handle_exception(qjs::JS_NewNativeError(ctx.as_ptr(), kind))?;
+            Object::from_js_value(ctx.clone(), value)
+        };
+        let message = message.into_js(&ctx)?;
+        unsafe {
+            // Defined like quickjs does for the errors it throws.
+            let res = qjs::JS_DefinePropertyValue(
+                ctx.as_ptr(),
+                obj.as_js_value(),
+                PredefinedAtom::Message as qjs::JSAtom,
+                message.into_js_value(),
+                (qjs::JS_PROP_CONFIGURABLE | qjs::JS_PROP_WRITABLE | qjs::JS_PROP_THROW) as _,
+            );
+            if res < 0 {
+                return Err(ctx.raise_exception());
+            }
+        }
+        Ok(Exception(obj))
+    }
+
     /// Creates a new exception with a give message, file name and line number.
     pub fn from_message_location(
         ctx: Ctx<'js>,
@@ -72,6 +101,29 @@ impl<'js> Exception<'js> {
         Ok(Exception(obj))
     }
 
+    /// Creates a new exception with a given message and cause.
+    ///
+    /// The cause is defined as the non-enumerable `cause` property, like
+    /// `new Error(message, { cause })` does in javascript. Any value can be a cause, including
+    /// another exception to build a chain of errors.
+    pub fn from_message_cause(ctx: Ctx<'js>, message: &str, cause: Value<'js>) -> Result<Self> {
+        let exception = Self::from_message(ctx.clone(), message)?;
+        let atom = Atom::from_str(ctx.clone(), "cause")?;
+        unsafe {
+            let res = qjs::JS_DefinePropertyValue(
+                ctx.as_ptr(),
+                exception.0.as_js_value(),
+                atom.atom,
+                cause.into_js_value(),
+                (qjs::JS_PROP_CONFIGURABLE | qjs::JS_PROP_WRITABLE | qjs::JS_PROP_THROW) as _,
+            );
+            if res < 0 {
+                return Err(ctx.raise_exception());
+            }
+        }
+        Ok(exception)
+    }
+
     /// Returns the message of the error.
     ///
     /// Same as retrieving `error.message` in javascript.
@@ -104,7 +156,10 @@ impl<'js> Exception<'js> {
 
     /// Returns the error stack.
     ///
-    /// Same as retrieving `error.stack` in javascript.
+    /// Same as retrieving `error.stack` in javascript. The stack is captured when the error is
+    /// created and lists the calling functions, frames before an evaluation with
+    /// [`EvalOptions::backtrace_barrier`](crate::context::EvalOptions::backtrace_barrier) set are
+    /// left out.
     pub fn stack(&self) -> Option<String> {
         self.get::<_, Option<Coerced<String>>>(PredefinedAtom::Stack)
             .ok()
@@ -112,6 +167,16 @@ impl<'js> Exception<'js> {
             .map(|x| x.0)
     }
 
+    /// Returns the cause of the error.
+    ///
+    /// Same as retrieving `error.cause` in javascript but only returns a value if the error has
+    /// a `cause` own property.
+    pub fn cause(&self) -> Option<Value<'js>> {
+        let atom = Atom::from_str(self.ctx().clone(), "cause").ok()?;
+        self.own_descriptor(&atom).ok()??;
+        self.get(atom).ok()
+    }
+
     /// Throws a new generic error.
     ///
     /// Equivalent to:
@@ -138,6 +203,13 @@ impl<'js> Exception<'js> {
         e
     }
 
+    /// Throws a new generic error with a cause.
+    pub fn throw_with_cause(ctx: &Ctx<'js>, message: &str, cause: Value<'js>) -> Error {
+        let (Ok(e) | Err(e)) =
+            Self::from_message_cause(ctx.clone(), message, cause).map(|x| x.throw());
+        e
+    }
+
     /// Throws a new syntax error.
     pub fn throw_syntax(ctx: &Ctx<'js>, message: &str) -> Error {
         // generate C string inline.
@@ -264,3 +336,247 @@ impl fmt::Display for Exception<'_> {
         Ok(())
     }
 }
+
+/// The builtin javascript error classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExceptionKind {
+    /// A generic `Error`
+    Error,
+    /// A `TypeError`
+    Type,
+    /// A `RangeError`
+    Range,
+    /// A `SyntaxError`
+    Syntax,
+    /// A `ReferenceError`
+    Reference,
+    /// An `InternalError`
+    Internal,
+}
+
+impl ExceptionKind {
+    /// Returns the name of the javascript constructor of this kind of error.
+    pub fn name(self) -> &'static str {
+        match self {
+            ExceptionKind::Error => "Error",
+            ExceptionKind::Type => "TypeError",
+            ExceptionKind::Range => "RangeError",
+            ExceptionKind::Syntax => "SyntaxError",
+            ExceptionKind::Reference => "ReferenceError",
+            ExceptionKind::Internal => "InternalError",
+        }
+    }
+
+    /// Returns the kind of native error for [`Exception::from_native`].
+    pub(crate) fn native_error(self) -> qjs::c_int {
+        match self {
+            ExceptionKind::Error => -1,
+            ExceptionKind::Type => qjs::JS_TYPE_ERROR,
+            ExceptionKind::Range => qjs::JS_RANGE_ERROR,
+            ExceptionKind::Syntax => qjs::JS_SYNTAX_ERROR,
+            ExceptionKind::Reference => qjs::JS_REFERENCE_ERROR,
+            ExceptionKind::Internal => qjs::JS_INTERNAL_ERROR,
+        }
+    }
+}
+
+/// A rust error which is thrown into javascript as a builtin error.
+///
+/// Types implementing this trait convert into [`Error::Thrown`], so they can be returned from
+/// rust callbacks with `?`. The error message is the [`Display`](fmt::Display) output of the
+/// error and the class of the thrown error is chosen by [`IntoException::exception_kind`].
+///
+/// ```
+/// # use rquickjs::{Runtime, Context, Function, IntoException, ExceptionKind};
+/// # use std::fmt;
+/// #[derive(Debug)]
+/// struct OutOfRange(i32);
+///
+/// impl fmt::Display for OutOfRange {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "{} is out of range", self.0)
+///     }
+/// }
+///
+/// impl IntoException for OutOfRange {
+///     fn exception_kind(&self) -> ExceptionKind {
+///         ExceptionKind::Range
+///     }
+/// }
+///
+/// # let rt = Runtime::new().unwrap();
+/// # let ctx = Context::full(&rt).unwrap();
+/// # ctx.with(|ctx| {
+/// let check = Function::new(ctx.clone(), |x: i32| -> rquickjs::Result<i32> {
+///     if x > 10 {
+///         Err(OutOfRange(x))?;
+///     }
+///     Ok(x)
+/// })
+/// .unwrap();
+/// ctx.globals().set("check", check).unwrap();
+/// let res: bool = ctx.eval("try { check(11) } catch (e) { e instanceof RangeError }").unwrap();
+/// assert!(res);
+/// # })
+/// ```
+pub trait IntoException: fmt::Display {
+    /// The kind of javascript error this error is thrown as.
+    fn exception_kind(&self) -> ExceptionKind {
+        ExceptionKind::Error
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn throw_with_cause() {
+        test_with(|ctx| {
+            let func = Function::new(ctx.clone(), |ctx: Ctx| -> Result<()> {
+                let cause = Exception::from_message(ctx.clone(), "inner")?;
+                Err(Exception::throw_with_cause(
+                    &ctx,
+                    "outer",
+                    cause.into_object().into_value(),
+                ))
+            })
+            .unwrap();
+            ctx.globals().set("f", func).unwrap();
+
+            let res: StdString = ctx
+                .eval("try { f() } catch (e) { `${e.message}: ${e.cause.message}` }")
+                .unwrap();
+            assert_eq!(res, "outer: inner");
+            let res: bool = ctx
+                .eval("try { f() } catch (e) { Object.keys(e).includes('cause') }")
+                .unwrap();
+            assert!(!res);
+
+            let res = ctx.eval::<(), _>("f()");
+            assert!(matches!(res, Err(Error::Exception)));
+            let exception = Exception::from_js(&ctx, ctx.catch()).unwrap();
+            assert_eq!(exception.message().as_deref(), Some("outer"));
+            let cause = Exception::from_js(&ctx, exception.cause().unwrap()).unwrap();
+            assert_eq!(cause.message().as_deref(), Some("inner"));
+            assert!(cause.cause().is_none());
+        })
+    }
+
+    #[test]
+    fn nested_stack() {
+        test_with(|ctx| {
+            let res = ctx
+                .eval::<(), _>(
+                    r#"
+                    function inner() { throw new Error("nested"); }
+                    function outer() { inner(); }
+                    outer();
+                "#,
+                )
+                .catch(&ctx);
+            let Err(CaughtError::Exception(exception)) = res else {
+                panic!("expected an exception");
+            };
+            let stack = exception.stack().unwrap();
+            assert!(stack.contains("at inner"), "{stack}");
+            assert!(stack.contains("at outer"), "{stack}");
+            assert!(exception.to_string().contains("at outer"));
+        })
+    }
+
+    #[test]
+    fn backtrace_barrier() {
+        test_with(|ctx| {
+            let func = Function::new(ctx.clone(), |ctx: Ctx| {
+                let options = context::EvalOptions {
+                    backtrace_barrier: true,
+                    ..Default::default()
+                };
+                ctx.eval_with_options::<StdString, _>("new Error().stack", options)
+            })
+            .unwrap();
+            ctx.globals().set("f", func).unwrap();
+
+            let stack: StdString = ctx
+                .eval("function outer() { return f(); } outer()")
+                .unwrap();
+            assert!(!stack.contains("at outer"), "{stack}");
+        })
+    }
+
+    #[test]
+    fn throw_typed() {
+        test_with(|ctx| {
+            let func = Function::new(ctx.clone(), |ctx: Ctx, kind: StdString| -> Result<()> {
+                Err(match kind.as_str() {
+                    "type" => Exception::throw_type(&ctx, "type"),
+                    "range" => Exception::throw_range(&ctx, "range"),
+                    "syntax" => Exception::throw_syntax(&ctx, "syntax"),
+                    _ => Exception::throw_reference(&ctx, "reference"),
+                })
+            })
+            .unwrap();
+            ctx.globals().set("f", func).unwrap();
+
+            let res: bool = ctx
+                .eval(
+                    r#"
+                    [
+                        ["type", TypeError],
+                        ["range", RangeError],
+                        ["syntax", SyntaxError],
+                        ["reference", ReferenceError],
+                    ].every(([kind, ctor]) => {
+                        try { f(kind) } catch (e) { return e instanceof ctor && e.message == kind }
+                    })
+                "#,
+                )
+                .unwrap();
+            assert!(res);
+        })
+    }
+
+    #[derive(Debug)]
+    struct InvalidInput;
+
+    impl std::fmt::Display for InvalidInput {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            "invalid input".fmt(f)
+        }
+    }
+
+    impl IntoException for InvalidInput {
+        fn exception_kind(&self) -> ExceptionKind {
+            ExceptionKind::Type
+        }
+    }
+
+    #[test]
+    fn into_exception() {
+        test_with(|ctx| {
+            let func = Function::new(ctx.clone(), || -> Result<()> {
+                Err(InvalidInput)?;
+                Ok(())
+            })
+            .unwrap();
+            ctx.globals().set("f", func).unwrap();
+
+            let res: StdString = ctx
+                .eval("try { f() } catch (e) { e instanceof TypeError && e.message }")
+                .unwrap();
+            assert_eq!(res, "invalid input");
+
+            let res: bool = ctx
+                .eval(
+                    r#"
+                    const Intrinsic = TypeError;
+                    globalThis.TypeError = function () { throw new Error("replaced builtin used"); };
+                    try { f() } catch (e) { e instanceof Intrinsic && e.message === "invalid input" }
+                    "#,
+                )
+                .unwrap();
+            assert!(res);
+        })
+    }
+}
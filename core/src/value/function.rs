@@ -4,8 +4,11 @@ use crate::{
     atom::PredefinedAtom,
     class::{Class, JsClass},
     function::ffi::RustFunc,
-    qjs, Ctx, Error, FromJs, IntoJs, Object, Result, Value,
+    qjs,
+    runtime::raw::RawRuntime,
+    Ctx, Error, FromJs, IntoJs, Object, Result, Value,
 };
+use std::time::{Duration, Instant};
 
 mod args;
 mod ffi;
@@ -57,6 +60,17 @@ impl<'js> Function<'js> {
         Function(cls.into_inner()).with_length(F::param_requirements().min())
     }
 
+    /// Create a new function from a rust closure which implements [`FnMut`].
+    ///
+    /// The closure is wrapped in a [`MutFn`], a call to the function while it is already running
+    /// throws a javascript error instead of calling the closure again.
+    pub fn new_mut<P, F>(ctx: Ctx<'js>, f: F) -> Result<Self>
+    where
+        MutFn<F>: IntoJsFunc<'js, P> + 'js,
+    {
+        Self::new(ctx, MutFn::new(f))
+    }
+
     /// Call the function with given arguments.
     pub fn call<A, R>(&self, args: A) -> Result<R>
     where
@@ -70,6 +84,73 @@ impl<'js> Function<'js> {
         self.call_arg(accum_args)
     }
 
+    /// Call the function with given arguments, interrupting it if it runs longer than the
+    /// timeout.
+    ///
+    /// The timeout is enforced like a deadline set with
+    /// [`Runtime::set_deadline`](crate::Runtime::set_deadline) and returns
+    /// [`Error::Interrupted`] once exceeded, the interrupt can't be caught by the called code.
+    /// A deadline which is already active, from the runtime or from an outer call, stays in
+    /// effect if it is earlier and is restored once the call returns.
+    ///
+    /// ```
+    /// # use rquickjs::{Runtime, Context, Function, Error};
+    /// # use std::time::Duration;
+    /// # let rt = Runtime::new().unwrap();
+    /// # let ctx = Context::full(&rt).unwrap();
+    /// # ctx.with(|ctx| {
+    /// let spin: Function = ctx.eval("() => { while (true) {} }").unwrap();
+    /// let res = spin.call_with_timeout::<_, ()>((), Duration::from_millis(10));
+    /// assert!(matches!(res, Err(Error::Interrupted)));
+    /// # })
+    /// ```
+    pub fn call_with_timeout<A, R>(&self, args: A, timeout: Duration) -> Result<R>
+    where
+        A: IntoArgs<'js>,
+        R: FromJs<'js>,
+    {
+        struct DeadlineGuard<'js> {
+            ctx: Ctx<'js>,
+            deadline: Option<Instant>,
+            exceeded: bool,
+        }
+
+        impl Drop for DeadlineGuard<'_> {
+            fn drop(&mut self) {
+                unsafe {
+                    let opaque = &mut *self.ctx.get_opaque();
+                    // Keep the exceeded flag if the deadline which was hit is the restored one,
+                    // so the interrupt also reaches outer calls.
+                    let outer_hit = opaque.deadline_exceeded && opaque.deadline == self.deadline;
+                    opaque.deadline = self.deadline;
+                    opaque.deadline_exceeded = self.exceeded || outer_hit;
+                    RawRuntime::update_interrupt_handler_raw(qjs::JS_GetRuntime(self.ctx.as_ptr()));
+                }
+            }
+        }
+
+        let ctx = self.ctx();
+        let Some(deadline) = Instant::now().checked_add(timeout) else {
+            return self.call(args);
+        };
+        let _guard = unsafe {
+            let opaque = &mut *ctx.get_opaque();
+            let guard = DeadlineGuard {
+                ctx: ctx.clone(),
+                deadline: opaque.deadline,
+                exceeded: opaque.deadline_exceeded,
+            };
+            opaque.deadline = Some(
+                opaque
+                    .deadline
+                    .map_or(deadline, |outer| outer.min(deadline)),
+            );
+            RawRuntime::update_interrupt_handler_raw(qjs::JS_GetRuntime(ctx.as_ptr()));
+            guard
+        };
+        self.call(args)
+    }
+
     /// Call the function with given arguments in the form of an [`Args`] object.
     pub fn call_arg<R>(&self, args: Args<'js>) -> Result<R>
     where
@@ -78,6 +159,80 @@ impl<'js> Function<'js> {
         args.apply(self)
     }
 
+    /// Call the function with a `this` value and a list of arguments only known at runtime.
+    ///
+    /// Equivalent to `Function.prototype.apply` in javascript, the arguments array is allocated
+    /// once with the length of `args` and passed directly to the function.
+    pub fn apply<T, R>(&self, this: T, args: Vec<Value<'js>>) -> Result<R>
+    where
+        T: IntoJs<'js>,
+        R: FromJs<'js>,
+    {
+        let mut accum_args = Args::new(self.ctx().clone(), args.len());
+        accum_args.this(this)?;
+        accum_args.push_args(args)?;
+        self.call_arg(accum_args)
+    }
+
+    /// Create a bound function with a fixed `this` value and leading arguments.
+    ///
+    /// Equivalent to `Function.prototype.bind` in javascript. Calling the bound function calls
+    /// this function with the bound `this` and the bound arguments followed by the arguments
+    /// passed to the bound function. The `length` of the bound function is the length of this
+    /// function minus the number of bound arguments. The intrinsic implementation is used, so
+    /// scripts which replace `Function.prototype.bind` don't affect it.
+    pub fn bind(&self, this: Value<'js>, args: Vec<Value<'js>>) -> Result<Function<'js>> {
+        let ctx = self.ctx();
+        let mut argv: Vec<qjs::JSValue> = args.iter().map(|arg| arg.as_js_value()).collect();
+        unsafe {
+            let func = qjs::JS_BindFunction(
+                ctx.as_ptr(),
+                self.as_js_value(),
+                this.as_js_value(),
+                argv.len() as _,
+                argv.as_mut_ptr(),
+            );
+            Ok(Function::from_js_value(
+                ctx.clone(),
+                ctx.handle_exception(func)?,
+            ))
+        }
+    }
+
+    /// Call the function as a constructor with given arguments.
+    ///
+    /// Equivalent to `new func(...args)` in javascript, throws a `TypeError` if the function is
+    /// not a constructor.
+    pub fn construct<A, R>(&self, args: A) -> Result<R>
+    where
+        A: IntoArgs<'js>,
+        R: FromJs<'js>,
+    {
+        let ctx = self.ctx();
+        let num = args.num_args();
+        let mut accum_args = Args::new(ctx.clone(), num);
+        args.into_args(&mut accum_args)?;
+        accum_args.construct(&Constructor(self.clone()))
+    }
+
+    /// Call the function as a constructor with a different `new.target`.
+    ///
+    /// Equivalent to `Reflect.construct(func, args, new_target)` in javascript. The created
+    /// object gets its prototype from `new_target`, which is how a base class constructor is
+    /// called when constructing a subclass.
+    pub fn construct_with_new_target<A, R>(&self, new_target: &Function<'js>, args: A) -> Result<R>
+    where
+        A: IntoArgs<'js>,
+        R: FromJs<'js>,
+    {
+        let ctx = self.ctx();
+        let num = args.num_args();
+        let mut accum_args = Args::new(ctx.clone(), num);
+        accum_args.this(new_target.clone())?;
+        args.into_args(&mut accum_args)?;
+        accum_args.construct(&Constructor(self.clone()))
+    }
+
     /// Defer call the function with given arguments.
     ///
     /// Calling a function with defer is equivalent to calling a javascript function with
@@ -414,6 +569,105 @@ mod test {
         })
     }
 
+    #[test]
+    fn apply_js_fn_with_arg_vec() {
+        test_with(|ctx| {
+            let math: Object = ctx.globals().get("Math").unwrap();
+            let max: Function = math.get("max").unwrap();
+
+            let args = (0..100)
+                .map(|x| ((x * 37) % 101).into_js(&ctx))
+                .collect::<Result<Vec<_>>>()
+                .unwrap();
+            let res: i32 = max.apply(math.clone(), args).unwrap();
+            assert_eq!(res, 100);
+
+            let res: f64 = max.apply(math, Vec::new()).unwrap();
+            assert_eq!(res, f64::NEG_INFINITY);
+
+            let f: Function = ctx
+                .eval("function f(a, b) { return this.val + a + b; } f")
+                .unwrap();
+            let obj = Object::new(ctx.clone()).unwrap();
+            obj.set("val", 1).unwrap();
+            let args = vec![Value::new_int(ctx.clone(), 2), Value::new_int(ctx, 3)];
+            let res: i32 = f.apply(obj, args).unwrap();
+            assert_eq!(res, 6);
+        })
+    }
+
+    #[test]
+    fn call_js_fn_with_args_builder() {
+        test_with(|ctx| {
+            let f: Function = ctx
+                .eval("(...x) => x.reduce((acc, x, idx) => acc + x * (idx + 1), 0)")
+                .unwrap();
+
+            let mut args = function::Args::new(ctx.clone(), 2);
+            for x in 1..=20 {
+                args = args.push(x).unwrap();
+            }
+            let res: i32 = args.call(&f).unwrap();
+            assert_eq!(res, (1..=20).map(|x| x * x).sum::<i32>());
+
+            let date: function::Constructor = ctx.globals().get("Date").unwrap();
+            let res: Object = function::Args::new_unsized(ctx.clone())
+                .push(2000)
+                .unwrap()
+                .construct(&date)
+                .unwrap();
+            let get_time: Function = res.get("getTime").unwrap();
+            assert_eq!(get_time.call::<_, f64>((This(res),)).unwrap(), 2000.0);
+        })
+    }
+
+    #[test]
+    fn construct_date() {
+        test_with(|ctx| {
+            let date: Function = ctx.globals().get("Date").unwrap();
+            let res: Object = date.construct((1000,)).unwrap();
+            let get_time: Function = res.get("getTime").unwrap();
+            let time: f64 = get_time.call((This(res),)).unwrap();
+            assert_eq!(time, 1000.0);
+        })
+    }
+
+    #[test]
+    fn construct_js_class() {
+        test_with(|ctx| {
+            ctx.eval::<(), _>(
+                r#"
+                class Point {
+                    constructor(x, y) {
+                        this.sum = x + y;
+                        this.kind = new.target.name;
+                    }
+                }
+                class Point3 extends Point {}
+                globalThis.Point = Point;
+                globalThis.Point3 = Point3;
+                "#,
+            )
+            .unwrap();
+            let base: Function = ctx.globals().get("Point").unwrap();
+            let derived: Function = ctx.globals().get("Point3").unwrap();
+
+            let point: Object = base.construct((1, 2)).unwrap();
+            assert!(point.is_instance_of(&base).unwrap());
+            assert_eq!(point.get::<_, i32>("sum").unwrap(), 3);
+            assert_eq!(point.get::<_, StdString>("kind").unwrap(), "Point");
+
+            let point: Object = base.construct_with_new_target(&derived, (3, 4)).unwrap();
+            assert!(point.is_instance_of(&derived).unwrap());
+            assert_eq!(point.get::<_, i32>("sum").unwrap(), 7);
+            assert_eq!(point.get::<_, StdString>("kind").unwrap(), "Point3");
+
+            let f: Function = ctx.eval("() => {}").unwrap();
+            assert!(matches!(f.construct::<_, Value>(()), Err(Error::Exception)));
+            ctx.catch();
+        })
+    }
+
     #[test]
     fn call_js_fn_with_this_and_1_arg_and_return() {
         test_with(|ctx| {
@@ -473,6 +727,71 @@ mod test {
         })
     }
 
+    #[test]
+    fn name_and_length() {
+        test_with(|ctx| {
+            let f = Function::new(ctx.clone(), |ctx: Ctx| {
+                ctx.eval::<StdString, _>("new Error().stack")
+            })
+            .unwrap()
+            .with_name("bar")
+            .unwrap()
+            .with_name("foo")
+            .unwrap()
+            .with_length(2)
+            .unwrap();
+            ctx.globals().set("f", f).unwrap();
+
+            let res: bool = ctx.eval("f.name === 'foo' && f.length === 2").unwrap();
+            assert!(res);
+            let stack: StdString = ctx.eval("f()").unwrap();
+            assert!(stack.contains("at foo (native)"), "{stack}");
+            let source: StdString = ctx.eval("f.toString()").unwrap();
+            assert!(source.contains("function foo"), "{source}");
+        })
+    }
+
+    #[test]
+    fn rest_args() {
+        fn sum(first: i32, rest: Rest<i32>) -> i32 {
+            first + rest.0.into_iter().sum::<i32>()
+        }
+
+        test_with(|ctx| {
+            ctx.globals()
+                .set("sum", Function::new(ctx.clone(), sum).unwrap())
+                .unwrap();
+            assert_eq!(ctx.eval::<i32, _>("sum(1)").unwrap(), 1);
+            assert_eq!(ctx.eval::<i32, _>("sum(1, 2)").unwrap(), 3);
+            assert_eq!(ctx.eval::<i32, _>("sum(1, 2, 3, 4)").unwrap(), 10);
+
+            let res: StdString = ctx
+                .eval("try { sum(1, 2, {}) } catch (e) { e.message }")
+                .unwrap();
+            assert!(res.contains("argument 2"), "{res}");
+        })
+    }
+
+    #[test]
+    fn opt_args() {
+        fn f(a: i32, b: Opt<StdString>) -> StdString {
+            match b.0 {
+                Some(b) => format!("{a} {b}"),
+                None => format!("{a}"),
+            }
+        }
+
+        test_with(|ctx| {
+            ctx.globals()
+                .set("f", Function::new(ctx.clone(), f).unwrap())
+                .unwrap();
+            assert_eq!(ctx.eval::<StdString, _>("f(1, 'b')").unwrap(), "1 b");
+            assert_eq!(ctx.eval::<StdString, _>("f(1)").unwrap(), "1");
+            assert_eq!(ctx.eval::<StdString, _>("f(1, undefined)").unwrap(), "1");
+            assert_eq!(ctx.eval::<i32, _>("f.length").unwrap(), 1);
+        })
+    }
+
     #[test]
     fn const_callback() {
         use std::sync::{Arc, Mutex};
@@ -528,6 +847,29 @@ mod test {
         })
     }
 
+    #[test]
+    fn new_mut_callback() {
+        test_with(|ctx| {
+            let mut count = 0;
+            let f = Function::new_mut(ctx.clone(), move |ctx: Ctx| {
+                count += 1;
+                if count == 2 {
+                    let res: StdString = ctx
+                        .eval("try { counter(); 'called' } catch (e) { e.message }")
+                        .unwrap();
+                    assert!(res.starts_with("Error borrowing function"), "{res}");
+                }
+                count
+            })
+            .unwrap();
+            ctx.globals().set("counter", f).unwrap();
+
+            assert_eq!(ctx.eval::<i32, _>("counter()").unwrap(), 1);
+            assert_eq!(ctx.eval::<i32, _>("counter()").unwrap(), 2);
+            assert_eq!(ctx.eval::<i32, _>("counter()").unwrap(), 3);
+        })
+    }
+
     #[test]
     #[should_panic(
         expected = "Error borrowing function: can't borrow a value as it is already borrowed"
@@ -550,7 +892,84 @@ mod test {
             )
             .unwrap();
             ctx.globals().set("foo", f.clone()).unwrap();
-            f.call::<_, ()>(()).unwrap();
+            // The panic in the callback is thrown as an error.
+            f.call::<_, ()>(()).catch(&ctx).unwrap();
+        })
+    }
+
+    #[test]
+    fn call_with_timeout() {
+        use std::time::{Duration, Instant};
+
+        test_with(|ctx| {
+            let spin: Function = ctx.eval("() => { while (true) {} }").unwrap();
+            let start = Instant::now();
+            let res = spin.call_with_timeout::<_, ()>((), Duration::from_millis(50));
+            assert!(matches!(res, Err(Error::Interrupted)));
+            assert!(start.elapsed() < Duration::from_secs(2));
+
+            // The context is still usable and the deadline is gone after the call.
+            let _ = ctx.catch();
+            let res: i32 = ctx.eval("let i = 0; while(i < 100000){ i++ }; i").unwrap();
+            assert_eq!(res, 100000);
+
+            // A tighter outer timeout also applies to nested calls with a longer timeout.
+            let nested = Function::new(ctx.clone(), |ctx: Ctx| -> Result<()> {
+                let spin: Function = ctx.globals().get("spin")?;
+                spin.call_with_timeout((), Duration::from_secs(60))
+            })
+            .unwrap();
+            ctx.globals().set("spin", spin).unwrap();
+            let start = Instant::now();
+            let res = nested.call_with_timeout::<_, ()>((), Duration::from_millis(50));
+            assert!(matches!(res, Err(Error::Interrupted)));
+            assert!(start.elapsed() < Duration::from_secs(2));
+
+            let _ = ctx.catch();
+            assert_eq!(ctx.eval::<i32, _>("i").unwrap(), 100000);
+        })
+    }
+
+    #[test]
+    fn panic_in_callback_is_thrown() {
+        test_with(|ctx| {
+            let f = Function::new(ctx.clone(), |value: i32| -> i32 {
+                if value > 1 {
+                    panic!("value {} too large", value);
+                }
+                value
+            })
+            .unwrap();
+            ctx.globals().set("check", f).unwrap();
+
+            let res: StdString = ctx
+                .eval(
+                    r#"
+                    try {
+                        check(2);
+                        "not thrown"
+                    } catch (e) {
+                        `${e instanceof Error}: ${e.message}`
+                    }
+                "#,
+                )
+                .unwrap();
+            assert_eq!(res, "true: value 2 too large");
+            // The runtime is still usable after the panic.
+            assert_eq!(ctx.eval::<i32, _>("check(1)").unwrap(), 1);
+        })
+    }
+
+    #[test]
+    #[should_panic(expected = "carried panic")]
+    fn panic_in_callback_carried() {
+        let rt = Runtime::new().unwrap();
+        rt.set_panic_to_exception(false);
+        let ctx = Context::full(&rt).unwrap();
+        ctx.with(|ctx| {
+            let f = Function::new(ctx.clone(), || -> i32 { panic!("carried panic") }).unwrap();
+            ctx.globals().set("fail", f).unwrap();
+            let _ = ctx.eval::<(), _>("fail()");
         })
     }
 
@@ -693,6 +1112,28 @@ mod test {
         assert_eq!(res, 11.0);
     }
 
+    #[test]
+    fn rust_fn_as_method() {
+        test_with(|ctx| {
+            let func = Function::new(ctx.clone(), |this: This<Object>| {
+                this.get::<_, StdString>("name")
+            })
+            .unwrap();
+            let obj = Object::new(ctx.clone()).unwrap();
+            obj.set("name", "foo").unwrap();
+            obj.set("getName", func).unwrap();
+            ctx.globals().set("obj", obj).unwrap();
+
+            let res: StdString = ctx.eval("obj.getName()").unwrap();
+            assert_eq!(res, "foo");
+
+            let res: StdString = ctx
+                .eval("const getName = obj.getName; try { getName() } catch (e) { e.name }")
+                .unwrap();
+            assert_eq!(res, "TypeError");
+        })
+    }
+
     #[test]
     fn apply_rust_fn_with_this_and_args() {
         let res: f32 = test_with(|ctx| {
@@ -714,6 +1155,45 @@ mod test {
         assert_eq!(res, 11.0);
     }
 
+    #[test]
+    fn bind_leading_args() {
+        test_with(|ctx| {
+            let add = Function::new(ctx.clone(), |this: This<Object>, x: i32, y: i32| {
+                let base: i32 = this.get("base").unwrap();
+                base + x + y
+            })
+            .unwrap()
+            .with_length(2)
+            .unwrap();
+            let this: Value = ctx.eval("({ base: 100 })").unwrap();
+            let one = 1.into_js(&ctx).unwrap();
+            let add_one = add.bind(this, vec![one]).unwrap();
+            assert_eq!(add_one.get::<_, usize>("length").unwrap(), 1);
+            assert_eq!(add_one.call::<_, i32>((2,)).unwrap(), 103);
+
+            ctx.globals().set("addOne", add_one).unwrap();
+            let res: i32 = ctx.eval("addOne.call({ base: 0 }, 3)").unwrap();
+            assert_eq!(res, 104);
+        })
+    }
+
+    #[test]
+    fn bind_with_replaced_bind() {
+        test_with(|ctx| {
+            let func: Function = ctx
+                .eval(
+                    r#"
+                    Function.prototype.bind = () => { throw new Error("replaced builtin used"); };
+                    (function (x) { return this.base + x; })
+                    "#,
+                )
+                .unwrap();
+            let this: Value = ctx.eval("({ base: 10 })").unwrap();
+            let bound = func.bind(this, Vec::new()).unwrap();
+            assert_eq!(bound.call::<_, i32>((1,)).unwrap(), 11);
+        })
+    }
+
     #[test]
     fn bind_rust_fn_with_this_and_call_with_args() {
         let res: f32 = test_with(|ctx| {
@@ -64,11 +64,16 @@ impl<'js> Args<'js> {
                 ref mut slice,
                 ref mut offset,
             } => {
-                if *offset >= 8 {
-                    panic!("pushed more arguments than num_args returned");
+                if (*offset as usize) < ARGS_ON_STACK {
+                    slice[*offset as usize] = v.into_js_value();
+                    *offset += 1;
+                } else {
+                    // More arguments than reserved, move them to the heap.
+                    let mut heap = Vec::with_capacity(ARGS_ON_STACK * 2);
+                    heap.extend_from_slice(slice);
+                    heap.push(v.into_js_value());
+                    self.args = ArgsSlice::Heap(heap);
                 }
-                slice[*offset as usize] = v.into_js_value();
-                *offset += 1;
             }
             ArgsSlice::Heap(ref mut h) => h.push(v.into_js_value()),
         }
@@ -76,6 +81,29 @@ impl<'js> Args<'js> {
         Ok(())
     }
 
+    /// Add an argument to the list and return the arguments, for building arguments in a chain.
+    ///
+    /// ```
+    /// # use rquickjs::{Runtime, Context, Function, function::Args};
+    /// # let rt = Runtime::new().unwrap();
+    /// # let ctx = Context::full(&rt).unwrap();
+    /// # ctx.with(|ctx| {
+    /// let func: Function = ctx.eval("(a, b, c) => a + b + (c ?? 0)").unwrap();
+    /// let verbose = false;
+    /// let mut args = Args::new_unsized(ctx.clone()).push(1)?.push(2)?;
+    /// if verbose {
+    ///     args = args.push(3)?;
+    /// }
+    /// let res: i32 = args.call(&func)?;
+    /// assert_eq!(res, 3);
+    /// # rquickjs::Result::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub fn push<T: IntoJs<'js>>(mut self, arg: T) -> Result<Self> {
+        self.push_arg(arg)?;
+        Ok(self)
+    }
+
     /// Add multiple arguments to the list.
     pub fn push_args<T, I>(&mut self, iter: I) -> Result<()>
     where
@@ -142,6 +170,14 @@ impl<'js> Args<'js> {
         R::from_js(&self.ctx, val)
     }
 
+    /// Call a function with the current set of arguments, an alias of [`Args::apply`].
+    pub fn call<R>(self, func: &Function<'js>) -> Result<R>
+    where
+        R: FromJs<'js>,
+    {
+        self.apply(func)
+    }
+
     pub fn defer(mut self, func: Function<'js>) -> Result<()> {
         let this = self.take_this();
         self.push_arg(this)?;
@@ -161,6 +197,9 @@ impl<'js> Args<'js> {
         Ok(())
     }
 
+    /// Call a function as a constructor with the current set of arguments.
+    ///
+    /// A `this` value set on the arguments is used as `new.target`.
     pub fn construct<R>(self, constructor: &Constructor<'js>) -> Result<R>
     where
         R: FromJs<'js>,
@@ -12,6 +12,27 @@ use super::Constructor;
 
 ///. The C side callback
 pub unsafe extern "C" fn js_callback_class<F: StaticJsFunction>(
+    ctx: *mut qjs::JSContext,
+    function: qjs::JSValue,
+    this: qjs::JSValue,
+    argc: qjs::c_int,
+    argv: *mut qjs::JSValue,
+    flags: qjs::c_int,
+) -> qjs::JSValue {
+    // Quickjs only pushes a stack frame for its own native functions, without one the function
+    // would be missing from the stack trace of errors created while it runs.
+    qjs::JS_CallWithStackFrame(
+        ctx,
+        function,
+        this,
+        argc,
+        argv,
+        flags,
+        Some(js_callback_class_frame::<F>),
+    )
+}
+
+unsafe extern "C" fn js_callback_class_frame<F: StaticJsFunction>(
     ctx: *mut qjs::JSContext,
     function: qjs::JSValue,
     this: qjs::JSValue,
@@ -1,6 +1,6 @@
 use crate::{
     function::{Exhaustive, Flat, FuncArg, Opt, Rest, This},
-    qjs, Ctx, FromJs, Result, Value,
+    qjs, Ctx, Error, FromJs, Result, Value,
 };
 use std::slice;
 
@@ -267,11 +267,15 @@ impl<'js, T: FromJs<'js>> FromParam<'js> for Opt<T> {
     }
 
     fn from_param<'a>(params: &mut ParamsAccessor<'a, 'js>) -> Result<Self> {
-        if !params.is_empty() {
-            let ctx = params.ctx().clone();
-            Ok(Opt(Some(T::from_js(&ctx, params.arg())?)))
-        } else {
+        if params.is_empty() {
+            return Ok(Opt(None));
+        }
+        let ctx = params.ctx().clone();
+        let value = params.arg();
+        if value.is_undefined() {
             Ok(Opt(None))
+        } else {
+            T::from_js(&ctx, value).map(|x| Opt(Some(x)))
         }
     }
 }
@@ -304,8 +308,25 @@ impl<'js, T: FromJs<'js>> FromParam<'js> for Rest<T> {
     fn from_param<'a>(params: &mut ParamsAccessor<'a, 'js>) -> Result<Self> {
         let mut res = Vec::with_capacity(params.len());
         for _ in 0..params.len() {
+            let index = params.offset;
             let p = params.arg();
-            res.push(T::from_js(params.ctx(), p)?);
+            let value = T::from_js(params.ctx(), p).map_err(|error| match error {
+                Error::FromJs { from, to, message } => {
+                    let message = match message {
+                        Some(message) if !message.is_empty() => {
+                            format!("argument {index}: {message}")
+                        }
+                        _ => format!("argument {index}"),
+                    };
+                    Error::FromJs {
+                        from,
+                        to,
+                        message: Some(message),
+                    }
+                }
+                error => error,
+            })?;
+            res.push(value);
         }
         Ok(Rest(res))
     }
@@ -46,9 +46,17 @@ pub struct This<T>(pub T);
 pub struct FuncArg<T>(pub T);
 
 /// Helper type for optional paramaters.
+///
+/// The value is `None` when the argument is left out or explicitly `undefined`, any other value,
+/// including `null`, is converted with [`FromJs`](crate::FromJs) for `T`. This differs from using an
+/// [`Option<T>`] parameter, which still counts as a required argument and converts both `null`
+/// and `undefined` into `None`.
 pub struct Opt<T>(pub Option<T>);
 
 /// Helper type for rest and spread arguments.
+///
+/// As a parameter it collects all remaining arguments, a conversion error names the index of the
+/// argument which failed to convert.
 pub struct Rest<T>(pub Vec<T>);
 
 /// Helper type for converting an option into null instead of undefined.
@@ -0,0 +1,499 @@
+use std::collections::HashSet;
+
+use crate::{
+    convert::Coerced, object::Filter, qjs, value::builtin::BuiltinClass, Atom, Object, Result,
+    StdString, Type, Value,
+};
+
+/// The number of entries of arrays, maps and sets which are shown, like the default
+/// `maxArrayLength` of node.
+const MAX_ITEMS: usize = 100;
+
+impl<'js> Value<'js> {
+    /// Render the value similar to how a javascript console displays it.
+    ///
+    /// Strings are quoted, objects and arrays are printed with their contents, nested objects
+    /// and arrays deeper than `depth` are abbreviated, like `[Object]` and `[Array]`, and
+    /// references back to an object which is currently printed are shown as `[Circular]`. With
+    /// a `depth` of `0` only the contents of the value itself are printed.
+    ///
+    /// Rendering doesn't call `toString`, `valueOf` or getters of the value, accessor properties
+    /// are shown as `[Getter]`, `[Setter]` or `[Getter/Setter]`. Arrays, maps, sets and dates are
+    /// recognized by their builtin class and their contents are read from their internal state,
+    /// so scripts replacing their methods don't affect the output. Proxies can't be detected and
+    /// their traps are called.
+    ///
+    /// Like in node only the first 100 entries of arrays, maps and sets are shown, followed by
+    /// the number of remaining items, for example `[ 0, 1, ... 98 more items ]`.
+    pub fn inspect(&self, depth: usize) -> Result<StdString> {
+        let mut out = StdString::new();
+        Inspector {
+            max_depth: depth,
+            parents: HashSet::new(),
+        }
+        .value(&mut out, self, 0)?;
+        Ok(out)
+    }
+}
+
+struct Inspector {
+    max_depth: usize,
+    /// Objects which are currently being printed, used to detect cycles.
+    parents: HashSet<*mut qjs::c_void>,
+}
+
+/// An own property of an object.
+enum Property<'js> {
+    Data(Value<'js>),
+    Accessor { get: bool, set: bool },
+}
+
+/// Returns the own property of an object without invoking getters.
+fn own_property<'js>(object: &Object<'js>, atom: &Atom<'js>) -> Result<Option<Property<'js>>> {
+    Ok(object.own_descriptor(atom)?.map(|desc| {
+        if desc.is_accessor() {
+            Property::Accessor {
+                get: !desc.getter.is_undefined(),
+                set: !desc.setter.is_undefined(),
+            }
+        } else {
+            Property::Data(desc.value)
+        }
+    }))
+}
+
+/// Look up a data property on the object or its prototypes without invoking getters.
+fn data_property<'js>(object: &Object<'js>, name: &str) -> Result<Option<Value<'js>>> {
+    let atom = Atom::from_str(object.ctx().clone(), name)?;
+    let mut object = object.clone();
+    loop {
+        match own_property(&object, &atom)? {
+            Some(Property::Data(value)) => return Ok(Some(value)),
+            Some(Property::Accessor { .. }) => return Ok(None),
+            None => match object.get_prototype()? {
+                Some(proto) => object = proto,
+                None => return Ok(None),
+            },
+        }
+    }
+}
+
+fn data_string<'js>(object: &Object<'js>, name: &str) -> Result<Option<StdString>> {
+    match data_property(object, name)? {
+        Some(value) if value.is_string() => Ok(Some(value.get()?)),
+        _ => Ok(None),
+    }
+}
+
+impl Inspector {
+    fn value<'js>(&mut self, out: &mut StdString, value: &Value<'js>, depth: usize) -> Result<()> {
+        match value.type_of() {
+            Type::String => {
+                let string = value.as_string().unwrap().to_string()?;
+                quote(out, &string);
+            }
+            Type::Symbol => {
+                let symbol = value.as_symbol().unwrap();
+                let description: Value = symbol.description()?.into_value();
+                out.push_str("Symbol(");
+                if let Some(description) = description.as_string() {
+                    out.push_str(&description.to_string()?);
+                }
+                out.push(')');
+            }
+            Type::BigInt => {
+                out.push_str(&value.get::<Coerced<StdString>>()?.0);
+                out.push('n');
+            }
+            Type::Function | Type::Constructor => {
+                let name = data_string(value.as_object().unwrap(), "name")?;
+                match name.filter(|name| !name.is_empty()) {
+                    Some(name) => {
+                        out.push_str("[Function: ");
+                        out.push_str(&name);
+                        out.push(']');
+                    }
+                    None => out.push_str("[Function (anonymous)]"),
+                }
+            }
+            Type::Array | Type::Object | Type::Exception => {
+                let object = value.as_object().unwrap();
+                let key = unsafe { value.get_ptr() };
+                if !self.parents.insert(key) {
+                    out.push_str("[Circular]");
+                    return Ok(());
+                }
+                let res = self.object(out, object, depth);
+                self.parents.remove(&key);
+                res?;
+            }
+            _ => out.push_str(&value.get::<Coerced<StdString>>()?.0),
+        }
+        Ok(())
+    }
+
+    fn object<'js>(
+        &mut self,
+        out: &mut StdString,
+        object: &Object<'js>,
+        depth: usize,
+    ) -> Result<()> {
+        if object.is_error() {
+            let name = data_string(object, "name")?;
+            out.push_str(name.as_deref().unwrap_or("Error"));
+            if let Some(message) = data_string(object, "message")?.filter(|msg| !msg.is_empty()) {
+                out.push_str(": ");
+                out.push_str(&message);
+            }
+            return Ok(());
+        }
+
+        let class = object.builtin_class();
+
+        if class == BuiltinClass::Date {
+            match object.date_value()? {
+                millis if millis.is_finite() => iso_date(out, millis as i64),
+                _ => out.push_str("Invalid Date"),
+            }
+            return Ok(());
+        }
+
+        let nested = depth > self.max_depth;
+
+        if class == BuiltinClass::Array {
+            if nested {
+                out.push_str("[Array]");
+                return Ok(());
+            }
+            return self.array(out, object, depth);
+        }
+
+        if matches!(class, BuiltinClass::Map | BuiltinClass::Set) {
+            let name = if class == BuiltinClass::Map {
+                "Map"
+            } else {
+                "Set"
+            };
+            if nested {
+                out.push_str(&format!("[{}]", name));
+                return Ok(());
+            }
+            let entries = object.map_entries()?;
+            out.push_str(&format!("{}({}) {{", name, entries.len()));
+            for (idx, (key, value)) in entries.iter().take(MAX_ITEMS).enumerate() {
+                out.push_str(if idx == 0 { " " } else { ", " });
+                self.value(out, key, depth + 1)?;
+                if class == BuiltinClass::Map {
+                    out.push_str(" => ");
+                    self.value(out, value, depth + 1)?;
+                }
+            }
+            if entries.len() > MAX_ITEMS {
+                out.push_str(", ");
+                more_items(out, entries.len() - MAX_ITEMS);
+            }
+            out.push_str(if entries.is_empty() { "}" } else { " }" });
+            return Ok(());
+        }
+
+        let class = if object.get_prototype()?.is_none() {
+            Some("[Object: null prototype]".to_string())
+        } else {
+            match data_property(object, "constructor")? {
+                Some(ctor) => match ctor.as_object() {
+                    Some(ctor) => data_string(ctor, "name")?,
+                    None => None,
+                },
+                None => None,
+            }
+            .filter(|name| !name.is_empty() && name != "Object")
+        };
+        if nested {
+            out.push('[');
+            out.push_str(class.as_deref().unwrap_or("Object"));
+            out.push(']');
+            return Ok(());
+        }
+        if let Some(class) = class {
+            out.push_str(&class);
+            out.push(' ');
+        }
+        out.push('{');
+        let mut empty = true;
+        for (idx, atom) in object.keys::<Atom>().enumerate() {
+            let atom = atom?;
+            let key = atom.to_string()?;
+            out.push_str(if idx == 0 { " " } else { ", " });
+            if is_identifier(&key) {
+                out.push_str(&key);
+            } else {
+                quote(out, &key);
+            }
+            out.push_str(": ");
+            self.property(out, own_property(object, &atom)?, depth)?;
+            empty = false;
+        }
+        out.push_str(if empty { "}" } else { " }" });
+        Ok(())
+    }
+
+    fn array<'js>(&mut self, out: &mut StdString, array: &Object<'js>, depth: usize) -> Result<()> {
+        let len: u32 = array.get("length")?;
+        out.push('[');
+        // The indices of sparse arrays are only collected once a hole is found, walking over
+        // the holes one by one could take a very long time.
+        let mut indices: Option<Vec<u32>> = None;
+        let mut idx = 0;
+        let mut items = 0;
+        while idx < len {
+            out.push_str(if items == 0 { " " } else { ", " });
+            if items == MAX_ITEMS {
+                more_items(out, (len - idx) as usize);
+                break;
+            }
+            items += 1;
+            let atom = Atom::from_u32(array.ctx().clone(), idx)?;
+            let property = own_property(array, &atom)?;
+            if property.is_none() {
+                let indices = match &mut indices {
+                    Some(indices) => indices,
+                    None => indices.insert(own_indices(array, len)?),
+                };
+                let next = match indices.binary_search(&idx) {
+                    Ok(pos) | Err(pos) => indices.get(pos).copied().unwrap_or(len),
+                };
+                empty_items(out, next - idx);
+                idx = next;
+                continue;
+            }
+            self.property(out, property, depth)?;
+            idx += 1;
+        }
+        out.push_str(if len == 0 { "]" } else { " ]" });
+        Ok(())
+    }
+
+    fn property<'js>(
+        &mut self,
+        out: &mut StdString,
+        property: Option<Property<'js>>,
+        depth: usize,
+    ) -> Result<()> {
+        match property {
+            Some(Property::Data(value)) => self.value(out, &value, depth + 1)?,
+            Some(Property::Accessor { get, set }) => out.push_str(match (get, set) {
+                (true, true) => "[Getter/Setter]",
+                (true, false) => "[Getter]",
+                _ => "[Setter]",
+            }),
+            None => out.push_str("undefined"),
+        }
+        Ok(())
+    }
+}
+
+/// Returns the indices of the own elements of an array in ascending order.
+fn own_indices<'js>(array: &Object<'js>, len: u32) -> Result<Vec<u32>> {
+    let mut indices = Vec::new();
+    for key in array.own_keys::<Atom>(Filter::new().string()) {
+        let key = key?.to_string()?;
+        match key.parse::<u32>() {
+            Ok(idx) if idx < len && idx.to_string() == key => indices.push(idx),
+            _ => {}
+        }
+    }
+    indices.sort_unstable();
+    Ok(indices)
+}
+
+fn more_items(out: &mut StdString, count: usize) {
+    out.push_str(&format!(
+        "... {} more item{}",
+        count,
+        if count == 1 { "" } else { "s" }
+    ));
+}
+
+fn empty_items(out: &mut StdString, count: u32) {
+    out.push_str(&format!(
+        "<{} empty item{}>",
+        count,
+        if count == 1 { "" } else { "s" }
+    ));
+}
+
+/// Write a time value in the format of `Date.prototype.toISOString`.
+fn iso_date(out: &mut StdString, millis: i64) {
+    let days = millis.div_euclid(86_400_000);
+    let time = millis.rem_euclid(86_400_000);
+
+    // Convert days since the epoch into a date of the proleptic gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    if (0..=9999).contains(&year) {
+        out.push_str(&format!("{:04}", year));
+    } else {
+        out.push_str(&format!("{:+07}", year));
+    }
+    out.push_str(&format!(
+        "-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        month,
+        day,
+        time / 3_600_000,
+        time / 60_000 % 60,
+        time / 1000 % 60,
+        time % 1000
+    ));
+}
+
+fn quote(out: &mut StdString, string: &str) {
+    out.push('\'');
+    for c in string.chars() {
+        match c {
+            '\'' => out.push_str("\\'"),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out.push('\'');
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn nested_and_circular() {
+        test_with(|ctx| {
+            let value: Value = ctx
+                .eval(
+                    r#"({
+                        name: "root",
+                        "odd key": [1, 2.5, 3n],
+                        nested: { a: { b: { c: {} } } },
+                        empty: {},
+                        date: new Date(Date.UTC(2020, 1, 29, 12, 30)),
+                        get secret() { throw new Error("getter called"); },
+                        toString() { throw new Error("toString called"); },
+                    })"#,
+                )
+                .unwrap();
+            assert_eq!(
+                value.inspect(2).unwrap(),
+                "{ name: 'root', 'odd key': [ 1, 2.5, 3n ], \
+                 nested: { a: { b: [Object] } }, empty: {}, \
+                 date: 2020-02-29T12:30:00.000Z, secret: [Getter], \
+                 toString: [Function: toString] }"
+            );
+            assert_eq!(
+                value.inspect(0).unwrap(),
+                "{ name: 'root', 'odd key': [Array], nested: [Object], empty: [Object], \
+                 date: 2020-02-29T12:30:00.000Z, secret: [Getter], \
+                 toString: [Function: toString] }"
+            );
+
+            let value: Value = ctx
+                .eval(
+                    r#"
+                    const list = [1, , , "two"];
+                    list.push(list, { list });
+                    list
+                    "#,
+                )
+                .unwrap();
+            assert_eq!(
+                value.inspect(2).unwrap(),
+                "[ 1, <2 empty items>, 'two', [Circular], { list: [Circular] } ]"
+            );
+
+            let value: Value = ctx
+                .eval(
+                    r#"
+                    class Point { constructor() { this.x = 1; } }
+                    [new Point(), Object.create(null)]
+                    "#,
+                )
+                .unwrap();
+            assert_eq!(
+                value.inspect(2).unwrap(),
+                "[ Point { x: 1 }, [Object: null prototype] {} ]"
+            );
+            assert_eq!(
+                ctx.eval::<Value, _>("'it\\'s'")
+                    .unwrap()
+                    .inspect(2)
+                    .unwrap(),
+                "'it\\'s'"
+            );
+        });
+    }
+
+    #[test]
+    fn long_and_sparse_arrays() {
+        test_with(|ctx| {
+            let value: Value = ctx
+                .eval("Array.from({ length: 150 }, (_, idx) => idx)")
+                .unwrap();
+            let items = (0..100).map(|idx| idx.to_string()).collect::<Vec<_>>();
+            assert_eq!(
+                value.inspect(2).unwrap(),
+                format!("[ {}, ... 50 more items ]", items.join(", "))
+            );
+
+            let value: Value = ctx
+                .eval("const sparse = [1]; sparse[4294967294] = 2; sparse")
+                .unwrap();
+            assert_eq!(
+                value.inspect(2).unwrap(),
+                "[ 1, <4294967293 empty items>, 2 ]"
+            );
+
+            let value: Value = ctx
+                .eval("new Set(Array.from({ length: 101 }, (_, idx) => idx))")
+                .unwrap();
+            assert!(value
+                .inspect(2)
+                .unwrap()
+                .ends_with(", 98, 99, ... 1 more item }"));
+        });
+    }
+
+    #[test]
+    fn replaced_methods() {
+        test_with(|ctx| {
+            let value: Value = ctx
+                .eval(
+                    r#"
+                    const value = [new Map([[1, "one"]]), new Set([2]), new Date(0)];
+                    const fail = () => { throw new Error("replaced builtin used"); };
+                    Map.prototype.entries = Map.prototype[Symbol.iterator] = fail;
+                    Set.prototype.values = Set.prototype[Symbol.iterator] = fail;
+                    Object.defineProperty(Map.prototype, "size", { get: fail });
+                    Date.prototype.valueOf = Date.prototype.getTime = fail;
+                    globalThis.Map = globalThis.Set = globalThis.Date = fail;
+                    value
+                    "#,
+                )
+                .unwrap();
+            assert_eq!(
+                value.inspect(2).unwrap(),
+                "[ Map(1) { 1 => 'one' }, Set(1) { 2 }, 1970-01-01T00:00:00.000Z ]"
+            );
+        });
+    }
+}
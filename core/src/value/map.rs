@@ -0,0 +1,220 @@
+//! Javascript map types.
+
+use crate::{
+    convert::List, function::This, value::Constructor, Ctx, FromJs, Function, IntoJs, Object,
+    Outlive, Result, Value,
+};
+use std::{iter::FusedIterator, marker::PhantomData};
+
+/// Rust representation of a javascript object of class Map.
+///
+/// All operations are forwarded to the javascript methods so keys keep their javascript
+/// semantics, objects for instance are compared by reference.
+#[derive(Debug, PartialEq, Clone)]
+#[repr(transparent)]
+pub struct Map<'js>(pub(crate) Object<'js>);
+
+unsafe impl<'js> Outlive<'js> for Map<'js> {
+    type Target<'to> = Map<'to>;
+}
+
+impl<'js> Map<'js> {
+    /// Create a new empty map.
+    pub fn new(ctx: Ctx<'js>) -> Result<Self> {
+        let ctor: Constructor = ctx.globals().get("Map")?;
+        ctor.construct(()).map(Map)
+    }
+
+    /// Set the value for a key.
+    pub fn set<K, V>(&self, key: K, value: V) -> Result<()>
+    where
+        K: IntoJs<'js>,
+        V: IntoJs<'js>,
+    {
+        let func: Function = self.0.get("set")?;
+        func.call::<_, Value>((This(self.0.clone()), key, value))?;
+        Ok(())
+    }
+
+    /// Get the value for a key, returns `undefined` when the key is not in the map.
+    pub fn get<K, V>(&self, key: K) -> Result<V>
+    where
+        K: IntoJs<'js>,
+        V: FromJs<'js>,
+    {
+        let func: Function = self.0.get("get")?;
+        func.call((This(self.0.clone()), key))
+    }
+
+    /// Returns wether the map contains the key.
+    pub fn has<K>(&self, key: K) -> Result<bool>
+    where
+        K: IntoJs<'js>,
+    {
+        let func: Function = self.0.get("has")?;
+        func.call((This(self.0.clone()), key))
+    }
+
+    /// Remove a key from the map, returns wether the key was in the map.
+    pub fn delete<K>(&self, key: K) -> Result<bool>
+    where
+        K: IntoJs<'js>,
+    {
+        let func: Function = self.0.get("delete")?;
+        func.call((This(self.0.clone()), key))
+    }
+
+    /// Remove all entries from the map.
+    pub fn clear(&self) -> Result<()> {
+        let func: Function = self.0.get("clear")?;
+        func.call((This(self.0.clone()),))
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn size(&self) -> Result<usize> {
+        self.0.get("size")
+    }
+
+    /// Get an iterator over the entries of the map in insertion order.
+    pub fn entries<K, V>(&self) -> Result<MapIter<'js, K, V>>
+    where
+        K: FromJs<'js>,
+        V: FromJs<'js>,
+    {
+        let func: Function = self.0.get("entries")?;
+        let iter: Object = func.call((This(self.0.clone()),))?;
+        Ok(MapIter {
+            iter: JsIter::new(iter)?,
+            marker: PhantomData,
+        })
+    }
+}
+
+/// A javascript iterator object driven from rust.
+pub(crate) struct JsIter<'js> {
+    iter: Object<'js>,
+    next: Function<'js>,
+    done: bool,
+}
+
+impl<'js> JsIter<'js> {
+    pub(crate) fn new(iter: Object<'js>) -> Result<Self> {
+        let next = iter.get("next")?;
+        Ok(JsIter {
+            iter,
+            next,
+            done: false,
+        })
+    }
+
+    pub(crate) fn ctx(&self) -> &Ctx<'js> {
+        &self.iter.ctx
+    }
+
+    pub(crate) fn next_value(&mut self) -> Option<Result<Value<'js>>> {
+        if self.done {
+            return None;
+        }
+        let res = self
+            .next
+            .call::<_, Object>((This(self.iter.clone()),))
+            .and_then(|res| {
+                if res.get("done")? {
+                    Ok(None)
+                } else {
+                    res.get("value").map(Some)
+                }
+            });
+        match res {
+            Ok(Some(value)) => Some(Ok(value)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(error) => {
+                self.done = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// The iterator over the entries of a map
+pub struct MapIter<'js, K, V> {
+    iter: JsIter<'js>,
+    marker: PhantomData<(K, V)>,
+}
+
+impl<'js, K, V> Iterator for MapIter<'js, K, V>
+where
+    K: FromJs<'js>,
+    V: FromJs<'js>,
+{
+    type Item = Result<(K, V)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ctx = self.iter.ctx().clone();
+        self.iter.next_value().map(|entry| {
+            let List(entry) = List::<(K, V)>::from_js(&ctx, entry?)?;
+            Ok(entry)
+        })
+    }
+}
+
+impl<'js, K, V> FusedIterator for MapIter<'js, K, V>
+where
+    K: FromJs<'js>,
+    V: FromJs<'js>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn object_key() {
+        test_with(|ctx| {
+            let map = Map::new(ctx.clone()).unwrap();
+            let key = Object::new(ctx.clone()).unwrap();
+            let other = Object::new(ctx.clone()).unwrap();
+            map.set(key.clone(), "value").unwrap();
+
+            assert_eq!(map.size().unwrap(), 1);
+            assert!(map.has(key.clone()).unwrap());
+            assert!(!map.has(other.clone()).unwrap());
+            assert_eq!(map.get::<_, StdString>(key.clone()).unwrap(), "value");
+            assert_eq!(map.get::<_, Option<StdString>>(other).unwrap(), None);
+
+            assert!(map.delete(key.clone()).unwrap());
+            assert!(!map.delete(key).unwrap());
+            assert_eq!(map.size().unwrap(), 0);
+        });
+    }
+
+    #[test]
+    fn entries() {
+        test_with(|ctx| {
+            let map: Map = ctx.eval("new Map([['a', 1], ['b', 2], ['c', 3]])").unwrap();
+            let entries: Vec<(StdString, i32)> =
+                map.entries().unwrap().collect::<Result<_>>().unwrap();
+            assert_eq!(
+                entries,
+                vec![("a".into(), 1), ("b".into(), 2), ("c".into(), 3)]
+            );
+        });
+    }
+
+    #[test]
+    fn from_javascript() {
+        test_with(|ctx| {
+            let map = Map::new(ctx.clone()).unwrap();
+            map.set(1, "one").unwrap();
+            ctx.globals().set("m", map).unwrap();
+            let res: StdString = ctx.eval("m.get(1)").unwrap();
+            assert_eq!(res, "one");
+            let res: Result<Map> = ctx.eval("new Set()");
+            assert!(matches!(res, Err(Error::FromJs { .. })));
+        });
+    }
+}
@@ -10,6 +10,8 @@ use std::{
     slice,
 };
 
+#[cfg(feature = "exports")]
+use crate::Object;
 #[cfg(feature = "exports")]
 use std::marker::PhantomData;
 
@@ -414,7 +416,46 @@ pub struct Module<'js> {
 }
 
 /// Module definition trait
+///
+/// Defines a module implemented in rust without any javascript source. The names of the exports
+/// are declared in [`ModuleDef::declare`] when the module is declared and their values are
+/// provided by [`ModuleDef::evaluate`] when the module is evaluated. Such a module can be
+/// declared directly with [`Module::declare_def`] or made importable with the
+/// [`ModuleLoader`](crate::loader::ModuleLoader).
+///
+/// ```
+/// # use rquickjs::{Runtime, Context, Ctx, Function, Module, Result, module::{ModuleDef, Declarations, Exports}};
+/// struct Fs;
+///
+/// impl ModuleDef for Fs {
+///     fn declare(declare: &mut Declarations) -> Result<()> {
+///         declare.declare("read")?;
+///         Ok(())
+///     }
+///
+///     fn evaluate<'js>(ctx: &Ctx<'js>, exports: &mut Exports<'js>) -> Result<()> {
+///         let read = Function::new(ctx.clone(), |path: String| format!("contents of {path}"))?;
+///         exports.export("read", read)?;
+///         Ok(())
+///     }
+/// }
+///
+/// # let rt = Runtime::new().unwrap();
+/// # let ctx = Context::full(&rt).unwrap();
+/// # ctx.with(|ctx| {
+/// Module::declare_def::<Fs, _>(ctx.clone(), "native:fs").unwrap();
+/// let _ = Module::evaluate(
+///     ctx.clone(),
+///     "main",
+///     "import { read } from 'native:fs'; globalThis.text = read('a.txt');",
+/// )
+/// .unwrap();
+/// let text: String = ctx.globals().get("text").unwrap();
+/// assert_eq!(text, "contents of a.txt");
+/// # })
+/// ```
 pub trait ModuleDef {
+    /// The names of the exports should be declared here
     fn declare(declare: &mut Declarations) -> Result<()> {
         let _ = declare;
         Ok(())
@@ -624,11 +665,19 @@ impl<'js> Module<'js> {
                 ctx.as_ptr(),
                 bytes.as_ptr(),
                 bytes.len() as _,
-                (qjs::JS_READ_OBJ_BYTECODE | qjs::JS_READ_OBJ_ROM_DATA) as i32,
+                // Not `JS_READ_OBJ_ROM_DATA`, the bytes are not guaranteed to outlive the module.
+                qjs::JS_READ_OBJ_BYTECODE as i32,
             )
         };
         let module = ctx.handle_exception(module)?;
-        debug_assert_eq!(qjs::JS_TAG_MODULE, unsafe { qjs::JS_VALUE_GET_TAG(module) });
+        if qjs::JS_VALUE_GET_TAG(module) != qjs::JS_TAG_MODULE {
+            qjs::JS_FreeValue(ctx.as_ptr(), module);
+            return Err(Error::new_from_js_message(
+                "bytecode",
+                "module",
+                "Bytecode is not a module",
+            ));
+        }
         let module = qjs::JS_VALUE_GET_PTR(module).cast::<qjs::JSModuleDef>();
         // Quickjs should throw an exception on allocation errors
         // So this should always be non-null.
@@ -747,23 +796,43 @@ impl<'js> Module<'js> {
 #[cfg(feature = "exports")]
 impl<'js> Module<'js> {
     /// Return exported value by name
+    ///
+    /// Returns an error if the module has no export with the given name. Reading an export
+    /// which is not yet initialized, because the module has not finished evaluating, results in
+    /// the `ReferenceError` thrown by javascript.
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "exports")))]
     pub fn get<N, T>(&self, name: N) -> Result<T>
     where
         N: AsRef<str>,
         T: FromJs<'js>,
     {
-        let name = CString::new(name.as_ref())?;
-        let value = unsafe {
+        let name = name.as_ref();
+        let namespace = self.namespace()?;
+        if !namespace.contains_key(name)? {
+            return Err(Error::new_from_js_message(
+                "module",
+                "export",
+                format!("module has no export named '{name}'"),
+            ));
+        }
+        namespace.get(name)
+    }
+
+    /// Returns the namespace object of the module.
+    ///
+    /// This is the same object which is returned from a dynamic `import()` of the module.
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "exports")))]
+    pub fn namespace(&self) -> Result<Object<'js>> {
+        let namespace = unsafe {
             Value::from_js_value(
                 self.ctx.clone(),
-                self.ctx.handle_exception(qjs::JS_GetModuleExport(
+                self.ctx.handle_exception(qjs::JS_GetModuleNamespace(
                     self.ctx.as_ptr(),
                     self.as_module_def().as_ptr(),
-                    name.as_ptr(),
                 ))?,
             )
         };
-        T::from_js(&self.ctx, value)
+        Object::from_js(&self.ctx, namespace)
     }
 
     /// Returns a iterator over the exported names of the module export.
@@ -985,4 +1054,32 @@ mod test {
             }
         });
     }
+
+    #[cfg(feature = "exports")]
+    #[test]
+    fn typed_exports() {
+        test_with(|ctx| {
+            let module: Module = ctx
+                .clone()
+                .compile(
+                    "typed",
+                    r#"
+            export function add(a, b) { return a + b; }
+            export const value = 3;
+                "#,
+                )
+                .unwrap();
+
+            let add: Function = module.get("add").unwrap();
+            let value: i32 = module.get("value").unwrap();
+            assert_eq!(add.call::<_, i32>((value, 4)).unwrap(), 7);
+
+            let namespace = module.namespace().unwrap();
+            assert!(namespace.contains_key("add").unwrap());
+            assert_eq!(namespace.get::<_, i32>("value").unwrap(), 3);
+
+            let res = module.get::<_, Value>("missing");
+            assert!(matches!(res, Err(Error::FromJs { .. })));
+        });
+    }
 }
@@ -1,8 +1,9 @@
 //! Module for types dealing with JS objects.
 
 use crate::{
-    convert::FromIteratorJs, qjs, Array, Atom, Ctx, FromAtom, FromJs, IntoAtom, IntoJs, Result,
-    Value,
+    convert::FromIteratorJs,
+    function::{Args, IntoArgs},
+    qjs, Array, Atom, Ctx, Error, FromAtom, FromJs, IntoAtom, IntoJs, Result, Value,
 };
 use std::{
     iter::{DoubleEndedIterator, ExactSizeIterator, FusedIterator, IntoIterator, Iterator},
@@ -87,6 +88,47 @@ impl<'js> Object<'js> {
         Ok(())
     }
 
+    /// Call a method of the object with the object as `this`.
+    ///
+    /// Returns an error if the object has no property with the given name or if the property
+    /// is not a function.
+    ///
+    /// ```
+    /// # use rquickjs::{Runtime, Context, Object};
+    /// # let rt = Runtime::new().unwrap();
+    /// # let ctx = Context::full(&rt).unwrap();
+    /// # ctx.with(|ctx| {
+    /// let obj: Object = ctx.eval("({ x: 2, mul(y) { return this.x * y } })").unwrap();
+    /// let res: i32 = obj.call_method("mul", (3,)).unwrap();
+    /// assert_eq!(res, 6);
+    /// # })
+    /// ```
+    pub fn call_method<A, R>(&self, name: &str, args: A) -> Result<R>
+    where
+        A: IntoArgs<'js>,
+        R: FromJs<'js>,
+    {
+        let method: Value = self.get(name)?;
+        let Some(method) = method.as_function() else {
+            if method.is_undefined() && !self.contains_key(name)? {
+                return Err(Error::new_from_js_message(
+                    "undefined",
+                    "function",
+                    format!("method '{}' not found", name),
+                ));
+            }
+            return Err(Error::new_from_js_message(
+                method.type_name(),
+                "function",
+                format!("property '{}' is not a function", name),
+            ));
+        };
+        let mut accum_args = Args::new(self.ctx().clone(), args.num_args());
+        accum_args.this(self.clone())?;
+        args.into_args(&mut accum_args)?;
+        method.call_arg(accum_args)
+    }
+
     /// Check the object for empty
     pub fn is_empty(&self) -> bool {
         self.keys::<Atom>().next().is_none()
@@ -103,6 +145,9 @@ impl<'js> Object<'js> {
     }
 
     /// Get own property names of an object
+    ///
+    /// The filter controls whether string keys, symbol keys and non-enumerable properties are
+    /// included. Iterating with [`Atom`] as key type keeps symbol keys distinguishable from strings.
     pub fn own_keys<K: FromAtom<'js>>(&self, filter: Filter) -> ObjectKeysIter<'js, K> {
         ObjectKeysIter {
             state: Some(IterState::new(&self.0, filter.flags)),
@@ -111,6 +156,10 @@ impl<'js> Object<'js> {
     }
 
     /// Get own string enumerable properties of an object
+    ///
+    /// Yields the converted key and value of each entry like `Object.entries`. The keys are read
+    /// when the iterator is created, properties added while iterating are not visited.
+    #[doc(alias = "entries")]
     pub fn props<K: FromAtom<'js>, V: FromJs<'js>>(&self) -> ObjectIter<'js, K, V> {
         self.own_props(Filter::default())
     }
@@ -143,14 +192,17 @@ impl<'js> Object<'js> {
 
     /// Get an object prototype
     ///
-    /// Objects can have no prototype, in this case this function will return null.
-    pub fn get_prototype(&self) -> Option<Object<'js>> {
+    /// Objects can have no prototype, in this case this function will return `None`. Returns an
+    /// error if the object is a proxy whose `getPrototypeOf` trap throws.
+    pub fn get_prototype(&self) -> Result<Option<Object<'js>>> {
         unsafe {
             let proto = qjs::JS_GetPrototype(self.0.ctx.as_ptr(), self.0.as_js_value());
-            if qjs::JS_IsNull(proto) {
-                None
+            if qjs::JS_IsException(proto) {
+                Err(self.0.ctx.raise_exception())
+            } else if qjs::JS_IsNull(proto) {
+                Ok(None)
             } else {
-                Some(Object::from_js_value(self.0.ctx.clone(), proto))
+                Ok(Some(Object::from_js_value(self.0.ctx.clone(), proto)))
             }
         }
     }
@@ -159,7 +211,8 @@ impl<'js> Object<'js> {
     ///
     /// If called with None the function will set the prototype of the object to null.
     ///
-    /// This function will error if setting the prototype causes a cycle in the prototype chain.
+    /// This function will error if setting the prototype causes a cycle in the prototype chain or
+    /// if the object is not extensible, like setting `__proto__` does in javascript.
     pub fn set_prototype(&self, proto: Option<&Object<'js>>) -> Result<()> {
         let proto = proto.map(|x| x.as_js_value()).unwrap_or(qjs::JS_NULL);
         unsafe {
@@ -172,15 +225,21 @@ impl<'js> Object<'js> {
     }
 
     /// Check instance of object
-    pub fn is_instance_of(&self, class: impl AsRef<Value<'js>>) -> bool {
+    ///
+    /// Returns an error if `class` is not callable or its `Symbol.hasInstance` method throws.
+    pub fn is_instance_of(&self, class: impl AsRef<Value<'js>>) -> Result<bool> {
         let class = class.as_ref();
-        0 != unsafe {
+        let res = unsafe {
             qjs::JS_IsInstanceOf(
                 self.0.ctx.as_ptr(),
                 self.0.as_js_value(),
                 class.as_js_value(),
             )
+        };
+        if res < 0 {
+            return Err(self.0.ctx.raise_exception());
         }
+        Ok(res != 0)
     }
 
     /// Convert into an array
@@ -191,6 +250,147 @@ impl<'js> Object<'js> {
             None
         }
     }
+
+    /// Freeze the object like `Object.freeze`.
+    ///
+    /// Only the object itself is frozen, use [`Object::freeze_deep`] to also freeze the objects
+    /// it refers to. Freezing an already frozen object does nothing.
+    pub fn freeze(&self) -> Result<()> {
+        self.seal_object(true)
+    }
+
+    /// Freeze the object and, recursively, all objects stored in its own properties.
+    ///
+    /// Objects which are already frozen are not traversed further.
+    pub fn freeze_deep(&self) -> Result<()> {
+        self.freeze()?;
+        let values = self.own_values::<Value>(Filter::new().string().symbol());
+        for value in values {
+            if let Some(object) = value?.into_object() {
+                if !object.is_frozen()? {
+                    object.freeze_deep()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the own enumerable properties of the sources onto this object like `Object.assign`.
+    ///
+    /// Getters of the sources are invoked and their values are copied. Properties of later
+    /// sources override those of earlier ones. Both string and symbol keyed properties are
+    /// copied and the global `Object` is not used, so scripts replacing `Object.assign` don't
+    /// affect it.
+    pub fn assign(&self, sources: &[Object<'js>]) -> Result<()> {
+        let filter = Filter::new().string().symbol().enum_only();
+        for source in sources {
+            for key in source.own_keys::<Atom>(filter) {
+                let key = key?;
+                let value: Value = source.get(key.clone())?;
+                self.set(key, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Seal the object like `Object.seal`.
+    pub fn seal(&self) -> Result<()> {
+        self.seal_object(false)
+    }
+
+    /// Prevent new properties from being added to the object.
+    pub fn prevent_extensions(&self) -> Result<()> {
+        unsafe {
+            if 0 > qjs::JS_PreventExtensions(self.0.ctx.as_ptr(), self.0.as_js_value()) {
+                return Err(self.0.ctx.raise_exception());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns wether the object is frozen.
+    pub fn is_frozen(&self) -> Result<bool> {
+        self.is_sealed_object(true)
+    }
+
+    /// Returns wether the object is sealed.
+    pub fn is_sealed(&self) -> Result<bool> {
+        self.is_sealed_object(false)
+    }
+
+    /// Returns wether new properties can be added to the object.
+    pub fn is_extensible(&self) -> Result<bool> {
+        let res = unsafe { qjs::JS_IsExtensible(self.0.ctx.as_ptr(), self.0.as_js_value()) };
+        if res < 0 {
+            return Err(self.0.ctx.raise_exception());
+        }
+        Ok(res != 0)
+    }
+
+    /// Same as the intrinsic `Object.seal` and `Object.freeze`, which scripts can't replace.
+    fn seal_object(&self, freeze: bool) -> Result<()> {
+        unsafe {
+            if 0 > qjs::JS_SealObject(self.0.ctx.as_ptr(), self.0.as_js_value(), freeze as _) {
+                return Err(self.0.ctx.raise_exception());
+            }
+        }
+        Ok(())
+    }
+
+    fn is_sealed_object(&self, frozen: bool) -> Result<bool> {
+        let res =
+            unsafe { qjs::JS_IsSealed(self.0.ctx.as_ptr(), self.0.as_js_value(), frozen as _) };
+        if res < 0 {
+            return Err(self.0.ctx.raise_exception());
+        }
+        Ok(res != 0)
+    }
+
+    /// Returns the descriptor of an own property without invoking getters, or `None` if the
+    /// object doesn't have the property.
+    pub(crate) fn own_descriptor(&self, key: &Atom<'js>) -> Result<Option<Descriptor<'js>>> {
+        let ctx = &self.0.ctx;
+        let mut desc = mem::MaybeUninit::<qjs::JSPropertyDescriptor>::uninit();
+        let res = unsafe {
+            qjs::JS_GetOwnProperty(
+                ctx.as_ptr(),
+                desc.as_mut_ptr(),
+                self.0.as_js_value(),
+                key.atom,
+            )
+        };
+        if res < 0 {
+            return Err(ctx.raise_exception());
+        }
+        if res == 0 {
+            return Ok(None);
+        }
+        let desc = unsafe { desc.assume_init() };
+        Ok(Some(unsafe {
+            Descriptor {
+                flags: desc.flags,
+                value: Value::from_js_value(ctx.clone(), desc.value),
+                getter: Value::from_js_value(ctx.clone(), desc.getter),
+                setter: Value::from_js_value(ctx.clone(), desc.setter),
+            }
+        }))
+    }
+}
+
+/// The descriptor of an own property of an object.
+#[derive(Debug, Clone)]
+pub(crate) struct Descriptor<'js> {
+    pub flags: qjs::c_int,
+    pub value: Value<'js>,
+    pub getter: Value<'js>,
+    pub setter: Value<'js>,
+}
+
+impl<'js> Descriptor<'js> {
+    /// Returns if the property is an accessor property instead of a data property.
+    pub fn is_accessor(&self) -> bool {
+        self.flags & qjs::JS_PROP_GETSET as qjs::c_int != 0
+    }
 }
 
 /// The property filter
@@ -610,7 +810,52 @@ where
 
 #[cfg(test)]
 mod test {
-    use crate::{prelude::*, *};
+    use crate::{object::Filter, prelude::*, *};
+
+    #[test]
+    fn call_method() {
+        test_with(|ctx| {
+            let array: Object = ctx.eval("[1, 2, 3]").unwrap();
+            let idx: i32 = array.call_method("indexOf", (2,)).unwrap();
+            assert_eq!(idx, 1);
+
+            let err = array.call_method::<_, ()>("missing", ()).unwrap_err();
+            assert!(
+                err.to_string().contains("method 'missing' not found"),
+                "{}",
+                err
+            );
+            let err = array.call_method::<_, ()>("length", ()).unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("property 'length' is not a function"),
+                "{}",
+                err
+            );
+        });
+    }
+
+    #[test]
+    fn is_instance_of() {
+        test_with(|ctx| {
+            let array: Object = ctx.eval("[]").unwrap();
+            let array_ctor: Value = ctx.globals().get("Array").unwrap();
+            let date_ctor: Value = ctx.globals().get("Date").unwrap();
+            assert!(array.is_instance_of(&array_ctor).unwrap());
+            assert!(!array.is_instance_of(&date_ctor).unwrap());
+
+            let thrower: Value = ctx
+                .eval("(class { static [Symbol.hasInstance]() { throw new Error('no') } })")
+                .unwrap();
+            assert!(matches!(
+                array.is_instance_of(&thrower),
+                Err(Error::Exception)
+            ));
+            let error = Exception::from_js(&ctx, ctx.catch()).unwrap();
+            assert_eq!(error.message().as_deref(), Some("no"));
+            assert!(ctx.catch().is_null());
+        });
+    }
 
     #[test]
     fn from_javascript() {
@@ -698,6 +943,89 @@ mod test {
         })
     }
 
+    #[test]
+    fn assign() {
+        test_with(|ctx| {
+            let target: Object = ctx.eval("({ a: 0, keep: true })").unwrap();
+            let first: Object = ctx.eval("({ a: 1, b: 1 })").unwrap();
+            let second: Object = ctx.eval("({ b: 2, get c() { return 3; } })").unwrap();
+            target.assign(&[first, second]).unwrap();
+
+            assert_eq!(target.get::<_, i32>("a").unwrap(), 1);
+            assert_eq!(target.get::<_, i32>("b").unwrap(), 2);
+            assert_eq!(target.get::<_, i32>("c").unwrap(), 3);
+            assert!(target.get::<_, bool>("keep").unwrap());
+
+            ctx.globals().set("target", target).unwrap();
+            let res: bool = ctx
+                .eval("Object.getOwnPropertyDescriptor(target, 'c').value === 3")
+                .unwrap();
+            assert!(res);
+        })
+    }
+
+    #[test]
+    fn assign_with_replaced_object() {
+        test_with(|ctx| {
+            let target = Object::new(ctx.clone()).unwrap();
+            let source: Object = ctx
+                .eval(
+                    r#"
+                    Object.assign = () => { throw new Error("replaced builtin used"); };
+                    globalThis.Object = undefined;
+                    globalThis.sym = Symbol("sym");
+                    ({ a: 1, [sym]: 2 })
+                    "#,
+                )
+                .unwrap();
+            target.assign(&[source]).unwrap();
+            assert_eq!(target.get::<_, i32>("a").unwrap(), 1);
+            let sym: Value = ctx.globals().get("sym").unwrap();
+            assert_eq!(target.get::<_, i32>(sym).unwrap(), 2);
+        })
+    }
+
+    #[test]
+    fn own_keys_filter() {
+        test_with(|ctx| {
+            let val: Object = ctx
+                .eval(
+                    r#"
+                    const obj = { visible: 1, [Symbol("sym")]: 2 };
+                    Object.defineProperty(obj, "hidden", { value: 3, enumerable: false });
+                    obj
+                "#,
+                )
+                .unwrap();
+            let keys = |filter: Filter| {
+                val.own_keys(filter)
+                    .map(|key: Result<Value>| {
+                        let key = key?;
+                        Ok(if key.is_symbol() {
+                            let symbol = key.into_symbol().unwrap();
+                            format!("@{}", symbol.description()?.to_string()?)
+                        } else {
+                            key.get::<StdString>()?
+                        })
+                    })
+                    .collect::<Result<Vec<StdString>>>()
+                    .unwrap()
+            };
+
+            assert_eq!(keys(Filter::default()), ["visible"]);
+            assert_eq!(keys(Filter::new().string()), ["visible", "hidden"]);
+            assert_eq!(keys(Filter::new().symbol()), ["@sym"]);
+            assert_eq!(
+                keys(Filter::new().string().symbol().enum_only()),
+                ["visible", "@sym"]
+            );
+            assert_eq!(
+                keys(Filter::new().string().symbol()),
+                ["visible", "hidden", "@sym"]
+            );
+        })
+    }
+
     #[test]
     fn own_props_iter() {
         test_with(|ctx| {
@@ -726,6 +1054,28 @@ mod test {
         })
     }
 
+    #[test]
+    fn props_into_map() {
+        test_with(|ctx| {
+            let val: Object = ctx.eval("({ a: 1, b: 2, c: 3, [Symbol()]: 4 })").unwrap();
+            let map = val
+                .props::<StdString, i32>()
+                .collect::<Result<std::collections::HashMap<_, _>>>()
+                .unwrap();
+            assert_eq!(map.len(), 3);
+            assert_eq!(map["a"], 1);
+            assert_eq!(map["b"], 2);
+            assert_eq!(map["c"], 3);
+
+            let mut iter = val.props::<StdString, Option<i32>>();
+            assert_eq!(iter.next().unwrap().unwrap(), ("a".to_string(), Some(1)));
+            val.set("d", 5).unwrap();
+            val.remove("c").unwrap();
+            let rest = iter.collect::<Result<Vec<_>>>().unwrap();
+            assert_eq!(rest, [("b".to_string(), Some(2)), ("c".to_string(), None)]);
+        })
+    }
+
     #[test]
     fn into_iter() {
         test_with(|ctx| {
@@ -804,4 +1154,119 @@ mod test {
             );
         })
     }
+
+    #[test]
+    fn freeze() {
+        test_with(|ctx| {
+            let obj: Object = ctx
+                .eval("globalThis.obj = { a: 1, inner: { b: 2 } }")
+                .unwrap();
+            assert!(!obj.is_frozen().unwrap());
+            obj.freeze().unwrap();
+            assert!(obj.is_frozen().unwrap());
+            assert!(obj.is_sealed().unwrap());
+            assert!(!obj.is_extensible().unwrap());
+            // freezing twice is fine
+            obj.freeze().unwrap();
+
+            let res = ctx.eval::<(), _>("'use strict'; obj.a = 2");
+            assert!(matches!(res, Err(Error::Exception)));
+            let exception = Exception::from_js(&ctx, ctx.catch()).unwrap();
+            let name: StdString = exception.get("name").unwrap();
+            assert_eq!(name, "TypeError");
+
+            // only shallow
+            let res: i32 = ctx
+                .eval("'use strict'; obj.inner.b = 3; obj.inner.b")
+                .unwrap();
+            assert_eq!(res, 3);
+        })
+    }
+
+    #[test]
+    fn freeze_deep() {
+        test_with(|ctx| {
+            let obj: Object = ctx
+                .eval("globalThis.obj = { inner: { b: 2 } }; obj.inner.self = obj; obj")
+                .unwrap();
+            obj.freeze_deep().unwrap();
+            let inner: Object = obj.get("inner").unwrap();
+            assert!(inner.is_frozen().unwrap());
+            let res = ctx.eval::<(), _>("'use strict'; obj.inner.b = 3");
+            assert!(matches!(res, Err(Error::Exception)));
+            let _ = ctx.catch();
+        })
+    }
+
+    #[test]
+    fn seal_and_prevent_extensions() {
+        test_with(|ctx| {
+            let obj: Object = ctx.eval("globalThis.obj = { a: 1 }").unwrap();
+            obj.prevent_extensions().unwrap();
+            assert!(!obj.is_extensible().unwrap());
+            assert!(!obj.is_sealed().unwrap());
+            let res = ctx.eval::<(), _>("'use strict'; obj.b = 2");
+            assert!(matches!(res, Err(Error::Exception)));
+            let _ = ctx.catch();
+
+            obj.seal().unwrap();
+            assert!(obj.is_sealed().unwrap());
+            assert!(!obj.is_frozen().unwrap());
+            let res: i32 = ctx.eval("'use strict'; obj.a = 5; obj.a").unwrap();
+            assert_eq!(res, 5);
+            let res = ctx.eval::<(), _>("'use strict'; delete obj.a");
+            assert!(matches!(res, Err(Error::Exception)));
+            let _ = ctx.catch();
+        })
+    }
+
+    #[test]
+    fn freeze_with_replaced_object() {
+        test_with(|ctx| {
+            let obj: Object = ctx
+                .eval(
+                    r#"
+                    const fail = () => { throw new Error("replaced builtin used"); };
+                    Object.freeze = Object.seal = Object.isFrozen = Object.isSealed = fail;
+                    globalThis.Object = fail;
+                    ({ a: 1 })
+                    "#,
+                )
+                .unwrap();
+            obj.seal().unwrap();
+            assert!(obj.is_sealed().unwrap());
+            assert!(!obj.is_frozen().unwrap());
+            obj.freeze().unwrap();
+            assert!(obj.is_frozen().unwrap());
+        })
+    }
+
+    #[test]
+    fn prototype() {
+        test_with(|ctx| {
+            let array: Object = ctx.eval("[1, 2]").unwrap();
+            let array_proto: Object = ctx.eval("Array.prototype").unwrap();
+            assert_eq!(array.get_prototype().unwrap(), Some(array_proto.clone()));
+            let object_proto: Object = ctx.eval("Object.prototype").unwrap();
+            assert_eq!(array_proto.get_prototype().unwrap(), Some(object_proto));
+
+            let bare: Object = ctx.eval("Object.create(null)").unwrap();
+            assert_eq!(bare.get_prototype().unwrap(), None);
+            bare.set_prototype(Some(&array_proto)).unwrap();
+            assert!(bare
+                .is_instance_of(ctx.globals().get::<_, Value>("Array").unwrap())
+                .unwrap());
+            bare.set_prototype(None).unwrap();
+            assert_eq!(bare.get_prototype().unwrap(), None);
+
+            // Cycles and non extensible objects are rejected like in javascript.
+            let res = array_proto.set_prototype(Some(&array));
+            assert!(matches!(res, Err(Error::Exception)));
+            let _ = ctx.catch();
+            bare.prevent_extensions().unwrap();
+            let res = bare.set_prototype(Some(&array_proto));
+            assert!(matches!(res, Err(Error::Exception)));
+            let _ = ctx.catch();
+        })
+    }
 }
@@ -52,6 +52,21 @@ impl<'js> Object<'js> {
         }
         Ok(())
     }
+
+    /// Define an enumerable property which can't be reassigned, deleted or redefined.
+    ///
+    /// This is a shorthand for `prop(key, Property::from(value).enumerable())` and is useful to
+    /// inject constants into the global object. Assigning to the property from non-strict
+    /// javascript silently does nothing while strict mode code throws a `TypeError`, setting the
+    /// property with [`Object::set`] returns an error.
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "properties")))]
+    pub fn set_readonly<K, V>(&self, key: K, value: V) -> Result<()>
+    where
+        K: IntoAtom<'js>,
+        V: IntoJs<'js>,
+    {
+        self.prop(key, Property::from(value).enumerable())
+    }
 }
 
 pub type PropertyFlags = qjs::c_int;
@@ -258,7 +273,7 @@ where
 
 #[cfg(test)]
 mod test {
-    use crate::{object::*, *};
+    use crate::{context::EvalOptions, object::*, *};
 
     #[test]
     fn property_with_undefined() {
@@ -378,6 +393,48 @@ mod test {
         });
     }
 
+    #[test]
+    fn readonly_global() {
+        test_with(|ctx| {
+            ctx.globals().set_readonly("VERSION", "1.0.0").unwrap();
+
+            // In sloppy mode the assignment is silently ignored.
+            let sloppy = || EvalOptions {
+                strict: false,
+                ..Default::default()
+            };
+            let version: StdString = ctx
+                .eval_with_options("VERSION = '2.0.0'; VERSION", sloppy())
+                .unwrap();
+            assert_eq!(version, "1.0.0");
+
+            let res: bool = ctx
+                .eval(
+                    r#"
+                    "use strict";
+                    try { VERSION = "2.0.0"; false } catch (e) { e instanceof TypeError }
+                    "#,
+                )
+                .unwrap();
+            assert!(res);
+
+            let res: bool = ctx
+                .eval_with_options(
+                    "!delete globalThis.VERSION && Object.keys(globalThis).includes('VERSION')",
+                    sloppy(),
+                )
+                .unwrap();
+            assert!(res);
+            assert!(matches!(
+                ctx.globals().set("VERSION", "2.0.0"),
+                Err(Error::Exception)
+            ));
+            let _ = ctx.catch();
+            let version: StdString = ctx.globals().get("VERSION").unwrap();
+            assert_eq!(version, "1.0.0");
+        });
+    }
+
     #[test]
     fn property_with_getter_only() {
         test_with(|ctx| {
@@ -434,4 +491,39 @@ mod test {
             assert_eq!(val.lock().clone(), "");
         });
     }
+
+    #[test]
+    fn property_with_counting_getter() {
+        use std::cell::RefCell;
+
+        test_with(|ctx| {
+            let obj = Object::new(ctx.clone()).unwrap();
+            let counter = RefCell::new(0u32);
+            obj.prop(
+                "count",
+                Accessor::from(move || {
+                    let mut counter = counter.borrow_mut();
+                    *counter += 1;
+                    *counter
+                }),
+            )
+            .unwrap();
+            ctx.globals().set("obj", obj.clone()).unwrap();
+
+            let count: u32 = obj.get("count").unwrap();
+            assert_eq!(count, 1);
+            let count: u32 = ctx.eval("obj.count + obj.count").unwrap();
+            assert_eq!(count, 5);
+
+            if let Err(Error::Exception) = ctx.eval::<(), _>("obj.count = 10") {
+                let exception = Exception::from_js(&ctx, ctx.catch()).unwrap();
+                assert_eq!(
+                    exception.message().as_deref(),
+                    Some("no setter for property")
+                );
+            } else {
+                panic!("Should fail");
+            }
+        });
+    }
 }
@@ -0,0 +1,196 @@
+//! Javascript proxy types.
+
+use crate::{function::IntoJsFunc, value::Constructor, Ctx, Function, Object, Outlive, Result};
+
+/// Rust representation of a javascript Proxy object.
+///
+/// The behavior of the proxy is defined by a [`ProxyHandler`] whose traps can be implemented
+/// by rust functions.
+///
+/// Quickjs does not provide a way to check if an object is a proxy so this type can't be
+/// converted from an arbitrary javascript value.
+#[derive(Debug, PartialEq, Clone)]
+#[repr(transparent)]
+pub struct Proxy<'js>(pub(crate) Object<'js>);
+
+unsafe impl<'js> Outlive<'js> for Proxy<'js> {
+    type Target<'to> = Proxy<'to>;
+}
+
+impl<'js> Proxy<'js> {
+    /// Create a new proxy for a target object.
+    pub fn new(target: Object<'js>, handler: ProxyHandler<'js>) -> Result<Self> {
+        let ctor: Constructor = target.ctx.globals().get("Proxy")?;
+        ctor.construct((target, handler.0)).map(Proxy)
+    }
+}
+
+/// The handler object of a proxy.
+///
+/// Every trap is a function which is called with the same arguments as the corresponding
+/// javascript trap, trailing arguments can be left out of the rust function if they are not
+/// needed. Errors returned from a trap are thrown as javascript exceptions.
+///
+/// ```
+/// # use rquickjs::{Runtime, Context, Object, Proxy, ProxyHandler};
+/// # let rt = Runtime::new().unwrap();
+/// # let ctx = Context::full(&rt).unwrap();
+/// # ctx.with(|ctx| {
+/// let handler = ProxyHandler::new(ctx.clone())
+///     .unwrap()
+///     .get(|_target: Object, key: String| format!("value of {}", key))
+///     .unwrap();
+/// let proxy = Proxy::new(Object::new(ctx.clone()).unwrap(), handler).unwrap();
+/// ctx.globals().set("proxy", proxy).unwrap();
+/// let res: String = ctx.eval("proxy.foo").unwrap();
+/// assert_eq!(res, "value of foo");
+/// # })
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+#[repr(transparent)]
+pub struct ProxyHandler<'js>(pub(crate) Object<'js>);
+
+macro_rules! proxy_traps {
+    ($($(#[$meta:meta])* $name:ident => $trap:literal,)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name<F, P>(self, f: F) -> Result<Self>
+            where
+                F: IntoJsFunc<'js, P> + 'js,
+            {
+                self.trap($trap, f)
+            }
+        )*
+    };
+}
+
+impl<'js> ProxyHandler<'js> {
+    /// Create a new handler without any traps.
+    pub fn new(ctx: Ctx<'js>) -> Result<Self> {
+        Object::new(ctx).map(ProxyHandler)
+    }
+
+    /// Create a handler from an existing handler object.
+    pub fn from_object(object: Object<'js>) -> Self {
+        ProxyHandler(object)
+    }
+
+    /// Set a trap by its javascript name.
+    pub fn trap<F, P>(self, name: &str, f: F) -> Result<Self>
+    where
+        F: IntoJsFunc<'js, P> + 'js,
+    {
+        let func = Function::new(self.0.ctx.clone(), f)?.with_name(name)?;
+        self.0.set(name, func)?;
+        Ok(self)
+    }
+
+    proxy_traps! {
+        /// Set the trap for reading a property, called with `(target, key, receiver)`.
+        get => "get",
+        /// Set the trap for assigning a property, called with `(target, key, value, receiver)`.
+        ///
+        /// The trap should return wether the assignment succeeded.
+        set => "set",
+        /// Set the trap for the `in` operator, called with `(target, key)`.
+        has => "has",
+        /// Set the trap for the `delete` operator, called with `(target, key)`.
+        delete_property => "deleteProperty",
+        /// Set the trap for listing the own keys of the object, called with `(target)`.
+        own_keys => "ownKeys",
+        /// Set the trap for retrieving a property descriptor, called with `(target, key)`.
+        get_own_property_descriptor => "getOwnPropertyDescriptor",
+    }
+
+    /// Reference as an object
+    #[inline]
+    pub fn as_object(&self) -> &Object<'js> {
+        &self.0
+    }
+
+    /// Convert into an object
+    #[inline]
+    pub fn into_object(self) -> Object<'js> {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn lazy_get() {
+        test_with(|ctx| {
+            let calls = Cell::new(0);
+            let handler = ProxyHandler::new(ctx.clone())
+                .unwrap()
+                .get(move |_target: Object, key: StdString| {
+                    calls.set(calls.get() + 1);
+                    format!("{}:{}", key, calls.get())
+                })
+                .unwrap();
+            let proxy = Proxy::new(Object::new(ctx.clone()).unwrap(), handler).unwrap();
+            ctx.globals().set("proxy", proxy).unwrap();
+
+            let res: StdString = ctx.eval("proxy.foo").unwrap();
+            assert_eq!(res, "foo:1");
+            let res: StdString = ctx.eval("proxy['bar']").unwrap();
+            assert_eq!(res, "bar:2");
+        })
+    }
+
+    #[test]
+    fn own_keys() {
+        fn descriptor<'js>(
+            ctx: Ctx<'js>,
+            _target: Object<'js>,
+            key: StdString,
+        ) -> Result<Object<'js>> {
+            let desc = Object::new(ctx)?;
+            desc.set("value", key)?;
+            desc.set("enumerable", true)?;
+            desc.set("configurable", true)?;
+            Ok(desc)
+        }
+
+        test_with(|ctx| {
+            let handler = ProxyHandler::new(ctx.clone())
+                .unwrap()
+                .own_keys(|| vec!["a", "b", "c"])
+                .unwrap()
+                .get_own_property_descriptor(descriptor)
+                .unwrap();
+            let proxy = Proxy::new(Object::new(ctx.clone()).unwrap(), handler).unwrap();
+            ctx.globals().set("proxy", proxy).unwrap();
+
+            let res: Vec<StdString> = ctx.eval("Object.keys(proxy)").unwrap();
+            assert_eq!(res, vec!["a", "b", "c"]);
+        })
+    }
+
+    #[test]
+    fn trap_error() {
+        test_with(|ctx| {
+            let handler = ProxyHandler::new(ctx.clone())
+                .unwrap()
+                .has(|_target: Object, key: StdString| {
+                    if key == "bad" {
+                        Err(Error::new_from_js("key", "property"))
+                    } else {
+                        Ok(key == "good")
+                    }
+                })
+                .unwrap();
+            let proxy = Proxy::new(Object::new(ctx.clone()).unwrap(), handler).unwrap();
+            ctx.globals().set("proxy", proxy).unwrap();
+
+            assert!(ctx.eval::<bool, _>("'good' in proxy").unwrap());
+            assert!(!ctx.eval::<bool, _>("'other' in proxy").unwrap());
+            let res = ctx.eval::<bool, _>("'bad' in proxy");
+            assert!(matches!(res, Err(Error::Exception)));
+            let _ = ctx.catch();
+        })
+    }
+}
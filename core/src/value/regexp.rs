@@ -0,0 +1,103 @@
+use crate::{function::This, value::Constructor, Array, Ctx, Function, Object, Outlive, Result};
+
+/// Rust representation of a javascript object of class RegExp.
+#[derive(Debug, PartialEq, Clone)]
+#[repr(transparent)]
+pub struct RegExp<'js>(pub(crate) Object<'js>);
+
+unsafe impl<'js> Outlive<'js> for RegExp<'js> {
+    type Target<'to> = RegExp<'to>;
+}
+
+impl<'js> RegExp<'js> {
+    /// Compile a new regular expression from a pattern and flags.
+    ///
+    /// An invalid pattern or flag results in an [`Error::Exception`] with the thrown
+    /// `SyntaxError` retrievable via [`Ctx::catch`].
+    pub fn new(ctx: Ctx<'js>, pattern: &str, flags: &str) -> Result<Self> {
+        let ctor: Constructor = ctx.globals().get("RegExp")?;
+        ctor.construct((pattern, flags)).map(RegExp)
+    }
+
+    /// Returns wether the regular expression matches the input.
+    ///
+    /// Like `RegExp.prototype.test` this advances `lastIndex` for global and sticky expressions.
+    pub fn test(&self, input: &str) -> Result<bool> {
+        let func: Function = self.0.get("test")?;
+        func.call((This(self.0.clone()), input))
+    }
+
+    /// Execute a search for a match in the input.
+    ///
+    /// Returns the match array or `None` if there was no match.
+    pub fn exec(&self, input: &str) -> Result<Option<Array<'js>>> {
+        let func: Function = self.0.get("exec")?;
+        func.call((This(self.0.clone()), input))
+    }
+
+    /// Returns the index at which the next match will start.
+    pub fn last_index(&self) -> Result<u32> {
+        self.0.get("lastIndex")
+    }
+
+    /// Set the index at which the next match will start.
+    pub fn set_last_index(&self, index: u32) -> Result<()> {
+        self.0.set("lastIndex", index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn test_match() {
+        test_with(|ctx| {
+            let re = RegExp::new(ctx, "^a+b$", "i").unwrap();
+            assert!(re.test("aaB").unwrap());
+            assert!(!re.test("aac").unwrap());
+        });
+    }
+
+    #[test]
+    fn exec_global() {
+        test_with(|ctx| {
+            let re = RegExp::new(ctx, "o(\\w)", "g").unwrap();
+            let input = "foo bot";
+
+            let res = re.exec(input).unwrap().unwrap();
+            assert_eq!(res.get::<StdString>(0).unwrap(), "oo");
+            assert_eq!(res.get::<StdString>(1).unwrap(), "o");
+            assert_eq!(re.last_index().unwrap(), 3);
+
+            let res = re.exec(input).unwrap().unwrap();
+            assert_eq!(res.get::<StdString>(0).unwrap(), "ot");
+            assert_eq!(res.get::<StdString>(1).unwrap(), "t");
+            assert_eq!(re.last_index().unwrap(), 7);
+
+            assert!(re.exec(input).unwrap().is_none());
+            assert_eq!(re.last_index().unwrap(), 0);
+        });
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        test_with(|ctx| {
+            let res = RegExp::new(ctx.clone(), "(unclosed", "");
+            assert!(matches!(res, Err(Error::Exception)));
+            let exception = Exception::from_js(&ctx, ctx.catch()).unwrap();
+            let name: StdString = exception.get("name").unwrap();
+            assert_eq!(name, "SyntaxError");
+        });
+    }
+
+    #[test]
+    fn from_javascript() {
+        test_with(|ctx| {
+            let re: RegExp = ctx.eval("/x/y").unwrap();
+            assert!(re.test("x").unwrap());
+            let res: Result<RegExp> = ctx.eval("({})");
+            assert!(matches!(res, Err(Error::FromJs { .. })));
+        });
+    }
+}
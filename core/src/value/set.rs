@@ -0,0 +1,134 @@
+//! Javascript set types.
+
+use crate::{
+    function::This, value::Constructor, Ctx, FromJs, Function, IntoJs, Object, Outlive, Result,
+    Value,
+};
+use std::{iter::FusedIterator, marker::PhantomData};
+
+use super::map::JsIter;
+
+/// Rust representation of a javascript object of class Set.
+///
+/// All operations are forwarded to the javascript methods so values keep their javascript
+/// semantics, objects for instance are compared by reference.
+#[derive(Debug, PartialEq, Clone)]
+#[repr(transparent)]
+pub struct Set<'js>(pub(crate) Object<'js>);
+
+unsafe impl<'js> Outlive<'js> for Set<'js> {
+    type Target<'to> = Set<'to>;
+}
+
+impl<'js> Set<'js> {
+    /// Create a new empty set.
+    pub fn new(ctx: Ctx<'js>) -> Result<Self> {
+        let ctor: Constructor = ctx.globals().get("Set")?;
+        ctor.construct(()).map(Set)
+    }
+
+    /// Add a value to the set.
+    pub fn add<V>(&self, value: V) -> Result<()>
+    where
+        V: IntoJs<'js>,
+    {
+        let func: Function = self.0.get("add")?;
+        func.call::<_, Value>((This(self.0.clone()), value))?;
+        Ok(())
+    }
+
+    /// Returns wether the set contains the value.
+    pub fn has<V>(&self, value: V) -> Result<bool>
+    where
+        V: IntoJs<'js>,
+    {
+        let func: Function = self.0.get("has")?;
+        func.call((This(self.0.clone()), value))
+    }
+
+    /// Remove a value from the set, returns wether the value was in the set.
+    pub fn delete<V>(&self, value: V) -> Result<bool>
+    where
+        V: IntoJs<'js>,
+    {
+        let func: Function = self.0.get("delete")?;
+        func.call((This(self.0.clone()), value))
+    }
+
+    /// Remove all values from the set.
+    pub fn clear(&self) -> Result<()> {
+        let func: Function = self.0.get("clear")?;
+        func.call((This(self.0.clone()),))
+    }
+
+    /// Returns the number of values in the set.
+    pub fn size(&self) -> Result<usize> {
+        self.0.get("size")
+    }
+
+    /// Get an iterator over the values of the set in insertion order.
+    pub fn values<V>(&self) -> Result<SetIter<'js, V>>
+    where
+        V: FromJs<'js>,
+    {
+        let func: Function = self.0.get("values")?;
+        let iter: Object = func.call((This(self.0.clone()),))?;
+        Ok(SetIter {
+            iter: JsIter::new(iter)?,
+            marker: PhantomData,
+        })
+    }
+}
+
+/// The iterator over the values of a set
+pub struct SetIter<'js, V> {
+    iter: JsIter<'js>,
+    marker: PhantomData<V>,
+}
+
+impl<'js, V> Iterator for SetIter<'js, V>
+where
+    V: FromJs<'js>,
+{
+    type Item = Result<V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ctx = self.iter.ctx().clone();
+        self.iter.next_value().map(|value| V::from_js(&ctx, value?))
+    }
+}
+
+impl<'js, V> FusedIterator for SetIter<'js, V> where V: FromJs<'js> {}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn object_value() {
+        test_with(|ctx| {
+            let set = Set::new(ctx.clone()).unwrap();
+            let value = Object::new(ctx.clone()).unwrap();
+            let other = Object::new(ctx.clone()).unwrap();
+            set.add(value.clone()).unwrap();
+            set.add(value.clone()).unwrap();
+
+            assert_eq!(set.size().unwrap(), 1);
+            assert!(set.has(value.clone()).unwrap());
+            assert!(!set.has(other).unwrap());
+
+            assert!(set.delete(value.clone()).unwrap());
+            assert!(!set.delete(value).unwrap());
+            assert_eq!(set.size().unwrap(), 0);
+        });
+    }
+
+    #[test]
+    fn values() {
+        test_with(|ctx| {
+            let set: Set = ctx.eval("new Set([3, 1, 2, 1])").unwrap();
+            let values: Vec<i32> = set.values().unwrap().collect::<Result<_>>().unwrap();
+            assert_eq!(values, vec![3, 1, 2]);
+        });
+    }
+}
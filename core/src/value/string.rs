@@ -1,5 +1,5 @@
 use crate::{qjs, Ctx, Error, Result, StdString, Value};
-use std::{mem, slice, str};
+use std::{ffi::c_char, mem, ops::Deref, slice, str};
 
 /// Rust representation of a javascript string.
 #[derive(Debug, Clone, PartialEq)]
@@ -25,6 +25,57 @@ impl<'js> String<'js> {
         Ok(result?)
     }
 
+    /// Borrow the contents of the javascript string without copying them into a rust string.
+    ///
+    /// The returned guard derefs to a `&str` which can only be used while the guard is alive.
+    /// QuickJS might still allocate internally to produce the UTF-8 representation, for example
+    /// when the string contains non-ASCII characters or is a rope, but no rust allocation is made.
+    pub fn as_bytes(&self) -> Result<StringRef<'js>> {
+        let mut len = mem::MaybeUninit::uninit();
+        let ptr = unsafe {
+            qjs::JS_ToCStringLen(self.0.ctx.as_ptr(), len.as_mut_ptr(), self.0.as_js_value())
+        };
+        if ptr.is_null() {
+            return Err(Error::Unknown);
+        }
+        let len = unsafe { len.assume_init() };
+        let string = StringRef {
+            ctx: self.0.ctx.clone(),
+            ptr,
+            len,
+        };
+        // Lone surrogates are encoded by QuickJS as invalid UTF-8 so this must be checked.
+        str::from_utf8(string.raw())?;
+        Ok(string)
+    }
+
+    /// Get the UTF-16 code units of the javascript string.
+    ///
+    /// Unlike [`String::to_string`] this conversion is lossless, unpaired surrogates are returned
+    /// as is. QuickJS stores strings either as Latin-1 or as UTF-16, narrow strings are widened to
+    /// one code unit per character.
+    pub fn to_utf16(&self) -> Result<Vec<u16>> {
+        let mut len = mem::MaybeUninit::uninit();
+        // With CESU-8 every UTF-16 code unit, including surrogates, is encoded separately which
+        // makes it possible to recover the exact code units.
+        let ptr = unsafe {
+            qjs::JS_ToCStringLen2(
+                self.0.ctx.as_ptr(),
+                len.as_mut_ptr(),
+                self.0.as_js_value(),
+                1,
+            )
+        };
+        if ptr.is_null() {
+            return Err(Error::Unknown);
+        }
+        let len = unsafe { len.assume_init() };
+        let bytes: &[u8] = unsafe { slice::from_raw_parts(ptr as _, len as _) };
+        let result = decode_cesu8(bytes);
+        unsafe { qjs::JS_FreeCString(self.0.ctx.as_ptr(), ptr) };
+        result
+    }
+
     /// Create a new js string from an rust string.
     pub fn from_str(ctx: Ctx<'js>, s: &str) -> Result<Self> {
         let len = s.as_bytes().len();
@@ -37,6 +88,60 @@ impl<'js> String<'js> {
     }
 }
 
+fn decode_cesu8(bytes: &[u8]) -> Result<Vec<u16>> {
+    let invalid = || Error::new_from_js_message("string", "utf-16", "invalid CESU-8 sequence");
+    let continuation = |b: Option<&u8>| match b {
+        Some(b) if b & 0xC0 == 0x80 => Ok(u16::from(b & 0x3F)),
+        _ => Err(invalid()),
+    };
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter();
+    while let Some(&b) = iter.next() {
+        let unit = match b {
+            0x00..=0x7F => u16::from(b),
+            0xC0..=0xDF => (u16::from(b & 0x1F) << 6) | continuation(iter.next())?,
+            0xE0..=0xEF => {
+                let high = continuation(iter.next())?;
+                let low = continuation(iter.next())?;
+                (u16::from(b & 0x0F) << 12) | (high << 6) | low
+            }
+            _ => return Err(invalid()),
+        };
+        units.push(unit);
+    }
+    Ok(units)
+}
+
+/// A borrowed UTF-8 view of a javascript string.
+///
+/// Returned by [`String::as_bytes`], the underlying C string is freed when the guard is dropped.
+pub struct StringRef<'js> {
+    ctx: Ctx<'js>,
+    ptr: *const c_char,
+    len: usize,
+}
+
+impl<'js> StringRef<'js> {
+    fn raw(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr as _, self.len) }
+    }
+}
+
+impl<'js> Deref for StringRef<'js> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        // Safety: The bytes were checked to be valid UTF-8 when the guard was created.
+        unsafe { str::from_utf8_unchecked(self.raw()) }
+    }
+}
+
+impl<'js> Drop for StringRef<'js> {
+    fn drop(&mut self) {
+        unsafe { qjs::JS_FreeCString(self.ctx.as_ptr(), self.ptr) }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::{prelude::*, *};
@@ -48,6 +153,49 @@ mod test {
         });
     }
 
+    #[test]
+    fn borrowed_access() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        let s = ctx.with(|ctx| {
+            let s: String = ctx.eval("'a'.repeat(1 << 20)").unwrap();
+            Persistent::save(&ctx, s)
+        });
+
+        let before = rt.memory_usage().malloc_size;
+        ctx.with(|ctx| {
+            let s = s.clone().restore(&ctx).unwrap();
+            let bytes = s.as_bytes().unwrap();
+            let again = s.as_bytes().unwrap();
+            // Ascii strings are handed out directly without copying them.
+            assert_eq!(bytes.as_ptr(), again.as_ptr());
+            assert_eq!(bytes.len(), 1 << 20);
+            assert!(bytes.chars().all(|c| c == 'a'));
+        });
+        let after = rt.memory_usage().malloc_size;
+        assert!(after < before + (1 << 20));
+
+        ctx.with(|ctx| {
+            let s: String = ctx.eval("'föö'").unwrap();
+            assert_eq!(&*s.as_bytes().unwrap(), "föö");
+        });
+    }
+
+    #[test]
+    fn utf16_lone_surrogate() {
+        test_with(|ctx| {
+            let s: String = ctx.eval("'a\\u00e9\\ud800b\\ud83d\\ude00'").unwrap();
+            let units = s.to_utf16().unwrap();
+            assert_eq!(units, [0x61, 0xe9, 0xd800, 0x62, 0xd83d, 0xde00]);
+
+            let from_units: Function = ctx
+                .eval("(s, units) => String.fromCharCode(...units) === s")
+                .unwrap();
+            let res: bool = from_units.call((s, units)).unwrap();
+            assert!(res);
+        });
+    }
+
     #[test]
     fn to_javascript() {
         test_with(|ctx| {
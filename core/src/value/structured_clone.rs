@@ -0,0 +1,353 @@
+use std::{collections::HashMap, mem::MaybeUninit};
+
+use crate::{
+    atom::PredefinedAtom,
+    qjs,
+    value::builtin::{BuiltinClass, View},
+    Array, Atom, Ctx, Error, IntoAtom, Object, Result, Type, Value,
+};
+
+impl<'js> Value<'js> {
+    /// Create a deep copy of the value in the given context following the structured clone
+    /// algorithm.
+    ///
+    /// Plain objects, arrays, `Map`, `Set`, `Date`, `RegExp`, errors, `ArrayBuffer` and its views
+    /// are copied recursively, references to the same object, including cycles, are preserved in
+    /// the copy. Only own enumerable string keyed properties are copied and prototypes are not
+    /// preserved.
+    ///
+    /// Values are copied according to the builtin class they were created with and the copies
+    /// are created by quickjs directly, so scripts which replace constructors, prototype methods
+    /// or `Symbol.toStringTag` can't change how values are cloned.
+    ///
+    /// Like in browsers cloning functions, symbols, promises and weak collections returns an
+    /// error.
+    pub fn structured_clone(&self, target_ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        Cloner {
+            source: self.ctx().clone(),
+            target: target_ctx.clone(),
+            visited: HashMap::new(),
+        }
+        .clone_value(self)
+    }
+}
+
+struct Cloner<'js> {
+    source: Ctx<'js>,
+    target: Ctx<'js>,
+    /// Already cloned objects keyed by the pointer of the original.
+    visited: HashMap<*mut qjs::c_void, Value<'js>>,
+}
+
+impl<'js> Cloner<'js> {
+    fn not_cloneable(value: &Value<'js>) -> Error {
+        Error::new_from_js_message(
+            value.type_name(),
+            "structured clone",
+            "value could not be cloned",
+        )
+    }
+
+    fn clone_value(&mut self, value: &Value<'js>) -> Result<Value<'js>> {
+        match value.type_of() {
+            Type::Uninitialized
+            | Type::Undefined
+            | Type::Null
+            | Type::Bool
+            | Type::Int
+            | Type::Float
+            | Type::String
+            | Type::BigInt => {
+                Ok(unsafe { Value::from_js_value_const(self.target.clone(), value.as_js_value()) })
+            }
+            Type::Array | Type::Object | Type::Exception => {
+                let key = unsafe { value.get_ptr() };
+                if let Some(cloned) = self.visited.get(&key) {
+                    return Ok(cloned.clone());
+                }
+                let object = value.as_object().unwrap();
+                self.clone_object(key, object)
+            }
+            _ => Err(Self::not_cloneable(value)),
+        }
+    }
+
+    /// Remember a new clone before its contents are cloned so cycles resolve to it.
+    fn visit<V: AsRef<Value<'js>>>(&mut self, key: *mut qjs::c_void, clone: V) -> V {
+        self.visited.insert(key, clone.as_ref().clone());
+        clone
+    }
+
+    fn clone_object(&mut self, key: *mut qjs::c_void, object: &Object<'js>) -> Result<Value<'js>> {
+        let source = self.source.clone();
+        let target = self.target.clone();
+
+        match object.builtin_class() {
+            BuiltinClass::Array => {
+                let array = self.visit(key, Array::new(target)?);
+                let len: Value = object.get(PredefinedAtom::Length)?;
+                array.as_object().set(PredefinedAtom::Length, len)?;
+                self.clone_properties(object, array.as_object())?;
+                Ok(array.into_value())
+            }
+            BuiltinClass::Date => {
+                let time = object.date_value()?;
+                let clone = unsafe {
+                    let clone = qjs::JS_NewDate(target.as_ptr(), time);
+                    Value::from_js_value(target.clone(), target.handle_exception(clone)?)
+                };
+                Ok(self.visit(key, clone))
+            }
+            BuiltinClass::RegExp => {
+                let clone = unsafe {
+                    let clone = qjs::JS_NewRegExpCopy(target.as_ptr(), object.as_js_value());
+                    Value::from_js_value(target.clone(), target.handle_exception(clone)?)
+                };
+                Ok(self.visit(key, clone))
+            }
+            class @ (BuiltinClass::Map | BuiltinClass::Set) => {
+                let class_id = if class == BuiltinClass::Map {
+                    qjs::JS_CLASS_MAP
+                } else {
+                    qjs::JS_CLASS_SET
+                };
+                let clone = unsafe {
+                    let clone = qjs::JS_NewMapObject(target.as_ptr(), class_id);
+                    Object::from_js_value(target.clone(), target.handle_exception(clone)?)
+                };
+                let clone = self.visit(key, clone);
+                for (k, v) in object.map_entries()? {
+                    let (k, v) = (self.clone_value(&k)?, self.clone_value(&v)?);
+                    let res = unsafe {
+                        qjs::JS_MapSetValue(
+                            target.as_ptr(),
+                            clone.as_js_value(),
+                            k.as_js_value(),
+                            v.as_js_value(),
+                        )
+                    };
+                    if res < 0 {
+                        return Err(target.raise_exception());
+                    }
+                }
+                Ok(clone.into_value())
+            }
+            BuiltinClass::Error => {
+                let clone = unsafe {
+                    // The kind is found by the prototype, a renamed error keeps its kind.
+                    let kind = qjs::JS_GetNativeErrorKind(source.as_ptr(), object.as_js_value());
+                    let clone = qjs::JS_NewNativeError(target.as_ptr(), kind);
+                    Object::from_js_value(target.clone(), target.handle_exception(clone)?)
+                };
+                let clone = self.visit(key, clone);
+                let flags = qjs::JS_PROP_WRITABLE | qjs::JS_PROP_CONFIGURABLE;
+                let message: Value = object.get(PredefinedAtom::Message)?;
+                if !message.is_undefined() {
+                    let message = message.to_js_string()?.into_value();
+                    self.define(&clone, PredefinedAtom::Message, message, flags)?;
+                }
+                let stack: Value = object.get(PredefinedAtom::Stack)?;
+                if stack.is_string() {
+                    let stack = self.clone_value(&stack)?;
+                    self.define(&clone, PredefinedAtom::Stack, stack, flags)?;
+                }
+                Ok(clone.into_value())
+            }
+            BuiltinClass::ArrayBuffer => {
+                let clone = unsafe {
+                    let mut len = MaybeUninit::<qjs::size_t>::uninit();
+                    let bytes = qjs::JS_GetArrayBuffer(
+                        source.as_ptr(),
+                        len.as_mut_ptr(),
+                        object.as_js_value(),
+                    );
+                    if bytes.is_null() {
+                        return Err(source.raise_exception());
+                    }
+                    let clone =
+                        qjs::JS_NewArrayBufferCopy(target.as_ptr(), bytes, len.assume_init());
+                    Value::from_js_value(target.clone(), target.handle_exception(clone)?)
+                };
+                Ok(self.visit(key, clone))
+            }
+            BuiltinClass::View(class_id) => {
+                let view = object.view()?;
+                let buffer = self.clone_value(view.buffer.as_value())?;
+                let clone = View::create(
+                    &target,
+                    class_id,
+                    buffer.as_object().unwrap(),
+                    view.offset,
+                    view.len,
+                )?;
+                Ok(self.visit(key, clone.into_value()))
+            }
+            BuiltinClass::Opaque => Err(Self::not_cloneable(object.as_value())),
+            BuiltinClass::Other => {
+                let clone = self.visit(key, Object::new(target)?);
+                self.clone_properties(object, &clone)?;
+                Ok(clone.into_value())
+            }
+        }
+    }
+
+    fn clone_properties(&mut self, from: &Object<'js>, to: &Object<'js>) -> Result<()> {
+        for key in from.keys::<Atom>() {
+            let key = key?;
+            let value: Value = from.get(key.clone())?;
+            let value = self.clone_value(&value)?;
+            self.define(to, key, value, qjs::JS_PROP_C_W_E)?;
+        }
+        Ok(())
+    }
+
+    /// Properties are defined instead of set, so setters on the prototype chain of the target
+    /// realm don't see the cloned values.
+    fn define<K: IntoAtom<'js>>(
+        &self,
+        object: &Object<'js>,
+        key: K,
+        value: Value<'js>,
+        flags: u32,
+    ) -> Result<()> {
+        let key = key.into_atom(&self.target)?;
+        unsafe {
+            let res = qjs::JS_DefinePropertyValue(
+                self.target.as_ptr(),
+                object.as_js_value(),
+                key.atom,
+                value.into_js_value(),
+                (flags | qjs::JS_PROP_THROW) as _,
+            );
+            if res < 0 {
+                return Err(self.target.raise_exception());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn clone_cycle_and_map() {
+        test_with(|ctx| {
+            let value: Value = ctx
+                .eval(
+                    r#"
+                    globalThis.original = {
+                        name: "root",
+                        list: [1, , 3],
+                        map: new Map([["key", { nested: true }]]),
+                        date: new Date(1000),
+                        bytes: new Uint8Array([1, 2, 3]).subarray(1),
+                    };
+                    original.self = original;
+                    original.map.set("root", original);
+                    original
+                    "#,
+                )
+                .unwrap();
+            let clone = value.structured_clone(&ctx).unwrap();
+            ctx.globals().set("clone", clone).unwrap();
+
+            let res: bool = ctx
+                .eval(
+                    r#"
+                    clone !== original
+                        && clone.self === clone
+                        && clone.name === "root"
+                        && clone.list.length === 3 && !(1 in clone.list) && clone.list[2] === 3
+                        && clone.map instanceof Map && clone.map !== original.map
+                        && clone.map.get("root") === clone
+                        && clone.map.get("key").nested === true
+                        && clone.map.get("key") !== original.map.get("key")
+                        && clone.date instanceof Date && clone.date.getTime() === 1000
+                        && clone.bytes instanceof Uint8Array
+                        && clone.bytes.join() === "2,3" && clone.bytes.byteOffset === 1
+                    "#,
+                )
+                .unwrap();
+            assert!(res);
+
+            let res: bool = ctx
+                .eval(
+                    r#"
+                    original.name = "changed";
+                    original.map.get("key").nested = false;
+                    original.bytes[0] = 0;
+                    clone.name === "root" && clone.map.get("key").nested && clone.bytes[0] === 2
+                    "#,
+                )
+                .unwrap();
+            assert!(res);
+        });
+    }
+
+    #[test]
+    fn clone_with_replaced_builtins() {
+        test_with(|ctx| {
+            let value: Value = ctx
+                .eval(
+                    r#"
+                    globalThis.intrinsics = { Map, Set, Date, RegExp, RangeError, Uint8Array, DataView };
+                    globalThis.original = {
+                        map: new Map([[1, 2]]),
+                        set: new Set([3]),
+                        date: new Date(5),
+                        regexp: /a/g,
+                        error: new RangeError("bad"),
+                        bytes: new Uint8Array([1, 2]),
+                        view: new DataView(new ArrayBuffer(4), 1, 2),
+                        list: [1],
+                    };
+                    const fail = () => { throw new Error("replaced builtin used"); };
+                    Map.prototype.entries = Map.prototype[Symbol.iterator] = Map.prototype.set = fail;
+                    Set.prototype.values = Set.prototype[Symbol.iterator] = Set.prototype.add = fail;
+                    Object.defineProperty(Uint8Array.prototype, Symbol.toStringTag, { get: () => "DataView" });
+                    Object.defineProperty(Object.prototype, "0", { set: fail });
+                    for (const name of ["Map", "Set", "Date", "RegExp", "RangeError", "ArrayBuffer", "Uint8Array", "DataView"]) {
+                        globalThis[name] = fail;
+                    }
+                    original
+                    "#,
+                )
+                .unwrap();
+            let clone = value.structured_clone(&ctx).unwrap();
+            ctx.globals().set("clone", clone).unwrap();
+
+            let res: bool = ctx
+                .eval(
+                    r#"
+                    const { Map, Set, Date, RegExp, RangeError, Uint8Array, DataView } = intrinsics;
+                    clone.map instanceof Map && clone.map.get(1) === 2
+                        && clone.set instanceof Set && clone.set.has(3)
+                        && clone.date instanceof Date && clone.date.getTime() === 5
+                        && clone.regexp instanceof RegExp && String(clone.regexp) === "/a/g"
+                        && clone.error instanceof RangeError && clone.error.message === "bad"
+                        && Object.getPrototypeOf(clone.bytes) === Uint8Array.prototype
+                        && clone.bytes[1] === 2
+                        && clone.view instanceof DataView
+                        && clone.view.byteOffset === 1 && clone.view.byteLength === 2
+                        && clone.list[0] === 1
+                    "#,
+                )
+                .unwrap();
+            assert!(res);
+        });
+    }
+
+    #[test]
+    fn not_cloneable() {
+        test_with(|ctx| {
+            let value: Value = ctx.eval("({ f() {} })").unwrap();
+            assert!(value.structured_clone(&ctx).is_err());
+            let value: Value = ctx.eval("Symbol()").unwrap();
+            assert!(value.structured_clone(&ctx).is_err());
+            let value: Value = ctx.eval("[Promise.resolve()]").unwrap();
+            assert!(value.structured_clone(&ctx).is_err());
+        });
+    }
+}
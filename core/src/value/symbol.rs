@@ -57,6 +57,10 @@ impl_symbols! {
     search => JS_ATOM_Symbol_search
     /// returns the symbol for `split`
     split => JS_ATOM_Symbol_split
+    /// returns the symbol for `toStringTag`
+    to_string_tag => JS_ATOM_Symbol_toStringTag
+    /// returns the symbol for `isConcatSpreadable`
+    is_concat_spreadable => JS_ATOM_Symbol_isConcatSpreadable
     /// returns the symbol for `hasInstance`
     has_instance => JS_ATOM_Symbol_hasInstance
     /// returns the symbol for `species`
@@ -71,7 +75,23 @@ impl_symbols! {
 
 #[cfg(test)]
 mod test {
-    use crate::*;
+    use crate::{function::MutFn, prelude::*, *};
+
+    fn counter<'js>(ctx: Ctx<'js>) -> Result<Object<'js>> {
+        let iter = Object::new(ctx)?;
+        let mut count = 0;
+        iter.set(
+            "next",
+            Func::from(MutFn::from(move |ctx: Ctx<'js>| {
+                count += 1;
+                let res = Object::new(ctx)?;
+                res.set("done", count > 3)?;
+                res.set("value", count)?;
+                Result::Ok(res)
+            })),
+        )?;
+        Ok(iter)
+    }
 
     #[test]
     fn description() {
@@ -83,4 +103,38 @@ mod test {
             assert_eq!(s.description().unwrap().to_string().unwrap(), "undefined");
         });
     }
+
+    #[test]
+    fn well_known() {
+        test_with(|ctx| {
+            let obj = Object::new(ctx.clone()).unwrap();
+            obj.set(Symbol::iterator(ctx.clone()), Func::from(counter))
+                .unwrap();
+            obj.set(Symbol::to_string_tag(ctx.clone()), "Counter")
+                .unwrap();
+            ctx.globals().set("obj", obj).unwrap();
+
+            let res: StdString = ctx
+                .eval("let res = []; for (const x of obj) res.push(x); res.join()")
+                .unwrap();
+            assert_eq!(res, "1,2,3");
+            let res: StdString = ctx.eval("Object.prototype.toString.call(obj)").unwrap();
+            assert_eq!(res, "[object Counter]");
+
+            let check: Function = ctx
+                .eval(
+                    "(a, b, c) => a === Symbol.asyncIterator && b === Symbol.hasInstance \
+                     && c === Symbol.toPrimitive",
+                )
+                .unwrap();
+            let res: bool = check
+                .call((
+                    Symbol::async_iterator(ctx.clone()),
+                    Symbol::has_instance(ctx.clone()),
+                    Symbol::to_primitive(ctx.clone()),
+                ))
+                .unwrap();
+            assert!(res);
+        });
+    }
 }
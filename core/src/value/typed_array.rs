@@ -14,18 +14,28 @@ use std::{
 
 use super::Constructor;
 
+mod sealed {
+    pub trait Sealed {}
+}
+
 /// The trait which implements types which capable to be TypedArray items
 ///
+/// This trait is sealed and implemented only for the element types of the javascript typed
+/// arrays.
 #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "array-buffer")))]
-pub trait TypedArrayItem: Copy {
+pub trait TypedArrayItem: sealed::Sealed + Copy {
     const CLASS_NAME: PredefinedAtom;
 }
 
 macro_rules! typedarray_items {
     ($($name:ident: $type:ty,)*) => {
-        $(impl TypedArrayItem for $type {
-            const CLASS_NAME: PredefinedAtom = PredefinedAtom::$name;
-        })*
+        $(
+            impl sealed::Sealed for $type {}
+
+            impl TypedArrayItem for $type {
+                const CLASS_NAME: PredefinedAtom = PredefinedAtom::$name;
+            }
+        )*
     };
 }
 
@@ -157,7 +167,7 @@ impl<'js, T> TypedArray<'js, T> {
         T: TypedArrayItem,
     {
         let class: Function = object.ctx.globals().get(T::CLASS_NAME)?;
-        if object.is_instance_of(class) {
+        if object.is_instance_of(class)? {
             Ok(Self(object, PhantomData))
         } else {
             Err(Error::new_from_js("object", T::CLASS_NAME.to_str()))
@@ -172,6 +182,23 @@ impl<'js, T> TypedArray<'js, T> {
         Some(unsafe { slice::from_raw_parts(ptr, len) })
     }
 
+    /// Returns the elements of the typed array as a slice.
+    ///
+    /// Returns an error if the underlying buffer is detached.
+    pub fn as_slice(&self) -> Result<&[T]>
+    where
+        T: TypedArrayItem,
+    {
+        let (len, ptr) = Self::get_raw(&self.0).ok_or_else(|| {
+            Error::new_from_js_message(
+                T::CLASS_NAME.to_str(),
+                "slice",
+                "The underlying ArrayBuffer is detached",
+            )
+        })?;
+        Ok(unsafe { slice::from_raw_parts(ptr as _, len) })
+    }
+
     /// Get underlaying ArrayBuffer
     pub fn arraybuffer(&self) -> Result<ArrayBuffer<'js>> {
         let ctx = self.ctx().clone();
@@ -377,6 +404,47 @@ mod test {
         })
     }
 
+    #[test]
+    fn round_trip_i32() {
+        test_with(|ctx| {
+            let val =
+                TypedArray::<i32>::new(ctx.clone(), &[i32::MIN, -1, 0, i32::MAX][..]).unwrap();
+            ctx.globals().set("v", val).unwrap();
+            let res: TypedArray<i32> = ctx
+                .eval(
+                    r#"
+                        v instanceof Int32Array ? v.map(x => x | 0) : null
+                    "#,
+                )
+                .unwrap();
+            assert_eq!(res.as_slice().unwrap(), &[i32::MIN, -1, 0, i32::MAX]);
+        })
+    }
+
+    #[test]
+    fn round_trip_f64() {
+        test_with(|ctx| {
+            let val = TypedArray::<f64>::new(ctx.clone(), &[0.5, -1.25, f64::MAX][..]).unwrap();
+            ctx.globals().set("v", val).unwrap();
+            let res: TypedArray<f64> = ctx
+                .eval(
+                    r#"
+                        v instanceof Float64Array ? v.map(x => x * 2) : null
+                    "#,
+                )
+                .unwrap();
+            assert_eq!(res.as_slice().unwrap(), &[1.0, -2.5, f64::INFINITY]);
+        })
+    }
+
+    #[test]
+    fn wrong_element_type() {
+        test_with(|ctx| {
+            let res: Result<TypedArray<f64>> = ctx.eval("new Int32Array(2)");
+            assert!(matches!(res, Err(Error::FromJs { .. })));
+        })
+    }
+
     #[test]
     fn as_bytes() {
         test_with(|ctx| {
@@ -0,0 +1,191 @@
+use std::fmt;
+
+use crate::{qjs, Ctx, Error, Object, Outlive, Result, Value};
+
+/// A weak reference to a javascript object.
+///
+/// A weak reference does not keep its target alive. Once the target has been collected
+/// [`WeakRef::deref`] will return `None`.
+///
+/// The bundled quickjs has no `WeakRef` class, so the reference is built on a `WeakMap`
+/// private to the weak reference, which maps the target to a private sentinel object. When the
+/// target is freed quickjs removes the entry and releases the sentinel right away, also when the
+/// target is freed by the cycle collector, so the reference count of the sentinel tells whether
+/// the target is still alive. The map is created and filled through the intrinsic `WeakMap`,
+/// scripts which replace `WeakMap` or its methods don't affect weak references.
+///
+/// The weak reference holds on to the `WeakMap`, so like other javascript values it must not
+/// outlive the runtime.
+#[derive(Clone)]
+pub struct WeakRef<'js> {
+    /// The `WeakMap`, which owns the sentinel through a property.
+    map: Object<'js>,
+    /// Referenced by the map as property and as value of the entry of the target.
+    sentinel: qjs::JSValue,
+    target: qjs::JSValue,
+}
+
+unsafe impl<'js> Outlive<'js> for WeakRef<'js> {
+    type Target<'to> = WeakRef<'to>;
+}
+
+impl<'js> WeakRef<'js> {
+    /// Create a new weak reference to an object.
+    ///
+    /// Returns an error if the target is not an object.
+    pub fn new(ctx: Ctx<'js>, target: &Value<'js>) -> Result<Self> {
+        if !target.is_object() {
+            return Err(Error::new_into_js(target.type_name(), "WeakRef"));
+        }
+        let map = unsafe {
+            let map = qjs::JS_NewMapObject(ctx.as_ptr(), qjs::JS_CLASS_WEAKMAP);
+            Object::from_js_value(ctx.clone(), ctx.handle_exception(map)?)
+        };
+        let sentinel = Object::new(ctx.clone())?;
+        let key = cstr!("sentinel");
+        unsafe {
+            // Defined instead of set, a setter on the prototype chain could keep the sentinel.
+            let res = qjs::JS_DefinePropertyValueStr(
+                ctx.as_ptr(),
+                map.as_js_value(),
+                key.as_ptr(),
+                sentinel.clone().into_js_value(),
+                0,
+            );
+            if res < 0 {
+                return Err(ctx.raise_exception());
+            }
+            let res = qjs::JS_MapSetValue(
+                ctx.as_ptr(),
+                map.as_js_value(),
+                target.as_js_value(),
+                sentinel.as_js_value(),
+            );
+            if res < 0 {
+                return Err(ctx.raise_exception());
+            }
+        }
+        Ok(WeakRef {
+            map,
+            sentinel: sentinel.as_js_value(),
+            target: target.as_js_value(),
+        })
+    }
+
+    /// Returns the target of the weak reference or `None` if the target was collected.
+    pub fn deref(&self) -> Option<Value<'js>> {
+        unsafe {
+            // One reference is held by the property of the map, the other by the entry which is
+            // removed together with the target.
+            if qjs::JS_ValueRefCount(self.sentinel) > 1 {
+                let target = qjs::JS_DupValue(self.target);
+                Some(Value::from_js_value(self.map.ctx().clone(), target))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+impl<'js> PartialEq for WeakRef<'js> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<'js> fmt::Debug for WeakRef<'js> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WeakRef")
+            .field("target", &self.deref())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn deref_after_collect() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+
+        let weak = ctx.with(|ctx| {
+            let target: Value = ctx.eval("globalThis.target = { a: 1 }").unwrap();
+            let weak = WeakRef::new(ctx.clone(), &target).unwrap();
+            assert_eq!(weak.deref(), Some(target));
+            Persistent::save(&ctx, weak)
+        });
+        ctx.with(|ctx| {
+            let weak = weak.clone().restore(&ctx).unwrap();
+            let target = weak.deref().unwrap().into_object().unwrap();
+            assert_eq!(target.get::<_, i32>("a").unwrap(), 1);
+            ctx.globals().remove("target").unwrap();
+        });
+        rt.run_gc();
+        ctx.with(|ctx| {
+            let weak = weak.restore(&ctx).unwrap();
+            assert_eq!(weak.deref(), None);
+        });
+    }
+
+    #[test]
+    fn deref_after_cycle_collected() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+
+        let weak = ctx.with(|ctx| {
+            let target: Value = ctx.eval("globalThis.target = { a: 1 }").unwrap();
+            let weak = WeakRef::new(ctx.clone(), &target).unwrap();
+            drop(target);
+            ctx.eval::<(), _>("target.self = target; delete globalThis.target")
+                .unwrap();
+            // The object is now only kept alive by its own cycle until it is collected.
+            assert!(weak.deref().is_some());
+            Persistent::save(&ctx, vec![weak.clone(), weak])
+        });
+        rt.run_gc();
+        ctx.with(|ctx| {
+            for weak in weak.restore(&ctx).unwrap() {
+                assert_eq!(weak.deref(), None);
+            }
+
+            let res = WeakRef::new(ctx.clone(), &Value::new_int(ctx.clone(), 1));
+            assert!(res.is_err());
+        });
+    }
+
+    #[test]
+    fn deref_with_replaced_weak_map() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+
+        let weak = ctx.with(|ctx| {
+            ctx.eval::<(), _>(
+                r#"
+                WeakMap.prototype.set = function (key, value) { globalThis.leak = value; return this; };
+                globalThis.WeakMap = function () { return { set() {} }; };
+                Object.defineProperty(Object.prototype, "sentinel", {
+                    set(value) { globalThis.leak = value; },
+                });
+                "#,
+            )
+            .unwrap();
+            let target: Value = ctx.eval("globalThis.target = { a: 1 }").unwrap();
+            let weak = WeakRef::new(ctx.clone(), &target).unwrap();
+            assert!(weak.deref().is_some());
+            Persistent::save(&ctx, weak)
+        });
+        ctx.with(|ctx| {
+            ctx.globals().remove("target").unwrap();
+            assert!(ctx
+                .eval::<bool, _>("globalThis.leak === undefined")
+                .unwrap());
+        });
+        rt.run_gc();
+        ctx.with(|ctx| {
+            let weak = weak.restore(&ctx).unwrap();
+            assert_eq!(weak.deref(), None);
+        });
+    }
+}
@@ -12,6 +12,8 @@ use crate::{
 #[darling(default)]
 pub(crate) struct AttrItem {
     freeze: bool,
+    /// Install a `[Symbol.iterator]` method using the `Iterable` implementation of the class.
+    iterable: bool,
     #[darling(rename = "crate")]
     crate_: Option<Ident>,
     rename_accessors: Option<Case>,
@@ -54,6 +56,12 @@ pub(crate) fn expand(attr: AttrItem, item: ItemStruct) -> TokenStream {
         quote!(#lib_crate::class::Writable)
     };
 
+    let iterable = attr.iterable.then(|| {
+        quote! {
+            <Self as #lib_crate::class::Iterable<'js>>::define_iterator(&proto)?;
+        }
+    });
+
     let prop_fields = Field::parse_fields(fields);
     let props = prop_fields
         .iter()
@@ -116,6 +124,7 @@ pub(crate) fn expand(attr: AttrItem, item: ItemStruct) -> TokenStream {
 
                     let proto = #lib_crate::Object::new(ctx.clone())?;
                     #(#props)*
+                    #iterable
                     let implementor = #lib_crate::class::impl_::MethodImpl::<Self>::new();
                     (&implementor).implement(&proto)?;
                     Ok(Some(proto))
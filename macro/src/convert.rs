@@ -0,0 +1,266 @@
+use darling::FromDeriveInput;
+use proc_macro2::{Ident, TokenStream};
+use proc_macro_error::abort;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Fields, Generics};
+
+use crate::{
+    class::add_js_lifetime,
+    common::{crate_ident, Case},
+    fields::Field,
+};
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(qjs))]
+pub(crate) struct AttrItem {
+    /// Rename all fields to the given case.
+    #[darling(default)]
+    rename_all: Option<Case>,
+    #[darling(default, rename = "crate")]
+    crate_: Option<Ident>,
+}
+
+/// Add the `'js` lifetime and require all type parameters to implement the given trait.
+fn add_bounds(generics: &Generics, bound: TokenStream) -> Generics {
+    let mut res = add_js_lifetime(generics);
+    let where_clause = res.make_where_clause();
+    for param in generics.type_params() {
+        let ident = &param.ident;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ident: #bound<'js>));
+    }
+    res
+}
+
+fn parse(input: &DeriveInput, derive: &str) -> (AttrItem, Fields) {
+    let attr = match AttrItem::from_derive_input(input) {
+        Ok(x) => x,
+        Err(e) => abort!(input, "{}", e),
+    };
+    let fields = match input.data {
+        Data::Struct(ref x) => x.fields.clone(),
+        Data::Enum(ref e) => {
+            abort!(
+                e.enum_token,
+                "deriving {} for enums is not supported",
+                derive
+            )
+        }
+        Data::Union(ref u) => {
+            abort!(
+                u.union_token,
+                "deriving {} for unions is not supported",
+                derive
+            )
+        }
+    };
+    (attr, fields)
+}
+
+pub(crate) fn expand_from_js(input: DeriveInput) -> TokenStream {
+    let (attr, fields) = parse(&input, "FromJs");
+    let lib_crate = attr.crate_.unwrap_or_else(crate_ident);
+    let DeriveInput {
+        ref ident,
+        ref generics,
+        ..
+    } = input;
+
+    let parsed_fields = Field::parse_fields(&fields);
+    let body = match fields {
+        Fields::Unnamed(_) => {
+            let fields = parsed_fields
+                .iter()
+                .enumerate()
+                .map(|(idx, f)| f.expand_from_js_element(&lib_crate, idx));
+            quote! {
+                let array = <#lib_crate::Array as #lib_crate::FromJs>::from_js(ctx, value)?;
+                Ok(Self( #(#fields),* ))
+            }
+        }
+        Fields::Named(_) | Fields::Unit => {
+            let fields = parsed_fields
+                .iter()
+                .enumerate()
+                .map(|(idx, f)| f.expand_from_js_field(&lib_crate, idx, attr.rename_all));
+            quote! {
+                let object = <#lib_crate::Object as #lib_crate::FromJs>::from_js(ctx, value)?;
+                Ok(Self{ #(#fields)* })
+            }
+        }
+    };
+
+    let lifetime_generics = add_bounds(generics, quote!(#lib_crate::FromJs));
+    let (impl_generics, _, where_clause) = lifetime_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #lib_crate::FromJs<'js> for #ident #ty_generics #where_clause{
+            fn from_js(ctx: &#lib_crate::Ctx<'js>, value: #lib_crate::Value<'js>) -> #lib_crate::Result<Self>{
+                #body
+            }
+        }
+    }
+}
+
+pub(crate) fn expand_into_js(input: DeriveInput) -> TokenStream {
+    let (attr, fields) = parse(&input, "IntoJs");
+    let lib_crate = attr.crate_.unwrap_or_else(crate_ident);
+    let DeriveInput {
+        ref ident,
+        ref generics,
+        ..
+    } = input;
+
+    let parsed_fields = Field::parse_fields(&fields);
+    let body = match fields {
+        Fields::Unnamed(_) => {
+            let fields = parsed_fields
+                .iter()
+                .enumerate()
+                .map(|(idx, f)| f.expand_into_js_element(idx));
+            quote! {
+                let array = #lib_crate::Array::new(ctx.clone())?;
+                #(#fields)*
+                Ok(array.into_value())
+            }
+        }
+        Fields::Named(_) | Fields::Unit => {
+            let fields = parsed_fields
+                .iter()
+                .enumerate()
+                .map(|(idx, f)| f.expand_into_js_field(idx, attr.rename_all));
+            quote! {
+                let object = #lib_crate::Object::new(ctx.clone())?;
+                #(#fields)*
+                Ok(object.into_value())
+            }
+        }
+    };
+
+    let lifetime_generics = add_bounds(generics, quote!(#lib_crate::IntoJs));
+    let (impl_generics, _, where_clause) = lifetime_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #lib_crate::IntoJs<'js> for #ident #ty_generics #where_clause{
+            fn into_js(self, ctx: &#lib_crate::Ctx<'js>) -> #lib_crate::Result<#lib_crate::Value<'js>>{
+                #body
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rquickjs::{Context, FromJs, IntoJs, Object, Runtime};
+
+    #[derive(Debug, PartialEq, FromJs, IntoJs)]
+    #[qjs(rename_all = "camelCase")]
+    struct User {
+        user_id: u32,
+        is_active: bool,
+        #[qjs(rename = "display")]
+        name: Option<String>,
+    }
+
+    #[derive(Debug, PartialEq, FromJs, IntoJs)]
+    struct Point(i32, i32);
+
+    #[derive(Debug, PartialEq, FromJs, IntoJs)]
+    struct Config {
+        name: String,
+        #[qjs(default)]
+        retries: u32,
+        #[qjs(skip)]
+        cache: Vec<u32>,
+    }
+
+    #[test]
+    fn round_trip_object() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        ctx.with(|ctx| {
+            let user = User {
+                user_id: 3,
+                is_active: true,
+                name: None,
+            };
+            ctx.globals().set("user", user).unwrap();
+            let res: bool = ctx
+                .eval("user.userId === 3 && user.isActive === true && !('user_id' in user)")
+                .unwrap();
+            assert!(res);
+
+            let user: User = ctx
+                .eval("({ userId: 4, isActive: false, display: 'foo' })")
+                .unwrap();
+            assert_eq!(
+                user,
+                User {
+                    user_id: 4,
+                    is_active: false,
+                    name: Some("foo".to_string()),
+                }
+            );
+
+            let res = ctx.eval::<User, _>("({ userId: 4 })");
+            assert!(res.is_err());
+
+            let object = Object::new(ctx.clone()).unwrap();
+            object.set("userId", 5).unwrap();
+            object.set("isActive", true).unwrap();
+            let user = User::from_js(&ctx, object.into_value()).unwrap();
+            assert_eq!(user.name, None);
+            let value = user.into_js(&ctx).unwrap();
+            assert_eq!(User::from_js(&ctx, value).unwrap().user_id, 5);
+        })
+    }
+
+    #[test]
+    fn default_and_skip() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        ctx.with(|ctx| {
+            let config: Config = ctx.eval("({ name: 'foo', cache: [1, 2] })").unwrap();
+            assert_eq!(
+                config,
+                Config {
+                    name: "foo".to_string(),
+                    retries: 0,
+                    cache: Vec::new(),
+                }
+            );
+            let config: Config = ctx.eval("({ name: 'foo', retries: 3 })").unwrap();
+            assert_eq!(config.retries, 3);
+            assert!(ctx.eval::<Config, _>("({ retries: 3 })").is_err());
+
+            let config = Config {
+                name: "bar".to_string(),
+                retries: 1,
+                cache: vec![1],
+            };
+            ctx.globals().set("config", config).unwrap();
+            let res: bool = ctx
+                .eval("config.retries === 1 && !('cache' in config)")
+                .unwrap();
+            assert!(res);
+        })
+    }
+
+    #[test]
+    fn round_trip_array() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        ctx.with(|ctx| {
+            ctx.globals().set("point", Point(1, 2)).unwrap();
+            let res: bool = ctx
+                .eval("Array.isArray(point) && point[0] === 1 && point[1] === 2")
+                .unwrap();
+            assert!(res);
+            let point: Point = ctx.eval("[3, 4]").unwrap();
+            assert_eq!(point, Point(3, 4));
+        })
+    }
+}
@@ -3,10 +3,36 @@ use darling::FromField;
 use proc_macro2::TokenStream;
 use proc_macro_error::abort;
 use quote::{format_ident, quote};
-use syn::{Attribute, Fields, Ident, Type, Visibility};
+use syn::{Attribute, Fields, GenericArgument, Ident, PathArguments, Type, Visibility};
 
 use crate::common::Case;
 
+/// Javascript value types which must always be traced.
+const JS_VALUE_TYPES: &[&str] = &[
+    "Value",
+    "String",
+    "Object",
+    "Array",
+    "Function",
+    "Constructor",
+    "Symbol",
+    "BigInt",
+    "Date",
+    "Exception",
+    "Promise",
+    "RegExp",
+    "Map",
+    "Set",
+    "Proxy",
+    "Module",
+    "ArrayBuffer",
+    "TypedArray",
+    "WeakRef",
+    "Class",
+    "Atom",
+    "Ctx",
+];
+
 #[derive(Debug, FromField)]
 #[darling(attributes(qjs))]
 pub(crate) struct Field {
@@ -28,6 +54,12 @@ pub(crate) struct Field {
     /// Don't trace this field
     #[darling(default)]
     skip_trace: bool,
+    /// Use the default value when converting from a javascript value without this field.
+    #[darling(default)]
+    default: bool,
+    /// Don't convert this field, the default value is used when converting from javascript.
+    #[darling(default)]
+    skip: bool,
     ident: Option<Ident>,
     vis: Visibility,
     ty: Type,
@@ -104,6 +136,115 @@ impl Field {
         }
     }
 
+    /// Abort if the field is marked with `skip_trace` while its type is clearly a javascript value.
+    ///
+    /// Only direct uses of the javascript value types of the library are detected, other types
+    /// are trusted to not contain javascript values.
+    pub fn check_skip_trace(&self) {
+        if !self.skip_trace {
+            return;
+        }
+        let Type::Path(ref path) = self.ty else {
+            return;
+        };
+        let Some(segment) = path.path.segments.last() else {
+            return;
+        };
+        let PathArguments::AngleBracketed(ref args) = segment.arguments else {
+            return;
+        };
+        let has_lifetime = args
+            .args
+            .iter()
+            .any(|x| matches!(x, GenericArgument::Lifetime(_)));
+        if has_lifetime && JS_VALUE_TYPES.iter().any(|x| segment.ident == x) {
+            abort!(
+                self.ty,
+                "field of type `{}` is a javascript value and must be traced",
+                segment.ident
+            );
+        }
+    }
+
+    /// Returns the identifier the field is bound to when matching on an enum variant, `None`
+    /// if the field is not traced.
+    pub fn trace_binding(&self, which: usize) -> Option<Ident> {
+        if self.skip_trace {
+            return None;
+        }
+        Some(
+            self.ident
+                .clone()
+                .unwrap_or_else(|| format_ident!("_{}", which)),
+        )
+    }
+
+    /// Expand the conversion of a property into the value of the field.
+    fn expand_from_js_value(&self, lib_crate: &Ident, get: TokenStream) -> TokenStream {
+        if self.skip {
+            return quote!(::std::default::Default::default());
+        }
+        if self.default {
+            return quote! {{
+                let value: #lib_crate::Value = #get?;
+                if value.is_undefined() {
+                    ::std::default::Default::default()
+                } else {
+                    #lib_crate::FromJs::from_js(ctx, value)?
+                }
+            }};
+        }
+        quote!(#get?)
+    }
+
+    pub fn expand_from_js_field(
+        &self,
+        lib_crate: &Ident,
+        which: usize,
+        case: Option<Case>,
+    ) -> TokenStream {
+        let field = self
+            .ident
+            .clone()
+            .unwrap_or_else(|| format_ident!("{}", which));
+        let name = self.name(which, case);
+        let value = self.expand_from_js_value(lib_crate, quote!(object.get(#name)));
+        quote! {
+            #field: #value,
+        }
+    }
+
+    pub fn expand_from_js_element(&self, lib_crate: &Ident, which: usize) -> TokenStream {
+        self.expand_from_js_value(lib_crate, quote!(array.get(#which)))
+    }
+
+    pub fn expand_into_js_field(&self, which: usize, case: Option<Case>) -> TokenStream {
+        if self.skip {
+            return TokenStream::new();
+        }
+        let field = self
+            .ident
+            .clone()
+            .unwrap_or_else(|| format_ident!("{}", which));
+        let name = self.name(which, case);
+        quote! {
+            object.set(#name, self.#field)?;
+        }
+    }
+
+    /// Expand setting the element of the array for a tuple struct field.
+    ///
+    /// Skipped fields leave a hole so the other fields keep their index.
+    pub fn expand_into_js_element(&self, which: usize) -> TokenStream {
+        if self.skip {
+            return TokenStream::new();
+        }
+        let field = syn::Index::from(which);
+        quote! {
+            array.set(#which, self.#field)?;
+        }
+    }
+
     pub fn expand_property(
         &self,
         lib_crate: &Ident,
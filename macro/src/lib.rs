@@ -6,6 +6,7 @@ use syn::{parse_macro_input, DeriveInput, Item};
 
 mod class;
 mod common;
+mod convert;
 mod fields;
 mod function;
 mod method;
@@ -58,6 +59,13 @@ pub fn function(attr: TokenStream1, item: TokenStream1) -> TokenStream1 {
     }
 }
 
+/// Define the methods of a class created with the [`class`](macro@class) macro.
+///
+/// Functions marked with `#[qjs(static)]` are defined on the constructor instead of the
+/// prototype, this can be combined with `#[qjs(get)]` and `#[qjs(set)]` to define static
+/// accessors. Static functions are called without an instance so they can't take `self`, the
+/// `this` value they are called with is the constructor itself. Static members require the class
+/// to have a `#[qjs(constructor)]`.
 #[proc_macro_attribute]
 #[proc_macro_error]
 pub fn methods(attr: TokenStream1, item: TokenStream1) -> TokenStream1 {
@@ -106,3 +114,17 @@ pub fn trace(stream: TokenStream1) -> TokenStream1 {
     let derive_input = parse_macro_input!(stream as DeriveInput);
     trace::expand(derive_input).into()
 }
+
+#[proc_macro_derive(FromJs, attributes(qjs))]
+#[proc_macro_error]
+pub fn from_js(stream: TokenStream1) -> TokenStream1 {
+    let derive_input = parse_macro_input!(stream as DeriveInput);
+    convert::expand_from_js(derive_input).into()
+}
+
+#[proc_macro_derive(IntoJs, attributes(qjs))]
+#[proc_macro_error]
+pub fn into_js(stream: TokenStream1) -> TokenStream1 {
+    let derive_input = parse_macro_input!(stream as DeriveInput);
+    convert::expand_into_js(derive_input).into()
+}
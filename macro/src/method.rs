@@ -2,10 +2,12 @@ use std::collections::HashMap;
 
 use convert_case::Casing;
 use darling::{FromAttributes, FromMeta};
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
 use proc_macro_error::{abort, emit_warning};
 use quote::{format_ident, quote};
-use syn::{spanned::Spanned, Attribute, Block, ImplItemFn, ItemImpl, Signature, Type, Visibility};
+use syn::{
+    spanned::Spanned, Attribute, Block, ImplItemFn, ItemImpl, Meta, Signature, Type, Visibility,
+};
 
 use crate::{
     class::add_js_lifetime,
@@ -39,6 +41,35 @@ pub(crate) struct MethodAttr {
     rename: Option<String>,
 }
 
+/// Turn keywords in `qjs` attributes into raw identifiers.
+///
+/// Attribute arguments are parsed as paths which can't contain keywords, this allows writing
+/// `#[qjs(static)]` instead of `#[qjs(r#static)]`.
+fn raw_keywords(attrs: &[Attribute]) -> Vec<Attribute> {
+    attrs
+        .iter()
+        .map(|attr| {
+            let mut attr = attr.clone();
+            if let Meta::List(ref mut list) = attr.meta {
+                if list.path.is_ident("qjs") {
+                    list.tokens = list
+                        .tokens
+                        .clone()
+                        .into_iter()
+                        .map(|token| match token {
+                            TokenTree::Ident(ident) if ident == "static" => {
+                                TokenTree::Ident(Ident::new_raw("static", ident.span()))
+                            }
+                            token => token,
+                        })
+                        .collect();
+                }
+            }
+            attr
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct JsMethod {
     pub attr_span: Span,
@@ -60,7 +91,7 @@ impl JsMethod {
             sig,
             block,
         } = func;
-        let parse_attrs = match MethodAttr::from_attributes(&attrs) {
+        let parse_attrs = match MethodAttr::from_attributes(&raw_keywords(&attrs)) {
             Ok(x) => x,
             Err(e) => {
                 abort!(span, "{}", e);
@@ -130,11 +161,19 @@ impl JsMethod {
     }
 
     /// The name on of this method on the javascript side.
+    ///
+    /// Setters are matched with their getter by this name, so a `set_` prefix is removed from the
+    /// name of setters.
     pub fn name(&self, case: Option<Case>) -> String {
         if let Some(x) = self.parse_attrs.rename.clone() {
             x
         } else {
-            let res = self.function.name.to_string();
+            let mut res = self.function.name.to_string();
+            if self.parse_attrs.set {
+                if let Some(name) = res.strip_prefix("set_") {
+                    res = name.to_string();
+                }
+            }
             if let Some(case) = case {
                 res.to_case(case.to_convert_case())
             } else {
@@ -243,7 +282,12 @@ impl Accessor {
         res
     }
 
-    fn expand_apply_to_proto(&self, lib_crate: &Ident, case: Option<Case>) -> TokenStream {
+    fn expand_apply_to_object(
+        &self,
+        lib_crate: &Ident,
+        object_name: &Ident,
+        case: Option<Case>,
+    ) -> TokenStream {
         let get_common = Common {
             prefix: "__impl_get_".to_string(),
             lib_crate: lib_crate.clone(),
@@ -269,7 +313,7 @@ impl Accessor {
 
                 let get_name = get.function.expand_carry_type_name(&get_common);
                 let set_name = set.function.expand_carry_type_name(&set_common);
-                quote! {_proto.prop(#name,
+                quote! {#object_name.prop(#name,
                         #lib_crate::object::Accessor::new(#get_name,#set_name)
                         #configurable
                         #enumerable
@@ -289,7 +333,7 @@ impl Accessor {
                     .unwrap_or_default();
 
                 let get_name = get.function.expand_carry_type_name(&get_common);
-                quote! {_proto.prop(#name,
+                quote! {#object_name.prop(#name,
                         #lib_crate::object::Accessor::new_get(#get_name)
                         #configurable
                         #enumerable
@@ -309,7 +353,7 @@ impl Accessor {
                     .unwrap_or_default();
 
                 let set_name = set.function.expand_carry_type_name(&set_common);
-                quote! {_proto.prop(#name,
+                quote! {#object_name.prop(#name,
                         #lib_crate::object::Accessor::new_set(#set_name)
                         #configurable
                         #enumerable
@@ -353,6 +397,7 @@ pub(crate) fn expand(attr: AttrItem, item: ItemImpl) -> TokenStream {
     };
 
     let mut accessors = HashMap::new();
+    let mut static_accessors = HashMap::new();
     let mut functions = Vec::new();
     let mut constructor: Option<JsMethod> = None;
     //let mut consts = Vec::new();
@@ -362,8 +407,13 @@ pub(crate) fn expand(attr: AttrItem, item: ItemImpl) -> TokenStream {
             syn::ImplItem::Const(_item) => {}
             syn::ImplItem::Fn(item) => {
                 let function = JsMethod::parse_impl_fn(item, &self_ty);
+                let accessor_map = if function.parse_attrs.r#static {
+                    &mut static_accessors
+                } else {
+                    &mut accessors
+                };
                 if function.parse_attrs.get {
-                    let access = accessors
+                    let access = accessor_map
                         .entry(function.name(attr.rename_accessors))
                         .or_insert(Accessor {
                             get: None,
@@ -378,10 +428,12 @@ pub(crate) fn expand(attr: AttrItem, item: ItemImpl) -> TokenStream {
                     }
                     access.get = Some(function);
                 } else if function.parse_attrs.set {
-                    let access = accessors.entry(function.name(None)).or_insert(Accessor {
-                        get: None,
-                        set: None,
-                    });
+                    let access = accessor_map
+                        .entry(function.name(attr.rename_accessors))
+                        .or_insert(Accessor {
+                            get: None,
+                            set: None,
+                        });
                     if let Some(first) = access.set.take() {
                         let first_span = first.attr_span;
                         emit_warning!(
@@ -414,7 +466,10 @@ pub(crate) fn expand(attr: AttrItem, item: ItemImpl) -> TokenStream {
     };
 
     let function_impls = functions.iter().map(|func| func.expand_impl());
-    let accessor_impls = accessors.values().map(|access| access.expand_impl());
+    let accessor_impls = accessors
+        .values()
+        .chain(static_accessors.values())
+        .map(|access| access.expand_impl());
     let constructor_impl = constructor.as_ref().map(|constr| constr.expand_impl());
 
     let function_js_impls = functions
@@ -422,6 +477,7 @@ pub(crate) fn expand(attr: AttrItem, item: ItemImpl) -> TokenStream {
         .map(|func| func.expand_js_impl(&func_common));
     let accessor_js_impls = accessors
         .values()
+        .chain(static_accessors.values())
         .map(|access| access.expand_js_impl(&common));
     let constructor_js_impl = constructor
         .as_ref()
@@ -439,9 +495,9 @@ pub(crate) fn expand(attr: AttrItem, item: ItemImpl) -> TokenStream {
             func.expand_apply_to_object(&common, &self_ty, &proto_ident, attr.rename_methods)
         })
     });
-    let accessor_apply_proto = accessors
-        .values()
-        .map(|access| access.expand_apply_to_proto(&common.lib_crate, attr.rename_accessors));
+    let accessor_apply_proto = accessors.values().map(|access| {
+        access.expand_apply_to_object(&common.lib_crate, &proto_ident, attr.rename_accessors)
+    });
 
     let constructor_ident = format_ident!("constr");
 
@@ -461,11 +517,20 @@ pub(crate) fn expand(attr: AttrItem, item: ItemImpl) -> TokenStream {
             })
         });
 
+        let static_accessor_apply = static_accessors.values().map(|access| {
+            access.expand_apply_to_object(
+                &common.lib_crate,
+                &constructor_ident,
+                attr.rename_accessors,
+            )
+        });
+
         quote! {
             impl #js_added_generics #lib_crate::class::impl_::ConstructorCreator<'js,#self_ty> for #lib_crate::class::impl_::ConstructorCreate<#self_ty> {
                 fn create_constructor(&self, ctx: &#lib_crate::Ctx<'js>) -> #lib_crate::Result<Option<#lib_crate::function::Constructor<'js>>>{
                     let constr = #lib_crate::function::Constructor::new_class::<#self_ty,_,_>(ctx.clone(),#name)?;
                     #(#static_function_apply)*
+                    #(#static_accessor_apply)*
                     Ok(Some(constr))
                 }
             }
@@ -475,6 +540,13 @@ pub(crate) fn expand(attr: AttrItem, item: ItemImpl) -> TokenStream {
             abort!(x.attr_span,"Defined a static method on an class without a constructor"; 
                 hint = "Static methods are defined on constructors");
         }
+        if let Some(x) = static_accessors
+            .values()
+            .find_map(|x| x.get.as_ref().or(x.set.as_ref()))
+        {
+            abort!(x.attr_span,"Defined a static accessor on an class without a constructor";
+                hint = "Static accessors are defined on constructors");
+        }
 
         TokenStream::new()
     };
@@ -507,3 +579,143 @@ pub(crate) fn expand(attr: AttrItem, item: ItemImpl) -> TokenStream {
         #constructor_create
     }
 }
+
+#[cfg(test)]
+mod test {
+    mod static_members {
+        use rquickjs::{class::Trace, Context, Runtime};
+
+        #[derive(Trace)]
+        #[rquickjs::class]
+        pub struct Counter {
+            #[qjs(skip_trace)]
+            count: i32,
+        }
+
+        #[rquickjs::methods]
+        impl Counter {
+            #[qjs(constructor)]
+            pub fn new(count: i32) -> Self {
+                Counter { count }
+            }
+
+            pub fn get(&self) -> i32 {
+                self.count
+            }
+
+            #[qjs(static)]
+            pub fn create() -> Self {
+                Counter { count: 10 }
+            }
+
+            #[qjs(static, get)]
+            pub fn max() -> i32 {
+                100
+            }
+        }
+
+        #[test]
+        fn static_members() {
+            let rt = Runtime::new().unwrap();
+            let ctx = Context::full(&rt).unwrap();
+            ctx.with(|ctx| {
+                rquickjs::Class::<Counter>::define(&ctx.globals()).unwrap();
+
+                let res: i32 = ctx.eval("Counter.create().get()").unwrap();
+                assert_eq!(res, 10);
+                let res: i32 = ctx.eval("Counter.max").unwrap();
+                assert_eq!(res, 100);
+                let res: bool = ctx
+                    .eval("new Counter(1).max === undefined && new Counter(1).create === undefined")
+                    .unwrap();
+                assert!(res);
+            })
+        }
+    }
+
+    mod accessors {
+        use rquickjs::{class::Trace, Context, Runtime};
+
+        #[derive(Trace)]
+        #[rquickjs::class]
+        pub struct Thermometer {
+            #[qjs(skip_trace)]
+            celsius: f64,
+        }
+
+        #[rquickjs::methods]
+        impl Thermometer {
+            #[qjs(constructor)]
+            pub fn new() -> Self {
+                Thermometer { celsius: 20.0 }
+            }
+
+            #[qjs(get)]
+            pub fn temperature(&self) -> f64 {
+                self.celsius
+            }
+
+            #[qjs(set)]
+            pub fn set_temperature(&mut self, celsius: f64) -> rquickjs::Result<()> {
+                if celsius < -273.15 {
+                    return Err(rquickjs::Error::new_from_js_message(
+                        "number",
+                        "temperature",
+                        "below absolute zero",
+                    ));
+                }
+                self.celsius = celsius;
+                Ok(())
+            }
+
+            #[qjs(get)]
+            pub fn kelvin(&self) -> f64 {
+                self.celsius + 273.15
+            }
+        }
+
+        #[test]
+        fn accessors() {
+            let rt = Runtime::new().unwrap();
+            let ctx = Context::full(&rt).unwrap();
+            ctx.with(|ctx| {
+                rquickjs::Class::<Thermometer>::define(&ctx.globals()).unwrap();
+                ctx.eval::<(), _>("globalThis.t = new Thermometer(); t.temperature = 30;")
+                    .unwrap();
+                let res: f64 = ctx.eval("t.temperature").unwrap();
+                assert_eq!(res, 30.0);
+
+                let res: bool = ctx
+                    .eval(
+                        r#"
+                        try {
+                            t.temperature = -300;
+                            false
+                        } catch (e) {
+                            e.message.includes("below absolute zero") && t.temperature === 30
+                        }
+                        "#,
+                    )
+                    .unwrap();
+                assert!(res);
+
+                let res: bool = ctx
+                    .eval(
+                        r#"
+                        (() => {
+                            "use strict";
+                            try {
+                                t.kelvin = 0;
+                                return false;
+                            } catch (e) {
+                                return e instanceof TypeError && t.kelvin > 303;
+                            }
+                        })()
+                        "#,
+                    )
+                    .unwrap();
+                assert!(res);
+            })
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use proc_macro2::TokenStream;
 use proc_macro_error::abort;
 use quote::quote;
-use syn::{Data, DataStruct, DeriveInput};
+use syn::{parse_quote, Data, DataEnum, DataStruct, DeriveInput, Fields, Generics, Ident};
 
 use crate::{class::add_js_lifetime, common::crate_ident, fields::Field};
 
@@ -13,35 +13,240 @@ pub(crate) fn expand(input: DeriveInput) -> TokenStream {
         ..
     } = input;
 
-    let struct_ = match data {
-        Data::Struct(x) => x,
-        Data::Enum(e) => {
-            abort!(
-                e.enum_token,
-                "implementing trace for enums is not yet supported"
-            )
-        }
+    let lib_crate = crate_ident();
+
+    let body = match data {
+        Data::Struct(x) => expand_struct(&lib_crate, x),
+        Data::Enum(x) => expand_enum(&lib_crate, x),
         Data::Union(u) => {
             abort!(u.union_token, "deriving trace for unions is not supported");
         }
     };
 
-    let DataStruct { fields, .. } = struct_;
+    let lifetime_generics = add_trace_bounds(&lib_crate, &generics);
+    let (impl_generics, _, where_clause) = lifetime_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
 
-    let lifetime_generics = add_js_lifetime(&generics);
-    let lib_crate = crate_ident();
+    quote! {
+        impl #impl_generics #lib_crate::class::Trace<'js> for #ident #ty_generics #where_clause{
+            fn trace<'a>(&self, _tracer: #lib_crate::class::Tracer<'a,'js>){
+                #body
+            }
+        }
+    }
+}
+
+/// Add the `'js` lifetime and require all type parameters to implement trace.
+fn add_trace_bounds(lib_crate: &Ident, generics: &Generics) -> Generics {
+    let mut res = add_js_lifetime(generics);
+    let where_clause = res.make_where_clause();
+    for param in generics.type_params() {
+        let ident = &param.ident;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ident: #lib_crate::class::Trace<'js>));
+    }
+    res
+}
+
+fn expand_struct(lib_crate: &Ident, struct_: DataStruct) -> TokenStream {
+    let DataStruct { fields, .. } = struct_;
 
     let parsed_fields = Field::parse_fields(&fields);
+    parsed_fields.iter().for_each(Field::check_skip_trace);
     let trace_impls = parsed_fields
         .iter()
         .enumerate()
-        .map(|(idx, f)| f.expand_trace_body(&lib_crate, idx));
+        .map(|(idx, f)| f.expand_trace_body(lib_crate, idx));
 
     quote! {
-        impl #lifetime_generics #lib_crate::class::Trace<'js> for #ident #generics{
-            fn trace<'a>(&self, _tracer: #lib_crate::class::Tracer<'a,'js>){
+        #(#trace_impls)*
+    }
+}
+
+fn expand_enum(lib_crate: &Ident, enum_: DataEnum) -> TokenStream {
+    let DataEnum { variants, .. } = enum_;
+
+    if variants.is_empty() {
+        return TokenStream::new();
+    }
+
+    let arms = variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let parsed_fields = Field::parse_fields(&variant.fields);
+        parsed_fields.iter().for_each(Field::check_skip_trace);
+        let bindings: Vec<_> = parsed_fields
+            .iter()
+            .enumerate()
+            .map(|(idx, f)| f.trace_binding(idx))
+            .collect();
+
+        let pattern = match variant.fields {
+            Fields::Named(_) => {
+                let bindings = bindings.iter().flatten();
+                quote!(Self::#ident{ #(#bindings,)* .. })
+            }
+            Fields::Unnamed(_) => {
+                let bindings = bindings.iter().map(|x| match x {
+                    Some(x) => quote!(#x),
+                    None => quote!(_),
+                });
+                quote!(Self::#ident( #(#bindings),* ))
+            }
+            Fields::Unit => quote!(Self::#ident),
+        };
+
+        let trace_impls = bindings.iter().flatten().map(|binding| {
+            quote! {
+                #lib_crate::class::Trace::<'js>::trace(#binding,_tracer);
+            }
+        });
+
+        quote! {
+            #pattern => {
                 #(#trace_impls)*
             }
         }
+    });
+
+    quote! {
+        match self {
+            #(#arms)*
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rquickjs::{
+        class::{ClassId, JsClass, Trace, Writable},
+        Class, Context, Object, Runtime,
+    };
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+    use syn::parse_quote;
+
+    #[test]
+    fn expand_generic_enum() {
+        let res = super::expand(parse_quote! {
+            enum Node<'js, T> {
+                Leaf(T),
+                Branch(Box<Node<'js, T>>, Object<'js>),
+                Named { value: T, #[qjs(skip_trace)] skipped: u32 },
+                Empty,
+            }
+        })
+        .to_string()
+        .replace(' ', "");
+
+        assert!(res.contains("whereT:rquickjs::class::Trace<'js>"), "{res}");
+        assert!(res.contains("Self::Leaf(_0)=>"), "{res}");
+        assert!(res.contains("Self::Branch(_0,_1)=>"), "{res}");
+        assert!(res.contains("Self::Named{value,..}=>"), "{res}");
+        assert!(res.contains("Self::Empty=>{}"), "{res}");
+    }
+
+    #[test]
+    fn expand_skip_trace() {
+        let res = super::expand(parse_quote! {
+            struct Data<'js> {
+                object: Object<'js>,
+                #[qjs(skip_trace)]
+                name: String,
+                #[qjs(skip_trace)]
+                shared: Rc<RefCell<u64>>,
+            }
+        })
+        .to_string()
+        .replace(' ', "");
+
+        assert!(res.contains("trace(&self.object,_tracer)"), "{res}");
+        assert!(!res.contains("self.name"), "{res}");
+        assert!(!res.contains("self.shared"), "{res}");
+    }
+
+    #[derive(Trace)]
+    enum Node<'js, T> {
+        Leaf(T),
+        Branch(Box<Node<'js, T>>, Object<'js>),
+        Named {
+            value: T,
+            #[qjs(skip_trace)]
+            _skipped: u32,
+        },
+        Empty,
+    }
+
+    struct Holder<'js> {
+        node: Node<'js, i32>,
+        dropped: Arc<AtomicBool>,
+    }
+
+    impl<'js> Drop for Holder<'js> {
+        fn drop(&mut self) {
+            self.dropped.store(true, Ordering::SeqCst);
+        }
+    }
+
+    impl<'js> Trace<'js> for Holder<'js> {
+        fn trace<'a>(&self, tracer: rquickjs::class::Tracer<'a, 'js>) {
+            self.node.trace(tracer)
+        }
+    }
+
+    impl<'js> JsClass<'js> for Holder<'js> {
+        const NAME: &'static str = "Holder";
+
+        type Mutable = Writable;
+
+        fn class_id() -> &'static ClassId {
+            static ID: ClassId = ClassId::new();
+            &ID
+        }
+
+        fn prototype(ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<Option<Object<'js>>> {
+            Ok(Some(Object::new(ctx.clone())?))
+        }
+
+        fn constructor(
+            _ctx: &rquickjs::Ctx<'js>,
+        ) -> rquickjs::Result<Option<rquickjs::function::Constructor<'js>>> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn collect_cyclic_enum() {
+        let rt = Runtime::new().unwrap();
+        let ctx = Context::full(&rt).unwrap();
+        let dropped = Arc::new(AtomicBool::new(false));
+
+        ctx.with(|ctx| {
+            let obj = Object::new(ctx.clone()).unwrap();
+            let node = Node::Branch(
+                Box::new(Node::Named {
+                    value: 1,
+                    _skipped: 0,
+                }),
+                obj.clone(),
+            );
+            let holder = Class::instance(
+                ctx.clone(),
+                Holder {
+                    node,
+                    dropped: dropped.clone(),
+                },
+            )
+            .unwrap();
+            obj.set("holder", holder).unwrap();
+
+            let _: Node<i32> = Node::Leaf(0);
+            let _: Node<i32> = Node::Empty;
+        });
+
+        rt.run_gc();
+        assert!(dropped.load(Ordering::SeqCst));
     }
 }
@@ -0,0 +1,6 @@
+#[cfg(feature = "compile-tests")]
+#[test]
+fn compile_test() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+}
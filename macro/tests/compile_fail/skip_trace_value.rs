@@ -0,0 +1,9 @@
+use rquickjs::{class::Trace, Object};
+
+#[derive(Trace)]
+struct Data<'js> {
+    #[qjs(skip_trace)]
+    object: Object<'js>,
+}
+
+fn main() {}
@@ -85,6 +85,9 @@
 //! - `indexmap` adds [`FromJs`]/[`IntoJs`] implementations for [`IndexSet`](`indexmap::IndexSet`)
 //! and [`IndexMap`](`indexmap_rs::IndexMap`)
 //!
+//! - `serde` adds the [`serde`] module for converting any type implementing the serde traits to
+//! and from javascript values
+//!
 //! ## Bindings
 //!
 //! The bindings is pre-built for the following platforms:
@@ -127,7 +130,7 @@ pub use rquickjs_core::*;
 
 #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "macro")))]
 #[cfg(feature = "macro")]
-pub use rquickjs_macro::{class, function, methods};
+pub use rquickjs_macro::{class, function, methods, FromJs, IntoJs};
 
 pub mod class {
     //! Javascript classes defined from rust.
@@ -64,10 +64,12 @@ fn main() {
         "check_stack_overflow.patch",
         "infinity_handling.patch",
         "atomic_new_class_id.patch",
+        "intrinsics.patch",
     ];
 
     let mut defines = vec![
         ("_GNU_SOURCE".into(), None),
+        // Keep in sync with `QUICKJS_VERSION` in src/lib.rs
         ("CONFIG_VERSION".into(), Some("\"2020-01-19\"")),
         ("CONFIG_BIGNUM".into(), None),
     ];
@@ -1602,6 +1602,99 @@ extern "C" {
 extern "C" {
     pub fn JS_GetFunctionProto(ctx: *mut JSContext) -> JSValue;
 }
+extern "C" {
+    pub fn JS_NewMapObject(ctx: *mut JSContext, class_id: JSClassID) -> JSValue;
+}
+extern "C" {
+    pub fn JS_MapSetValue(
+        ctx: *mut JSContext,
+        obj: JSValue,
+        key: JSValue,
+        value: JSValue,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn JS_GetClassID(v: JSValue) -> JSClassID;
+}
+extern "C" {
+    pub fn JS_NewDate(ctx: *mut JSContext, epoch_ms: f64) -> JSValue;
+}
+extern "C" {
+    pub fn JS_GetDateValue(ctx: *mut JSContext, obj: JSValue, pres: *mut f64)
+        -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn JS_GetMapEntries(ctx: *mut JSContext, obj: JSValue) -> JSValue;
+}
+extern "C" {
+    pub fn JS_NewRegExpCopy(ctx: *mut JSContext, obj: JSValue) -> JSValue;
+}
+extern "C" {
+    pub fn JS_GetNativeErrorKind(ctx: *mut JSContext, obj: JSValue) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn JS_NewNativeError(ctx: *mut JSContext, kind: ::std::os::raw::c_int) -> JSValue;
+}
+extern "C" {
+    pub fn JS_GetArrayBufferView(
+        ctx: *mut JSContext,
+        obj: JSValue,
+        pbyte_offset: *mut size_t,
+        pbyte_length: *mut size_t,
+        pbytes_per_element: *mut size_t,
+    ) -> JSValue;
+}
+extern "C" {
+    pub fn JS_NewArrayBufferView(
+        ctx: *mut JSContext,
+        class_id: JSClassID,
+        buffer: JSValue,
+        byte_offset: size_t,
+        byte_length: size_t,
+    ) -> JSValue;
+}
+extern "C" {
+    pub fn JS_MapGetValue(
+        ctx: *mut JSContext,
+        obj: JSValue,
+        key: JSValue,
+        pvalue: *mut JSValue,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn JS_SealObject(
+        ctx: *mut JSContext,
+        obj: JSValue,
+        freeze: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn JS_IsSealed(
+        ctx: *mut JSContext,
+        obj: JSValue,
+        is_frozen: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn JS_BindFunction(
+        ctx: *mut JSContext,
+        func: JSValue,
+        this_val: JSValue,
+        argc: ::std::os::raw::c_int,
+        argv: *mut JSValue,
+    ) -> JSValue;
+}
+extern "C" {
+    pub fn JS_CallWithStackFrame(
+        ctx: *mut JSContext,
+        func_obj: JSValue,
+        this_obj: JSValue,
+        argc: ::std::os::raw::c_int,
+        argv: *mut JSValue,
+        flags: ::std::os::raw::c_int,
+        call: JSClassCall,
+    ) -> JSValue;
+}
 extern "C" {
     pub fn JS_IsInstanceOf(
         ctx: *mut JSContext,
@@ -2641,6 +2734,9 @@ extern "C" {
         idx: ::std::os::raw::c_int,
     ) -> JSAtom;
 }
+extern "C" {
+    pub fn JS_GetModuleNamespace(ctx: *mut JSContext, m: *mut JSModuleDef) -> JSValue;
+}
 pub const __JS_ATOM_NULL: _bindgen_ty_2 = 0;
 pub const JS_ATOM_null: _bindgen_ty_2 = 1;
 pub const JS_ATOM_false: _bindgen_ty_2 = 2;
@@ -11,6 +11,50 @@ use std::ptr;
 /// Common error message for converting between c 'size_t' and rust 'usize';
 pub const SIZE_T_ERROR: &str = "c type 'size_t' didnt fit into rust type 'usize'";
 
+/// The version of quickjs, as defined by `CONFIG_VERSION` in the build script.
+pub const QUICKJS_VERSION: &str = "2020-01-19";
+
+/// The version of this crate, which pins the exact quickjs sources which are bundled.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Class ids of builtin classes, as assigned by the class enum of the bundled `quickjs.c` when
+// compiled with `CONFIG_BIGNUM`.
+pub const JS_CLASS_OBJECT: JSClassID = 1;
+pub const JS_CLASS_ARRAY: JSClassID = 2;
+pub const JS_CLASS_ERROR: JSClassID = 3;
+pub const JS_CLASS_DATE: JSClassID = 10;
+pub const JS_CLASS_REGEXP: JSClassID = 18;
+pub const JS_CLASS_ARRAY_BUFFER: JSClassID = 19;
+pub const JS_CLASS_SHARED_ARRAY_BUFFER: JSClassID = 20;
+pub const JS_CLASS_UINT8C_ARRAY: JSClassID = 21;
+pub const JS_CLASS_INT8_ARRAY: JSClassID = 22;
+pub const JS_CLASS_UINT8_ARRAY: JSClassID = 23;
+pub const JS_CLASS_INT16_ARRAY: JSClassID = 24;
+pub const JS_CLASS_UINT16_ARRAY: JSClassID = 25;
+pub const JS_CLASS_INT32_ARRAY: JSClassID = 26;
+pub const JS_CLASS_UINT32_ARRAY: JSClassID = 27;
+pub const JS_CLASS_BIG_INT64_ARRAY: JSClassID = 28;
+pub const JS_CLASS_BIG_UINT64_ARRAY: JSClassID = 29;
+pub const JS_CLASS_FLOAT32_ARRAY: JSClassID = 30;
+pub const JS_CLASS_FLOAT64_ARRAY: JSClassID = 31;
+pub const JS_CLASS_DATAVIEW: JSClassID = 32;
+pub const JS_CLASS_MAP: JSClassID = 38;
+pub const JS_CLASS_SET: JSClassID = 39;
+pub const JS_CLASS_WEAKMAP: JSClassID = 40;
+pub const JS_CLASS_WEAKSET: JSClassID = 41;
+pub const JS_CLASS_PROMISE: JSClassID = 49;
+
+// Kinds of native errors for `JS_NewNativeError`, as assigned by the error enum of the bundled
+// `quickjs.c`.
+pub const JS_EVAL_ERROR: c_int = 0;
+pub const JS_RANGE_ERROR: c_int = 1;
+pub const JS_REFERENCE_ERROR: c_int = 2;
+pub const JS_SYNTAX_ERROR: c_int = 3;
+pub const JS_TYPE_ERROR: c_int = 4;
+pub const JS_URI_ERROR: c_int = 5;
+pub const JS_INTERNAL_ERROR: c_int = 6;
+pub const JS_AGGREGATE_ERROR: c_int = 7;
+
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
 #[cfg(not(feature = "bindgen"))]